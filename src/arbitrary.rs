@@ -0,0 +1,166 @@
+//! `proptest` strategies producing well-formed requests and built-in matchers, for
+//! property tests such as "a matcher captured from a request matches it".
+
+use crate::body::Body;
+use crate::matchers::Matcher;
+use crate::request::{HttpVersion, Request};
+use proptest::prelude::*;
+
+fn token() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9]{0,7}"
+}
+
+fn header_name() -> impl Strategy<Value = String> {
+    "X-[A-Z][a-z]{1,8}"
+}
+
+fn header_value() -> impl Strategy<Value = String> {
+    "[!-~]([ -~]{0,14}[!-~])?"
+}
+
+fn method() -> impl Strategy<Value = String> {
+    prop::sample::select(vec![
+        "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS",
+    ])
+    .prop_map(String::from)
+}
+
+fn path() -> impl Strategy<Value = String> {
+    prop::collection::vec(token(), 0..4).prop_map(|segments| format!("/{}", segments.join("/")))
+}
+
+fn body() -> impl Strategy<Value = Body> {
+    prop_oneof![
+        Just(Body::Empty),
+        "[ -~]{1,32}".prop_map(Body::from),
+        prop::collection::vec(any::<u8>(), 1..32).prop_map(Body::from),
+    ]
+}
+
+impl Arbitrary for HttpVersion {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        prop::sample::select(vec![
+            HttpVersion::Http10,
+            HttpVersion::Http11,
+            HttpVersion::Http2,
+            HttpVersion::Http3,
+        ])
+        .boxed()
+    }
+}
+
+/// Requests with a token-like path, query, fragment and `X-` headers, so every generated
+/// request survives a round trip through its text forms.
+impl Arbitrary for Request {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let authority = prop::option::of((
+            prop::sample::select(vec!["http", "https"]),
+            "[a-z]{1,8}\\.test",
+            prop::option::of(1u16..),
+        ));
+        let query = prop::collection::vec((token(), prop::option::of("[a-zA-Z0-9]{1,8}")), 0..4);
+        let headers = prop::collection::vec((header_name(), header_value()), 0..4);
+        (
+            (method(), authority, path(), query),
+            (
+                prop::option::of(token()),
+                headers,
+                body(),
+                any::<HttpVersion>(),
+            ),
+        )
+            .prop_map(
+                |((method, authority, path, query), (fragment, headers, body, version))| {
+                    let mut request = Request::from(path.as_str())
+                        .with_method(method)
+                        .with_body(body)
+                        .with_http_version(version);
+                    if let Some((scheme, host, port)) = authority {
+                        request.scheme = Some(scheme.into());
+                        request.host = Some(host);
+                        request.port = port;
+                    }
+                    for (key, value) in query {
+                        request.append_query(key, value);
+                    }
+                    for (name, value) in headers {
+                        request.append_header(name, value);
+                    }
+                    request.fragment = fragment;
+                    request
+                },
+            )
+            .boxed()
+    }
+}
+
+/// Built-in matchers on the parts [`Request`]'s strategy generates, nested up to three
+/// levels through `Not`, `AllOf` and `AnyOf`.
+impl Arbitrary for Matcher {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let leaf = prop_oneof![
+            method().prop_map(Matcher::Method),
+            path().prop_map(Matcher::Path),
+            path().prop_map(Matcher::PathPrefix),
+            token().prop_map(Matcher::QueryExists),
+            token().prop_map(Matcher::QueryMiss),
+            (token(), "[a-zA-Z0-9]{0,8}").prop_map(|(k, v)| Matcher::QueryEq(k, v)),
+            token().prop_map(Matcher::FragmentEq),
+            Just(Matcher::FragmentMiss),
+            header_name().prop_map(Matcher::HeaderExists),
+            header_name().prop_map(Matcher::HeaderMiss),
+            (header_name(), header_value()).prop_map(|(k, v)| Matcher::HeaderEq(k, v)),
+            Just(Matcher::BodyExists),
+            Just(Matcher::BodyMiss),
+            "[ -~]{1,8}".prop_map(Matcher::BodyContains),
+            any::<HttpVersion>().prop_map(Matcher::Version),
+        ];
+        leaf.prop_recursive(3, 16, 4, |inner| {
+            prop_oneof![
+                inner.clone().prop_map(|m| Matcher::Not(Box::new(m))),
+                prop::collection::vec(inner.clone(), 1..4).prop_map(Matcher::AllOf),
+                prop::collection::vec(inner, 1..4).prop_map(Matcher::AnyOf),
+            ]
+        })
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matchers::{CaptureOptions, Matchers};
+
+    proptest! {
+        #[test]
+        fn captured_matchers_match_their_request(request: Request) {
+            let matchers = Matchers::from_request(&request, &CaptureOptions::default());
+
+            prop_assert!(matchers.is_matched(&request), "{matchers:?} should match {request}");
+        }
+
+        #[test]
+        fn corrections_match_the_request(matcher: Matcher, request: Request) {
+            if let Some(observed) = matcher.observed(&request) {
+                prop_assert_eq!(observed.observed(&request), None);
+            }
+        }
+
+        #[test]
+        fn target_parses_back(request: Request) {
+            let target = format!("{}{}", request.target(), request.fragment.as_ref().map_or(String::new(), |f| format!("#{f}")));
+            let parsed = Request::from(target.as_str());
+
+            prop_assert_eq!((&parsed.path, &parsed.query, &parsed.fragment), (&request.path, &request.query, &request.fragment));
+        }
+    }
+}
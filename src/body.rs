@@ -0,0 +1,204 @@
+use std::borrow::Cow;
+
+/// The payload of a request. Text and bytes bodies compare equal when they hold the same
+/// bytes, so it doesn't matter how a body was built.
+#[derive(Debug, Clone, Default)]
+pub enum Body {
+    /// No body at all, unlike an empty text or bytes body.
+    #[default]
+    Empty,
+    Text(String),
+    Bytes(Vec<u8>),
+    /// A pre-parsed JSON document, serialized compactly when read as bytes.
+    #[cfg(feature = "json")]
+    Json(serde_json::Value),
+}
+
+impl Body {
+    /// Whether there is no body at all.
+    pub fn is_empty(&self) -> bool {
+        matches!(self, Body::Empty)
+    }
+
+    /// The payload as sent on the wire, or `None` without a body.
+    pub fn bytes(&self) -> Option<Cow<'_, [u8]>> {
+        match self {
+            Body::Empty => None,
+            Body::Text(text) => Some(Cow::Borrowed(text.as_bytes())),
+            Body::Bytes(bytes) => Some(Cow::Borrowed(bytes)),
+            #[cfg(feature = "json")]
+            Body::Json(value) => Some(Cow::Owned(value.to_string().into_bytes())),
+        }
+    }
+
+    /// The payload as text, or `None` without a body or when it isn't valid UTF-8.
+    pub fn text(&self) -> Option<Cow<'_, str>> {
+        match self {
+            Body::Empty => None,
+            Body::Text(text) => Some(Cow::Borrowed(text)),
+            Body::Bytes(bytes) => std::str::from_utf8(bytes).ok().map(Cow::Borrowed),
+            #[cfg(feature = "json")]
+            Body::Json(value) => Some(Cow::Owned(value.to_string())),
+        }
+    }
+
+    pub fn into_bytes(self) -> Option<Vec<u8>> {
+        match self {
+            Body::Empty => None,
+            Body::Text(text) => Some(text.into_bytes()),
+            Body::Bytes(bytes) => Some(bytes),
+            #[cfg(feature = "json")]
+            Body::Json(value) => Some(value.to_string().into_bytes()),
+        }
+    }
+}
+
+impl PartialEq for Body {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            #[cfg(feature = "json")]
+            (Body::Json(a), Body::Json(b)) => a == b,
+            (a, b) => a.bytes() == b.bytes(),
+        }
+    }
+}
+
+impl From<String> for Body {
+    fn from(text: String) -> Self {
+        Body::Text(text)
+    }
+}
+
+impl From<&str> for Body {
+    fn from(text: &str) -> Self {
+        Body::Text(text.into())
+    }
+}
+
+impl From<Vec<u8>> for Body {
+    fn from(bytes: Vec<u8>) -> Self {
+        Body::Bytes(bytes)
+    }
+}
+
+impl From<&[u8]> for Body {
+    fn from(bytes: &[u8]) -> Self {
+        Body::Bytes(bytes.into())
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for Body {
+    fn from(bytes: [u8; N]) -> Self {
+        Body::Bytes(bytes.into())
+    }
+}
+
+impl<const N: usize> From<&[u8; N]> for Body {
+    fn from(bytes: &[u8; N]) -> Self {
+        Body::Bytes(bytes.into())
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<serde_json::Value> for Body {
+    fn from(value: serde_json::Value) -> Self {
+        Body::Json(value)
+    }
+}
+
+/// Text is written as a string, bytes as `{"base64": "..."}`, JSON as `{"json": ...}` and
+/// no body as `null`.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+enum Repr {
+    Text(String),
+    Bytes {
+        base64: String,
+    },
+    #[cfg(feature = "json")]
+    Json {
+        json: serde_json::Value,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Body {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use base64::Engine;
+        use base64::engine::general_purpose::STANDARD as BASE64;
+
+        let repr = match self {
+            Body::Empty => None,
+            Body::Text(text) => Some(Repr::Text(text.clone())),
+            Body::Bytes(bytes) => Some(Repr::Bytes {
+                base64: BASE64.encode(bytes),
+            }),
+            #[cfg(feature = "json")]
+            Body::Json(json) => Some(Repr::Json { json: json.clone() }),
+        };
+        repr.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Body {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use base64::Engine;
+        use base64::engine::general_purpose::STANDARD as BASE64;
+        use serde::de::Error;
+
+        Ok(match Option::<Repr>::deserialize(deserializer)? {
+            None => Body::Empty,
+            Some(Repr::Text(text)) => Body::Text(text),
+            Some(Repr::Bytes { base64 }) => {
+                Body::Bytes(BASE64.decode(&base64).map_err(D::Error::custom)?)
+            }
+            #[cfg(feature = "json")]
+            Some(Repr::Json { json }) => Body::Json(json),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::text_and_bytes(Body::from("hi"), Body::from(b"hi"), true)]
+    #[case::empty_text(Body::from(""), Body::Empty, false)]
+    #[case::different(Body::from("a"), Body::from("b"), false)]
+    fn equality(#[case] a: Body, #[case] b: Body, #[case] equal: bool) {
+        assert_eq!(a == b, equal, "{a:?} == {b:?} should be {equal}");
+    }
+
+    #[test]
+    fn binary_body_has_no_text() {
+        let body = Body::from(vec![0xff, 0x00]);
+
+        assert_eq!(body.text(), None);
+        assert_eq!(body.bytes().as_deref(), Some([0xff, 0x00].as_slice()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[rstest]
+    #[case::empty(Body::Empty, serde_json::json!(null))]
+    #[case::text(Body::from("hi"), serde_json::json!("hi"))]
+    #[case::bytes(Body::from([0xff, 0x00]), serde_json::json!({"base64": "/wA="}))]
+    fn serde_round_trip(#[case] body: Body, #[case] expected: serde_json::Value) {
+        let serialized = serde_json::to_value(&body).unwrap();
+
+        assert_eq!(serialized, expected);
+        assert_eq!(serde_json::from_value::<Body>(serialized).unwrap(), body);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_body_reads_as_compact_text() {
+        let body = Body::from(serde_json::json!({"id": 1}));
+
+        assert_eq!(body.text().as_deref(), Some(r#"{"id":1}"#));
+        assert_eq!(body, Body::from(serde_json::json!({"id": 1})));
+    }
+}
@@ -0,0 +1,501 @@
+use crate::request::{Request, split_url};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+
+/// Why a curl command line couldn't be understood.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CurlError {
+    /// The command doesn't start with `curl`.
+    NotCurl,
+    MissingUrl,
+    UnterminatedQuote,
+    /// An option such as `-H` came last, without its value.
+    MissingArgument(String),
+    /// An option this parser doesn't know, so it can't tell whether it changes the request.
+    UnsupportedOption(String),
+}
+
+impl std::fmt::Display for CurlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurlError::NotCurl => f.write_str("not a curl command"),
+            CurlError::MissingUrl => f.write_str("curl command has no URL"),
+            CurlError::UnterminatedQuote => f.write_str("unterminated quote"),
+            CurlError::MissingArgument(option) => write!(f, "option {option:?} needs a value"),
+            CurlError::UnsupportedOption(option) => write!(f, "unsupported option {option:?}"),
+        }
+    }
+}
+
+impl std::error::Error for CurlError {}
+
+/// Options that take a value but don't affect the request itself.
+const IGNORED_WITH_VALUE: &[&str] = &[
+    "-o",
+    "--output",
+    "-m",
+    "--max-time",
+    "--connect-timeout",
+    "-w",
+    "--write-out",
+    "--retry",
+    "-x",
+    "--proxy",
+    "--cacert",
+    "--cert",
+    "--key",
+    "-c",
+    "--cookie-jar",
+    "--resolve",
+];
+
+/// Options that take no value and don't affect the request itself.
+const IGNORED_FLAGS: &[&str] = &[
+    "-s",
+    "--silent",
+    "-S",
+    "--show-error",
+    "-L",
+    "--location",
+    "--location-trusted",
+    "-k",
+    "--insecure",
+    "-v",
+    "--verbose",
+    "-i",
+    "--include",
+    "-f",
+    "--fail",
+    "--fail-with-body",
+    "-g",
+    "--globoff",
+    "-N",
+    "--no-buffer",
+    "-#",
+    "--progress-bar",
+    "-O",
+    "--remote-name",
+    "-n",
+    "--netrc",
+    "-q",
+    "--compressed",
+    "--http1.1",
+    "--http2",
+];
+
+/// Options shaping the request that take a value.
+const WITH_VALUE: &[&str] = &[
+    "-X",
+    "--request",
+    "-H",
+    "--header",
+    "-d",
+    "--data",
+    "--data-raw",
+    "--data-binary",
+    "--data-ascii",
+    "--data-urlencode",
+    "--json",
+    "-u",
+    "--user",
+    "-b",
+    "--cookie",
+    "-A",
+    "--user-agent",
+    "-e",
+    "--referer",
+    "--url",
+];
+
+/// Builds the request a curl command line would send.
+pub(crate) fn parse(command: &str) -> Result<Request, CurlError> {
+    let mut args = std::collections::VecDeque::from(shell_words(command)?);
+    if args.pop_front().as_deref() != Some(b"curl".as_slice()) {
+        return Err(CurlError::NotCurl);
+    }
+
+    let mut request = Request::default();
+    let mut method = None;
+    let mut url = None;
+    let mut data: Vec<Vec<u8>> = Vec::new();
+    let mut get = false;
+
+    while let Some(mut word) = args.pop_front() {
+        // Short options cluster, as in `-sSL`, and take their value attached, as in `-XPUT`.
+        if let [b'-', option, rest @ ..] = word.as_slice()
+            && *option != b'-'
+            && option.is_ascii()
+            && !rest.is_empty()
+        {
+            let option = format!("-{}", *option as char);
+            args.push_front(match takes_value(&option) {
+                true => rest.to_vec(),
+                false => [b"-", rest].concat(),
+            });
+            word = option.into_bytes();
+        }
+        let arg = text(&word);
+        let mut value = || {
+            args.pop_front()
+                .ok_or_else(|| CurlError::MissingArgument(arg.clone()))
+        };
+        match arg.as_str() {
+            "-X" | "--request" => method = Some(text(&value()?)),
+            "-H" | "--header" => {
+                let header = text(&value()?);
+                if let Some((name, value)) = header.split_once(':') {
+                    request.append_header(name.trim(), value.trim());
+                }
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii" => {
+                data.push(value()?)
+            }
+            "--data-urlencode" => {
+                let field = value()?;
+                data.push(match field.iter().position(|&byte| byte == b'=') {
+                    Some(idx) => [&field[..=idx], urlencode(&field[idx + 1..]).as_bytes()].concat(),
+                    None => urlencode(&field).into_bytes(),
+                });
+            }
+            "--json" => {
+                data.push(value()?);
+                if request.header("Content-Type").is_none() {
+                    request.set_header("Content-Type", "application/json");
+                }
+                if request.header("Accept").is_none() {
+                    request.set_header("Accept", "application/json");
+                }
+            }
+            "-u" | "--user" => {
+                let credentials = BASE64.encode(value()?);
+                request.set_header("Authorization", format!("Basic {credentials}"));
+            }
+            "-b" | "--cookie" => request.set_header("Cookie", text(&value()?)),
+            "-A" | "--user-agent" => request.set_header("User-Agent", text(&value()?)),
+            "-e" | "--referer" => request.set_header("Referer", text(&value()?)),
+            "--url" => url = Some(text(&value()?)),
+            "-G" | "--get" => get = true,
+            "-I" | "--head" => method = Some("HEAD".into()),
+            option if IGNORED_WITH_VALUE.contains(&option) => {
+                value()?;
+            }
+            option if IGNORED_FLAGS.contains(&option) => {}
+            option if option.starts_with('-') && option.len() > 1 => {
+                return Err(CurlError::UnsupportedOption(option.into()));
+            }
+            _ => url = url.or(Some(arg)),
+        }
+    }
+
+    let url = url.ok_or(CurlError::MissingUrl)?;
+    let (authority, target) = split_url(&url);
+    let data = (!data.is_empty()).then(|| data.join(b"&".as_slice()));
+
+    let mut target = Request::from(target);
+    if get && let Some(data) = &data {
+        for (key, value) in form_urlencoded::parse(data) {
+            target.append_query(key, Some(value));
+        }
+    }
+    request.path = target.path;
+    request.query = target.query;
+    request.fragment = target.fragment;
+
+    if let Some((scheme, host, port)) = authority {
+        request.scheme = scheme.map(String::from);
+        request.host = Some(host.into());
+        request.port = port;
+    }
+
+    request.method = match (method, &data) {
+        (Some(method), _) => method.to_uppercase(),
+        (None, Some(_)) if !get => "POST".into(),
+        (None, _) => "GET".into(),
+    };
+    if !get && let Some(data) = data {
+        if request.header("Content-Type").is_none() {
+            request.set_header("Content-Type", "application/x-www-form-urlencoded");
+        }
+        match String::from_utf8(data) {
+            Ok(text) => request.set_body(text),
+            Err(binary) => request.set_body(binary.into_bytes()),
+        }
+    }
+
+    Ok(request)
+}
+
+fn takes_value(option: &str) -> bool {
+    WITH_VALUE.contains(&option) || IGNORED_WITH_VALUE.contains(&option)
+}
+
+/// Renders the curl command that sends `request`, quoting every word for a POSIX shell.
+pub(crate) fn render(request: &Request) -> String {
+    let mut words = vec!["curl".to_string()];
+    if request.method != "GET" || !request.body.is_empty() {
+        words.extend(["-X".into(), quote(&request.method)]);
+    }
+
+    words.push(quote(&request.absolute_uri()));
+
+    for (name, value) in request.headers.iter() {
+        words.extend(["-H".into(), quote(&format!("{name}: {value}"))]);
+    }
+    if let Some(body) = request.body.bytes() {
+        match std::str::from_utf8(&body) {
+            Ok(text) => words.extend(["--data-raw".into(), quote(text)]),
+            Err(_) => words.extend(["--data-binary".into(), ansi_c_quote(&body)]),
+        }
+    }
+
+    words.join(" ")
+}
+
+/// Single-quotes `word` unless it only holds characters a shell leaves alone.
+fn quote(word: &str) -> String {
+    let plain = !word.is_empty()
+        && word
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || "-_./:=@%+,".contains(ch));
+    if plain {
+        word.to_string()
+    } else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
+
+/// Quotes binary data as a `$'...'` string with every byte escaped.
+fn ansi_c_quote(bytes: &[u8]) -> String {
+    let escaped: String = bytes.iter().map(|byte| format!("\\x{byte:02x}")).collect();
+    format!("$'{escaped}'")
+}
+
+fn urlencode(value: &[u8]) -> String {
+    form_urlencoded::byte_serialize(value).collect()
+}
+
+fn text(word: &[u8]) -> String {
+    String::from_utf8_lossy(word).into_owned()
+}
+
+/// Splits a command line the way a POSIX shell would: single quotes are literal, double
+/// quotes and bare words honour backslash escapes, `$'...'` decodes ANSI-C escapes such as
+/// `\n` and `\xff`, and `\` before a newline continues a line. Words are bytes, since
+/// `$'...'` can spell any.
+fn shell_words(command: &str) -> Result<Vec<Vec<u8>>, CurlError> {
+    let mut words = Vec::new();
+    let mut current: Option<Vec<u8>> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\'' => {
+                let word = current.get_or_insert_with(Vec::new);
+                loop {
+                    match chars.next().ok_or(CurlError::UnterminatedQuote)? {
+                        '\'' => break,
+                        ch => push(word, ch),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                ansi_c(&mut chars, current.get_or_insert_with(Vec::new))?;
+            }
+            '"' => {
+                let word = current.get_or_insert_with(Vec::new);
+                loop {
+                    match chars.next().ok_or(CurlError::UnterminatedQuote)? {
+                        '"' => break,
+                        '\\' => match chars.next().ok_or(CurlError::UnterminatedQuote)? {
+                            escaped @ ('"' | '\\' | '$' | '`') => push(word, escaped),
+                            '\n' => {}
+                            other => {
+                                push(word, '\\');
+                                push(word, other);
+                            }
+                        },
+                        ch => push(word, ch),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                Some('\n') | None => {}
+                Some(escaped) => push(current.get_or_insert_with(Vec::new), escaped),
+            },
+            ch if ch.is_whitespace() => words.extend(current.take()),
+            ch => push(current.get_or_insert_with(Vec::new), ch),
+        }
+    }
+    words.extend(current);
+
+    Ok(words)
+}
+
+fn push(word: &mut Vec<u8>, ch: char) {
+    word.extend_from_slice(ch.encode_utf8(&mut [0; 4]).as_bytes());
+}
+
+/// Reads up to `max` more digits in `radix` onto `value`, as bash reads `\xHH`, `\nnn` and
+/// `\uHHHH`; `None` without any digit.
+fn digits(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    radix: u32,
+    max: usize,
+    mut value: Option<u32>,
+) -> Option<u32> {
+    for _ in 0..max {
+        let Some(digit) = chars.peek().and_then(|ch| ch.to_digit(radix)) else {
+            break;
+        };
+        value = Some(value.unwrap_or(0) * radix + digit);
+        chars.next();
+    }
+    value
+}
+
+/// Decodes the rest of a `$'...'` string, after its opening quote, into `word`.
+fn ansi_c(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+    word: &mut Vec<u8>,
+) -> Result<(), CurlError> {
+    loop {
+        let ch = chars.next().ok_or(CurlError::UnterminatedQuote)?;
+        if ch == '\'' {
+            return Ok(());
+        }
+        if ch != '\\' {
+            push(word, ch);
+            continue;
+        }
+        let escaped = chars.next().ok_or(CurlError::UnterminatedQuote)?;
+        let byte = match escaped {
+            'a' => 0x07,
+            'b' => 0x08,
+            'e' | 'E' => 0x1b,
+            'f' => 0x0c,
+            'n' => b'\n',
+            'r' => b'\r',
+            't' => b'\t',
+            'v' => 0x0b,
+            '\\' | '\'' | '"' | '?' => escaped as u8,
+            'x' => match digits(chars, 16, 2, None) {
+                Some(value) => value as u8,
+                None => {
+                    word.extend_from_slice(b"\\x");
+                    continue;
+                }
+            },
+            '0'..='7' => digits(chars, 8, 2, escaped.to_digit(8)).unwrap_or_default() as u8,
+            'u' | 'U' => {
+                let max = if escaped == 'u' { 4 } else { 8 };
+                match digits(chars, 16, max, None).and_then(char::from_u32) {
+                    Some(ch) => push(word, ch),
+                    None => word.extend_from_slice(&[b'\\', escaped as u8]),
+                }
+                continue;
+            }
+            other => {
+                word.push(b'\\');
+                push(word, other);
+                continue;
+            }
+        };
+        word.push(byte);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn parses_full_command() {
+        let request = Request::from_curl(
+            "curl -X PUT 'https://api.example.com:8443/users/42?notify=true' \\\n  -H 'Content-Type: application/json' -H \"X-Api-Key: k\" -d '{\"name\":\"Bob\"}' -s",
+        )
+        .unwrap();
+
+        assert_eq!(
+            request,
+            Request::from("/users/42?notify=true")
+                .with_method("PUT")
+                .with_scheme("https")
+                .with_host("api.example.com")
+                .with_port(8443)
+                .with_header("Content-Type", "application/json")
+                .with_header("X-Api-Key", "k")
+                .with_body(r#"{"name":"Bob"}"#)
+        );
+    }
+
+    #[rstest]
+    #[case::default_get("curl http://a.test/x", Request::from("/x").with_scheme("http").with_host("a.test"))]
+    #[case::data_implies_post("curl a.test -d a=1 -d b=2", Request::default().with_host("a.test").with_method("POST").with_header("Content-Type", "application/x-www-form-urlencoded").with_body("a=1&b=2"))]
+    #[case::get_moves_data("curl -G https://a.test/s --data-urlencode 'q=a b'", Request::from("/s?q=a b").with_scheme("https").with_host("a.test"))]
+    #[case::json("curl --json '{}' https://a.test", Request::default().with_scheme("https").with_host("a.test").with_method("POST").with_header("Content-Type", "application/json").with_header("Accept", "application/json").with_body("{}"))]
+    #[case::user("curl -u bob:secret -I a.test/", Request::default().with_host("a.test").with_method("HEAD").with_header("Authorization", "Basic Ym9iOnNlY3JldA=="))]
+    #[case::ignored("curl -o out.txt -L --url /only/path", Request::from("/only/path"))]
+    #[case::repeated_header("curl -H 'Via: a' -H 'via: b' a.test", Request::default().with_host("a.test").with_header("Via", "a").with_appended_header("via", "b"))]
+    #[case::attached_values("curl -XPUT -HAccept:x -ubob:pw a.test", Request::default().with_host("a.test").with_method("PUT").with_header("Accept", "x").with_header("Authorization", "Basic Ym9iOnB3"))]
+    #[case::cluster("curl -sSLd a=1 a.test", Request::default().with_host("a.test").with_method("POST").with_header("Content-Type", "application/x-www-form-urlencoded").with_body("a=1"))]
+    #[case::cluster_attached("curl -sXDELETE a.test", Request::default().with_host("a.test").with_method("DELETE"))]
+    #[case::dash_value("curl -d -1 a.test", Request::default().with_host("a.test").with_method("POST").with_header("Content-Type", "application/x-www-form-urlencoded").with_body("-1"))]
+    #[case::ansi_c_json(r#"curl a.test --data-raw $'{"a":"b\n"}'"#, Request::default().with_host("a.test").with_method("POST").with_header("Content-Type", "application/x-www-form-urlencoded").with_body("{\"a\":\"b\n\"}"))]
+    #[case::ansi_c_binary(r"curl a.test --data-binary $'\xff\x00'", Request::default().with_host("a.test").with_method("POST").with_header("Content-Type", "application/x-www-form-urlencoded").with_body([0xff, 0x00]))]
+    #[case::ansi_c_escapes(r"curl a.test -H $'X-Note: it\'s\t\101\u00e9\\'", Request::default().with_host("a.test").with_header("X-Note", "it's\tAé\\"))]
+    fn parses(#[case] command: &str, #[case] expected: Request) {
+        assert_eq!(
+            parse(command),
+            Ok(expected),
+            "Command {command:?} should parse"
+        );
+    }
+
+    #[rstest]
+    #[case::get(Request::from("/health").with_host("a.test"), "curl http://a.test/health")]
+    #[case::host_header(Request::from("/x?q=1").with_header("Host", "a.test:8080"), "curl 'http://a.test:8080/x?q=1' -H 'Host: a.test:8080'")]
+    #[case::no_host(Request::default(), "curl http://localhost/")]
+    #[case::quoted_body(Request::from("/n").with_scheme("https").with_host("a.test").with_method("PUT").with_body("it's"), r"curl -X PUT https://a.test/n --data-raw 'it'\''s'")]
+    #[case::binary(Request::from("/b").with_host("a.test").with_method("POST").with_body([0xff, 0x00]), r"curl -X POST http://a.test/b --data-binary $'\xff\x00'")]
+    fn renders(#[case] request: Request, #[case] command: &str) {
+        assert_eq!(request.to_curl(), command);
+    }
+
+    #[rstest]
+    #[case::text(
+        Request::from("/users/42?notify=true&tag=a b")
+            .with_method("PATCH")
+            .with_scheme("https")
+            .with_host("api.example.com")
+            .with_port(8443)
+            .with_header("Content-Type", "application/json")
+            .with_header("X-Note", "say \"hi\"")
+            .with_body(r#"{"name":"O'Brien"}"#)
+    )]
+    #[case::binary(
+        Request::from("/upload")
+            .with_method("POST")
+            .with_scheme("http")
+            .with_host("a.test")
+            .with_header("Content-Type", "application/octet-stream")
+            .with_body([0xff, 0x00, b'\'', b'\\', 0x7f])
+    )]
+    fn render_round_trips(#[case] request: Request) {
+        assert_eq!(Request::from_curl(&request.to_curl()), Ok(request));
+    }
+
+    #[rstest]
+    #[case::not_curl("wget http://a.test", CurlError::NotCurl)]
+    #[case::url("curl -X POST", CurlError::MissingUrl)]
+    #[case::quote("curl 'http://a.test", CurlError::UnterminatedQuote)]
+    #[case::ansi_c_quote(r"curl a.test -d $'a\'", CurlError::UnterminatedQuote)]
+    #[case::argument("curl a.test -H", CurlError::MissingArgument("-H".into()))]
+    #[case::attached_argument("curl a.test -sH", CurlError::MissingArgument("-H".into()))]
+    #[case::unknown("curl --upload-file x a.test", CurlError::UnsupportedOption("--upload-file".into()))]
+    #[case::unknown_short("curl -sT x a.test", CurlError::UnsupportedOption("-T".into()))]
+    fn rejects(#[case] command: &str, #[case] error: CurlError) {
+        assert_eq!(parse(command), Err(error));
+    }
+}
@@ -0,0 +1,196 @@
+use crate::request::Request;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Why a request fixture couldn't be loaded.
+#[derive(Debug)]
+pub enum FixtureError {
+    Io(PathBuf, std::io::Error),
+    /// The file isn't a valid request in its format.
+    Invalid(PathBuf, String),
+    /// The extension is none of `json`, `yaml`, `yml` or `http`.
+    UnsupportedFormat(PathBuf),
+}
+
+impl std::fmt::Display for FixtureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FixtureError::Io(path, error) => write!(f, "{}: {error}", path.display()),
+            FixtureError::Invalid(path, error) => {
+                write!(f, "{}: invalid request: {error}", path.display())
+            }
+            FixtureError::UnsupportedFormat(path) => {
+                write!(f, "{}: unsupported fixture format", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for FixtureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FixtureError::Io(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Format {
+    Json,
+    Yaml,
+    Http,
+}
+
+pub(crate) fn format(path: &Path) -> Option<Format> {
+    match path.extension()?.to_str()? {
+        "json" => Some(Format::Json),
+        "yaml" | "yml" => Some(Format::Yaml),
+        "http" => Some(Format::Http),
+        _ => None,
+    }
+}
+
+fn load(path: &Path, format: Format) -> Result<Request, FixtureError> {
+    let invalid = |error: String| FixtureError::Invalid(path.into(), error);
+    let bytes = std::fs::read(path).map_err(|error| FixtureError::Io(path.into(), error))?;
+    match format {
+        Format::Json => serde_json::from_slice(&bytes).map_err(|e| invalid(e.to_string())),
+        Format::Yaml => serde_yaml::from_slice(&bytes).map_err(|e| invalid(e.to_string())),
+        Format::Http => Request::parse_http(&bytes).map_err(|e| invalid(e.to_string())),
+    }
+}
+
+impl Request {
+    /// Loads a request from a fixture file: JSON or YAML in the `serde` form of
+    /// [`Request`], or a raw `.http` request.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Request, FixtureError> {
+        let path = path.as_ref();
+        let format = format(path).ok_or_else(|| FixtureError::UnsupportedFormat(path.into()))?;
+        load(path, format)
+    }
+}
+
+/// Request fixtures loaded from a directory tree, named by their path relative to it
+/// without the extension, e.g. `users/create` for `users/create.yaml`.
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    requests: BTreeMap<String, Request>,
+}
+
+impl Fixtures {
+    /// Loads every `json`, `yaml`, `yml` and `http` file below `dir`; other files are
+    /// skipped.
+    pub fn load_dir<P: AsRef<Path>>(dir: P) -> Result<Fixtures, FixtureError> {
+        let mut fixtures = Fixtures::default();
+        fixtures.load_from(dir.as_ref(), dir.as_ref())?;
+        Ok(fixtures)
+    }
+
+    fn load_from(&mut self, root: &Path, dir: &Path) -> Result<(), FixtureError> {
+        let io = |error| FixtureError::Io(dir.into(), error);
+        for entry in std::fs::read_dir(dir).map_err(io)? {
+            let path = entry.map_err(io)?.path();
+            if path.is_dir() {
+                self.load_from(root, &path)?;
+            } else if let Some(format) = format(&path) {
+                let name = path.strip_prefix(root).unwrap_or(&path).with_extension("");
+                let name = name.to_string_lossy().replace('\\', "/");
+                self.requests.insert(name, load(&path, format)?);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Request> {
+        self.requests.get(name)
+    }
+
+    /// Names in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.requests.keys()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Request)> {
+        self.requests.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+}
+
+/// Panics when there is no fixture named `name`.
+impl std::ops::Index<&str> for Fixtures {
+    type Output = Request;
+
+    fn index(&self, name: &str) -> &Request {
+        self.get(name)
+            .unwrap_or_else(|| panic!("no request fixture named {name:?}"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, content: &str) {
+        let path = dir.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    #[test]
+    fn loads_each_format() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "health.json", r#"{"path": "/health"}"#);
+        write(
+            dir.path(),
+            "users/create.yaml",
+            "method: POST\npath: /users\nheaders:\n  Content-Type: application/json\nbody: '{\"name\":\"Bob\"}'\n",
+        );
+        write(
+            dir.path(),
+            "users/list.http",
+            "GET /users?page=2 HTTP/1.1\n\n",
+        );
+        write(dir.path(), "README.md", "not a fixture");
+
+        let fixtures = Fixtures::load_dir(dir.path()).unwrap();
+
+        assert_eq!(
+            fixtures.names().collect::<Vec<_>>(),
+            ["health", "users/create", "users/list"]
+        );
+        assert_eq!(fixtures["health"], Request::from("/health"));
+        assert_eq!(
+            fixtures["users/create"],
+            Request::from("/users")
+                .with_method("POST")
+                .with_header("Content-Type", "application/json")
+                .with_body(r#"{"name":"Bob"}"#)
+        );
+        assert_eq!(fixtures["users/list"], Request::from("/users?page=2"));
+    }
+
+    #[test]
+    fn reports_the_failing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "broken.json", "{");
+
+        let error = Request::from_file(dir.path().join("broken.json")).unwrap_err();
+
+        assert!(
+            matches!(&error, FixtureError::Invalid(path, _) if path.ends_with("broken.json")),
+            "Unexpected error {error}"
+        );
+        assert!(matches!(
+            Request::from_file("request.txt"),
+            Err(FixtureError::UnsupportedFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+use crate::request::{HttpVersion, Request};
+use crate::response::Response;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::{Value, json};
+use std::time::SystemTime;
+
+/// Why a HAR document couldn't be read.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HarError {
+    /// The document has no `log.entries` array.
+    MissingEntries,
+    /// The entry at this index lacks a `request` with a `method` and `url`.
+    InvalidEntry(usize),
+    /// A base64 encoded body doesn't decode.
+    InvalidBody(usize),
+}
+
+impl std::fmt::Display for HarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HarError::MissingEntries => f.write_str("HAR has no `log.entries` array"),
+            HarError::InvalidEntry(idx) => write!(f, "entry {idx} has no request method and url"),
+            HarError::InvalidBody(idx) => write!(f, "entry {idx} has an undecodable body"),
+        }
+    }
+}
+
+impl std::error::Error for HarError {}
+
+impl Request {
+    /// Reads the request of every entry of a HAR log, e.g. one exported by a browser.
+    pub fn from_har(har: &Value) -> Result<Vec<Request>, HarError> {
+        let entries = har
+            .pointer("/log/entries")
+            .and_then(Value::as_array)
+            .ok_or(HarError::MissingEntries)?;
+
+        entries
+            .iter()
+            .enumerate()
+            .map(|(idx, entry)| {
+                let request = entry.get("request").ok_or(HarError::InvalidEntry(idx))?;
+                from_har_request(request, idx)
+            })
+            .collect()
+    }
+
+    /// Reads a single HAR request object, the `request` member of an entry.
+    pub fn from_har_request(request: &Value) -> Result<Request, HarError> {
+        from_har_request(request, 0)
+    }
+
+    /// Writes the request as a HAR request object.
+    pub fn to_har_request(&self) -> Value {
+        let pairs = |pairs: Vec<(&str, &str)>| -> Vec<Value> {
+            pairs
+                .into_iter()
+                .map(|(name, value)| json!({"name": name, "value": value}))
+                .collect()
+        };
+        let query = self
+            .query
+            .iter()
+            .flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+            .map(|(key, value)| (key.as_str(), value.as_deref().unwrap_or_default()))
+            .collect();
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .collect();
+        let body = self.body.bytes();
+
+        let mut request = json!({
+            "method": self.method,
+            "url": self.absolute_uri(),
+            "httpVersion": self.http_version.as_str(),
+            "cookies": pairs(self.cookies()),
+            "headers": pairs(headers),
+            "queryString": pairs(query),
+            "headersSize": -1,
+            "bodySize": body.as_ref().map_or(0, |body| body.len()),
+        });
+        if let Some(body) = body {
+            let mime_type = self.header("Content-Type").map_or("", String::as_str);
+            request["postData"] = match std::str::from_utf8(&body) {
+                Ok(text) => json!({"mimeType": mime_type, "text": text}),
+                Err(_) => json!({
+                    "mimeType": mime_type,
+                    "text": BASE64.encode(&body),
+                    "encoding": "base64",
+                }),
+            };
+        }
+        request
+    }
+}
+
+impl Response {
+    /// Writes the response as a HAR response object.
+    pub fn to_har_response(&self) -> Value {
+        let headers = self
+            .headers
+            .iter()
+            .map(|(name, value)| json!({"name": name, "value": value}))
+            .collect::<Vec<_>>();
+        let body = self.body.bytes().unwrap_or_default();
+        let mime_type = self.header("Content-Type").map_or("", String::as_str);
+        let content = match std::str::from_utf8(&body) {
+            Ok(text) => json!({"size": body.len(), "mimeType": mime_type, "text": text}),
+            Err(_) => json!({
+                "size": body.len(),
+                "mimeType": mime_type,
+                "text": BASE64.encode(&body),
+                "encoding": "base64",
+            }),
+        };
+        json!({
+            "status": self.status,
+            "statusText": self.reason().unwrap_or_default(),
+            "httpVersion": self.http_version.as_str(),
+            "cookies": [],
+            "headers": headers,
+            "content": content,
+            "redirectURL": self.header("Location").map_or("", String::as_str),
+            "headersSize": -1,
+            "bodySize": body.len(),
+        })
+    }
+}
+
+/// A HAR 1.2 document holding `entries`, created by this crate.
+pub(crate) fn log(entries: Vec<Value>) -> Value {
+    json!({
+        "log": {
+            "version": "1.2",
+            "creator": {"name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION")},
+            "entries": entries,
+        }
+    })
+}
+
+/// Formats `time` as the ISO 8601 timestamp HAR expects, e.g. `2024-01-01T08:49:37.250Z`.
+pub(crate) fn timestamp(time: SystemTime) -> String {
+    let elapsed = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = elapsed.as_secs() as i64;
+    let (days, clock) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Howard Hinnant's civil-from-days, as for HTTP dates.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        clock / 3_600,
+        clock % 3_600 / 60,
+        clock % 60,
+        elapsed.subsec_millis()
+    )
+}
+
+fn from_har_request(har: &Value, idx: usize) -> Result<Request, HarError> {
+    let (Some(method), Some(url)) = (
+        har.get("method").and_then(Value::as_str),
+        har.get("url").and_then(Value::as_str),
+    ) else {
+        return Err(HarError::InvalidEntry(idx));
+    };
+
+    let mut request = Request::from(url);
+    request.method = method.to_uppercase();
+    if let Some(version) = har.get("httpVersion").and_then(Value::as_str) {
+        request.http_version = match version.to_ascii_uppercase().as_str() {
+            "HTTP/1.0" => HttpVersion::Http10,
+            "HTTP/2" | "HTTP/2.0" | "H2" => HttpVersion::Http2,
+            "HTTP/3" | "HTTP/3.0" | "H3" => HttpVersion::Http3,
+            _ => HttpVersion::Http11,
+        };
+    }
+
+    let headers = har.get("headers").and_then(Value::as_array);
+    for header in headers.into_iter().flatten() {
+        if let (Some(name), Some(value)) = (
+            header.get("name").and_then(Value::as_str),
+            header.get("value").and_then(Value::as_str),
+        ) {
+            request.append_header(name, value);
+        }
+    }
+
+    if let Some(post) = har.get("postData") {
+        let text = post.get("text").and_then(Value::as_str);
+        let params = post.get("params").and_then(Value::as_array);
+        match (text, post.get("encoding").and_then(Value::as_str)) {
+            (Some(text), Some("base64")) => request.set_body(
+                BASE64
+                    .decode(text)
+                    .map_err(|_| HarError::InvalidBody(idx))?,
+            ),
+            (Some(text), _) => request.set_body(text),
+            (None, _) => {
+                let mut form = form_urlencoded::Serializer::new(String::new());
+                for param in params.into_iter().flatten() {
+                    if let Some(name) = param.get("name").and_then(Value::as_str) {
+                        let value = param.get("value").and_then(Value::as_str);
+                        form.append_pair(name, value.unwrap_or_default());
+                    }
+                }
+                request.set_body(form.finish());
+            }
+        }
+    }
+
+    Ok(request)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn har(entries: Value) -> Value {
+        json!({"log": {"version": "1.2", "creator": {"name": "test"}, "entries": entries}})
+    }
+
+    #[test]
+    fn reads_browser_export() {
+        let requests = Request::from_har(&har(json!([{
+            "startedDateTime": "2024-01-01T00:00:00Z",
+            "request": {
+                "method": "POST",
+                "url": "https://api.example.com/users?notify=true",
+                "httpVersion": "h2",
+                "headers": [
+                    {"name": ":authority", "value": "api.example.com"},
+                    {"name": "content-type", "value": "application/json"}
+                ],
+                "queryString": [{"name": "notify", "value": "true"}],
+                "postData": {"mimeType": "application/json", "text": "{\"name\":\"Bob\"}"}
+            }
+        }])))
+        .unwrap();
+
+        assert_eq!(
+            requests,
+            vec![
+                Request::from("/users?notify=true")
+                    .with_method("POST")
+                    .with_scheme("https")
+                    .with_host("api.example.com")
+                    .with_http_version(HttpVersion::Http2)
+                    .with_header("Content-Type", "application/json")
+                    .with_body(r#"{"name":"Bob"}"#)
+            ]
+        );
+    }
+
+    #[test]
+    fn form_params_become_body() {
+        let request = Request::from_har_request(&json!({
+            "method": "post",
+            "url": "http://a.test/login",
+            "postData": {
+                "mimeType": "application/x-www-form-urlencoded",
+                "params": [{"name": "user", "value": "bob"}, {"name": "note", "value": "a b"}]
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(request.body, "user=bob&note=a+b".into());
+    }
+
+    #[rstest]
+    #[case::text(Request::from("/n?q=1").with_scheme("http").with_host("a.test").with_method("PUT").with_header("Content-Type", "text/plain").with_cookie("id", "7").with_body("hi"))]
+    #[case::binary(Request::from("/b").with_scheme("https").with_host("a.test").with_port(8443).with_method("POST").with_body([0xff, 0x00]))]
+    fn round_trip(#[case] request: Request) {
+        let har = request.to_har_request();
+
+        assert_eq!(Request::from_har_request(&har), Ok(request));
+    }
+
+    #[rstest]
+    #[case::epoch(SystemTime::UNIX_EPOCH, "1970-01-01T00:00:00.000Z")]
+    #[case::leap_day(SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(951_782_400_250), "2000-02-29T00:00:00.250Z")]
+    fn formats_timestamps(#[case] time: SystemTime, #[case] expected: &str) {
+        assert_eq!(timestamp(time), expected);
+    }
+
+    #[test]
+    fn writes_response() {
+        let response = Response::new(302)
+            .with_header("Location", "/next")
+            .with_body([0xff]);
+
+        assert_eq!(
+            response.to_har_response(),
+            json!({
+                "status": 302,
+                "statusText": "Found",
+                "httpVersion": "HTTP/1.1",
+                "cookies": [],
+                "headers": [{"name": "Location", "value": "/next"}],
+                "content": {"size": 1, "mimeType": "", "text": "/w==", "encoding": "base64"},
+                "redirectURL": "/next",
+                "headersSize": -1,
+                "bodySize": 1,
+            })
+        );
+    }
+
+    #[rstest]
+    #[case::no_log(json!({}), HarError::MissingEntries)]
+    #[case::no_url(har(json!([{"request": {"method": "GET"}}])), HarError::InvalidEntry(0))]
+    #[case::body(har(json!([{"request": {"method": "GET", "url": "/", "postData": {"text": "%", "encoding": "base64"}}}])), HarError::InvalidBody(0))]
+    fn rejects(#[case] har: Value, #[case] error: HarError) {
+        assert_eq!(Request::from_har(&har), Err(error));
+    }
+}
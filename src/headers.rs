@@ -0,0 +1,226 @@
+/// Header fields of a request. Names are compared ignoring ASCII case and keep the
+/// spelling they were first added with; names iterate in insertion order and each keeps
+/// every value it was given.
+#[derive(Debug, Clone, Default)]
+pub struct Headers {
+    entries: Vec<(String, Vec<String>)>,
+}
+
+impl Headers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn position(&self, name: &str) -> Option<usize> {
+        let name = name.trim();
+        self.entries
+            .iter()
+            .position(|(key, _)| key.eq_ignore_ascii_case(name))
+    }
+
+    /// Replaces every value of `name`.
+    pub fn insert<K: Into<String>, V: Into<String>>(&mut self, name: K, value: V) {
+        let name = name.into();
+        match self.position(&name) {
+            Some(idx) => self.entries[idx].1 = vec![value.into()],
+            None => self.entries.push((name.trim().into(), vec![value.into()])),
+        }
+    }
+
+    /// Adds another value for `name`, keeping the ones already present.
+    pub fn append<K: Into<String>, V: Into<String>>(&mut self, name: K, value: V) {
+        let name = name.into();
+        match self.position(&name) {
+            Some(idx) => self.entries[idx].1.push(value.into()),
+            None => self.entries.push((name.trim().into(), vec![value.into()])),
+        }
+    }
+
+    /// Removes `name`, returning its values.
+    pub fn remove(&mut self, name: &str) -> Vec<String> {
+        match self.position(name) {
+            Some(idx) => self.entries.remove(idx).1,
+            None => Vec::new(),
+        }
+    }
+
+    /// The first value of `name`.
+    pub fn get(&self, name: &str) -> Option<&String> {
+        self.get_all(name).first()
+    }
+
+    /// Every value of `name` in the order they were added; empty when it is absent.
+    pub fn get_all(&self, name: &str) -> &[String] {
+        self.position(name)
+            .map_or(&[], |idx| self.entries[idx].1.as_slice())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.position(name).is_some()
+    }
+
+    /// The distinct header names, in insertion order.
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(name, _)| name)
+    }
+
+    /// Every `(name, value)` pair, repeated names once per value.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.entries
+            .iter()
+            .flat_map(|(name, values)| values.iter().map(move |value| (name, value)))
+    }
+
+    /// Number of distinct names.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The media type of `Content-Type`, without parameters such as `charset`.
+    pub fn content_type(&self) -> Option<&str> {
+        let value = self.get("Content-Type")?;
+        Some(value.split(';').next().unwrap_or_default().trim())
+    }
+
+    /// `Content-Length`, when it is a valid number.
+    pub fn content_length(&self) -> Option<u64> {
+        self.get("Content-Length")?.trim().parse().ok()
+    }
+}
+
+/// Equal when both hold the same names, ignoring case and order, with the same values.
+impl PartialEq for Headers {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len()
+            && self
+                .entries
+                .iter()
+                .all(|(name, values)| other.get_all(name) == values.as_slice())
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Headers {
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut headers = Headers::new();
+        headers.extend(iter);
+        headers
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> Extend<(K, V)> for Headers {
+    fn extend<T: IntoIterator<Item = (K, V)>>(&mut self, iter: T) {
+        for (name, value) in iter {
+            self.append(name, value);
+        }
+    }
+}
+
+impl<K: Into<String>, V: Into<String>, const N: usize> From<[(K, V); N]> for Headers {
+    fn from(pairs: [(K, V); N]) -> Self {
+        pairs.into_iter().collect()
+    }
+}
+
+/// Written as a map from each name to its value, or to a list when it repeats.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Headers {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (name, values) in &self.entries {
+            match values.as_slice() {
+                [value] => map.serialize_entry(name, value)?,
+                values => map.serialize_entry(name, values)?,
+            }
+        }
+        map.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Headers {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Values {
+            One(String),
+            Many(Vec<String>),
+        }
+
+        let entries = indexmap::IndexMap::<String, Values>::deserialize(deserializer)?;
+        let mut headers = Headers::new();
+        for (name, values) in entries {
+            match values {
+                Values::One(value) => headers.append(name, value),
+                Values::Many(values) => {
+                    for value in values {
+                        headers.append(name.clone(), value);
+                    }
+                }
+            }
+        }
+        Ok(headers)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn keeps_first_spelling_and_order() {
+        let mut headers = Headers::from([("Via", "1.1 a"), ("Accept", "*/*"), ("via", "1.1 b")]);
+        headers.insert("ACCEPT", "text/plain");
+
+        assert_eq!(headers.names().collect::<Vec<_>>(), vec!["Via", "Accept"]);
+        assert_eq!(headers.get_all("VIA"), ["1.1 a", "1.1 b"]);
+        assert_eq!(headers.get("accept"), Some(&"text/plain".to_string()));
+        assert_eq!(
+            headers
+                .iter()
+                .map(|(k, v)| format!("{k}: {v}"))
+                .collect::<Vec<_>>(),
+            vec!["Via: 1.1 a", "Via: 1.1 b", "Accept: text/plain"]
+        );
+        assert_eq!(headers.remove("via"), vec!["1.1 a", "1.1 b"]);
+        assert!(!headers.contains("Via"));
+    }
+
+    #[test]
+    fn equality_ignores_name_case_and_order() {
+        assert_eq!(
+            Headers::from([("A", "1"), ("b", "2")]),
+            Headers::from([("B", "2"), ("a", "1")])
+        );
+        assert_ne!(
+            Headers::from([("A", "1"), ("A", "2")]),
+            Headers::from([("A", "2"), ("A", "1")])
+        );
+    }
+
+    #[rstest]
+    #[case::plain("application/json", Some("application/json"))]
+    #[case::parameters("text/html; charset=utf-8", Some("text/html"))]
+    fn content_type(#[case] value: &str, #[case] expected: Option<&str>) {
+        assert_eq!(
+            Headers::from([("Content-Type", value)]).content_type(),
+            expected
+        );
+    }
+
+    #[rstest]
+    #[case::valid(" 42", Some(42))]
+    #[case::invalid("-1", None)]
+    fn content_length(#[case] value: &str, #[case] expected: Option<u64>) {
+        assert_eq!(
+            Headers::from([("content-length", value)]).content_length(),
+            expected
+        );
+    }
+}
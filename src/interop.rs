@@ -0,0 +1,14 @@
+//! Conversions from and to the request types of other HTTP crates, each behind a feature
+//! named after the crate.
+
+#[cfg(feature = "actix")]
+mod actix;
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "hyper")]
+mod hyper;
+#[cfg(feature = "reqwest")]
+mod reqwest;
+
+#[cfg(feature = "hyper")]
+pub use self::hyper::CollectError;
@@ -0,0 +1,72 @@
+use crate::body::Body;
+use crate::request::{HttpVersion, Request};
+use actix_web::HttpRequest;
+use actix_web::http::Version;
+
+impl Request {
+    /// Builds a request from an actix-web request and its body, as extracted with
+    /// `web::Bytes` in a handler or collected in middleware.
+    pub fn from_actix<B: Into<Body>>(request: &HttpRequest, body: B) -> Request {
+        let uri = request.uri();
+        let mut converted =
+            Request::from(uri.path_and_query().map_or("/", |target| target.as_str()));
+        converted.method = request.method().to_string();
+        converted.scheme = uri.scheme_str().map(String::from);
+        converted.host = uri.host().map(String::from);
+        converted.port = uri.port_u16();
+        converted.http_version = match request.version() {
+            Version::HTTP_09 | Version::HTTP_10 => HttpVersion::Http10,
+            Version::HTTP_2 => HttpVersion::Http2,
+            Version::HTTP_3 => HttpVersion::Http3,
+            _ => HttpVersion::Http11,
+        };
+        for (name, value) in request.headers() {
+            converted.append_header(name.as_str(), String::from_utf8_lossy(value.as_bytes()));
+        }
+        converted.body = body.into();
+        converted.connection.remote_addr = request.peer_addr();
+        converted
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use actix_web::test::TestRequest;
+
+    #[test]
+    fn converts_request_and_body() {
+        let request = TestRequest::post()
+            .uri("/users?notify=true")
+            .insert_header(("Content-Type", "application/json"))
+            .to_http_request();
+
+        assert_eq!(
+            Request::from_actix(&request, r#"{"name":"Bob"}"#),
+            Request::from("/users?notify=true")
+                .with_method("POST")
+                .with_header("content-type", "application/json")
+                .with_body(r#"{"name":"Bob"}"#)
+        );
+    }
+
+    #[test]
+    fn records_peer_address() {
+        let peer = "10.0.0.7:51000".parse().unwrap();
+        let request = TestRequest::get().peer_addr(peer).to_http_request();
+
+        assert_eq!(
+            Request::from_actix(&request, Body::Empty)
+                .connection
+                .remote_addr,
+            Some(peer)
+        );
+    }
+
+    #[test]
+    fn bodiless_request() {
+        let request = TestRequest::get().uri("/health").to_http_request();
+
+        assert!(Request::from_actix(&request, Body::Empty).body.is_empty());
+    }
+}
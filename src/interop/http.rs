@@ -0,0 +1,147 @@
+use crate::body::Body;
+use crate::request::{HttpVersion, Request};
+use http::header::ToStrError;
+use http::request::Parts;
+use std::convert::Infallible;
+
+impl From<http::Version> for HttpVersion {
+    fn from(version: http::Version) -> Self {
+        match version {
+            http::Version::HTTP_09 | http::Version::HTTP_10 => HttpVersion::Http10,
+            http::Version::HTTP_2 => HttpVersion::Http2,
+            http::Version::HTTP_3 => HttpVersion::Http3,
+            _ => HttpVersion::Http11,
+        }
+    }
+}
+
+impl From<HttpVersion> for http::Version {
+    fn from(version: HttpVersion) -> Self {
+        match version {
+            HttpVersion::Http10 => http::Version::HTTP_10,
+            HttpVersion::Http11 => http::Version::HTTP_11,
+            HttpVersion::Http2 => http::Version::HTTP_2,
+            HttpVersion::Http3 => http::Version::HTTP_3,
+        }
+    }
+}
+
+/// Builds the request line, authority and headers; header values must be visible ASCII.
+impl TryFrom<&Parts> for Request {
+    type Error = ToStrError;
+
+    fn try_from(parts: &Parts) -> Result<Self, Self::Error> {
+        from_parts(parts, |value| value.to_str().map(String::from))
+    }
+}
+
+/// Header values that aren't valid UTF-8 are converted lossily.
+impl<B: Into<Body>> From<http::Request<B>> for Request {
+    fn from(value: http::Request<B>) -> Self {
+        let (parts, body) = value.into_parts();
+        let Ok(mut request) = from_parts::<Infallible>(&parts, |value| {
+            Ok(String::from_utf8_lossy(value.as_bytes()).into_owned())
+        });
+        request.body = body.into();
+        request
+    }
+}
+
+fn from_parts<E>(
+    parts: &Parts,
+    header_value: impl Fn(&http::HeaderValue) -> Result<String, E>,
+) -> Result<Request, E> {
+    let uri = &parts.uri;
+    let mut request = Request::from(uri.path_and_query().map_or("/", |target| target.as_str()));
+    request.method = parts.method.to_string();
+    request.scheme = uri.scheme_str().map(String::from);
+    request.host = uri.host().map(String::from);
+    request.port = uri.port_u16();
+    request.http_version = parts.version.into();
+
+    for (name, value) in &parts.headers {
+        request.append_header(name.as_str(), header_value(value)?);
+    }
+    Ok(request)
+}
+
+/// Fails when the method, target or a header isn't valid for the `http` crate.
+impl TryFrom<&Request> for http::Request<Vec<u8>> {
+    type Error = http::Error;
+
+    fn try_from(request: &Request) -> Result<Self, Self::Error> {
+        let mut builder = http::Request::builder()
+            .method(request.method.as_str())
+            .uri(request.uri())
+            .version(request.http_version.into());
+        for (name, value) in request.headers.iter() {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        builder.body(
+            request
+                .body
+                .bytes()
+                .map(|body| body.into_owned())
+                .unwrap_or_default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn example() -> http::Request<&'static str> {
+        http::Request::builder()
+            .method("POST")
+            .uri("https://api.example.com:8443/users?notify=true&tag=a&tag=b")
+            .version(http::Version::HTTP_2)
+            .header("Content-Type", "application/json")
+            .header("Via", "1.1 a")
+            .header("Via", "1.1 b")
+            .body(r#"{"name":"Bob"}"#)
+            .unwrap()
+    }
+
+    #[test]
+    fn from_http_request() {
+        let request = Request::from(example());
+
+        assert_eq!(
+            request,
+            Request::from("/users?notify=true&tag=a&tag=b")
+                .with_method("POST")
+                .with_scheme("https")
+                .with_host("api.example.com")
+                .with_port(8443)
+                .with_http_version(HttpVersion::Http2)
+                .with_header("content-type", "application/json")
+                .with_header("via", "1.1 a")
+                .with_appended_header("via", "1.1 b")
+                .with_body(r#"{"name":"Bob"}"#)
+        );
+    }
+
+    #[test]
+    fn from_parts_rejects_opaque_header() {
+        let (mut parts, _) = example().into_parts();
+        parts
+            .headers
+            .insert("x-raw", http::HeaderValue::from_bytes(&[0xff]).unwrap());
+
+        assert!(Request::try_from(&parts).is_err());
+        parts.headers.remove("x-raw");
+        assert_eq!(Request::try_from(&parts).unwrap().method, "POST");
+    }
+
+    #[test]
+    fn round_trip() {
+        let request = Request::from(example());
+        let converted = http::Request::try_from(&request).unwrap();
+
+        assert_eq!(converted.uri(), example().uri());
+        assert_eq!(converted.version(), http::Version::HTTP_2);
+        assert_eq!(converted.headers().get_all("via").iter().count(), 2);
+        assert_eq!(Request::from(converted), request);
+    }
+}
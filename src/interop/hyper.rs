@@ -0,0 +1,126 @@
+use crate::request::Request;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
+use hyper::body::Body;
+
+/// Why [`Request::from_hyper`] couldn't collect a body.
+#[derive(Debug)]
+pub enum CollectError {
+    /// The body is larger than the given limit in bytes.
+    TooLarge(usize),
+    /// Reading the body failed, e.g. because the connection was closed.
+    Body(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl std::fmt::Display for CollectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CollectError::TooLarge(limit) => write!(f, "body exceeds {limit} bytes"),
+            CollectError::Body(error) => write!(f, "failed to read body: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for CollectError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CollectError::TooLarge(_) => None,
+            CollectError::Body(error) => Some(error.as_ref()),
+        }
+    }
+}
+
+impl Request {
+    /// Captures an incoming hyper request, such as `hyper::Request<Incoming>` inside a
+    /// service, reading at most `limit` body bytes. An empty body becomes [`Body::Empty`].
+    ///
+    /// [`Body::Empty`]: crate::Body::Empty
+    pub async fn from_hyper<B>(
+        request: hyper::Request<B>,
+        limit: usize,
+    ) -> Result<Request, CollectError>
+    where
+        B: Body,
+        B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let (parts, body) = request.into_parts();
+        let bytes = Limited::new(body, limit)
+            .collect()
+            .await
+            .map_err(|error| match error.downcast::<LengthLimitError>() {
+                Ok(_) => CollectError::TooLarge(limit),
+                Err(error) => CollectError::Body(error),
+            })?
+            .to_bytes();
+
+        let mut request = Request::from(http::Request::from_parts(parts, crate::Body::Empty));
+        if !bytes.is_empty() {
+            request.set_body(bytes.to_vec());
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use http_body_util::{Empty, Full};
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    /// Drives a future whose body is already buffered, so it never has to wait.
+    fn ready<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        match future
+            .as_mut()
+            .poll(&mut Context::from_waker(Waker::noop()))
+        {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("future should complete without waiting"),
+        }
+    }
+
+    fn hyper_request<B>(body: B) -> hyper::Request<B> {
+        hyper::Request::post("/users")
+            .header("X-Key", "k")
+            .body(body)
+            .unwrap()
+    }
+
+    #[test]
+    fn collects_body() {
+        let request = ready(Request::from_hyper(
+            hyper_request(Full::new(&b"hello"[..])),
+            16,
+        ))
+        .unwrap();
+
+        assert_eq!(
+            request,
+            Request::from("/users")
+                .with_method("POST")
+                .with_header("x-key", "k")
+                .with_body("hello")
+        );
+    }
+
+    #[test]
+    fn empty_body() {
+        let request = ready(Request::from_hyper(
+            hyper_request(Empty::<&[u8]>::new()),
+            16,
+        ))
+        .unwrap();
+
+        assert!(request.body.is_empty());
+    }
+
+    #[test]
+    fn rejects_large_body() {
+        let result = ready(Request::from_hyper(
+            hyper_request(Full::new(&b"hello"[..])),
+            4,
+        ));
+
+        assert!(matches!(result, Err(CollectError::TooLarge(4))));
+    }
+}
@@ -0,0 +1,72 @@
+use crate::request::Request;
+
+/// Streaming bodies can't be read without sending the request and are left out.
+impl From<&reqwest::Request> for Request {
+    fn from(value: &reqwest::Request) -> Self {
+        let url = value.url();
+        let target = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+
+        let mut request = Request::from(target.as_str());
+        request.method = value.method().to_string();
+        request.scheme = Some(url.scheme().into());
+        request.host = url.host_str().map(String::from);
+        request.port = url.port();
+        request.fragment = url.fragment().map(String::from);
+        request.http_version = value.version().into();
+        for (name, header) in value.headers() {
+            request.append_header(name.as_str(), String::from_utf8_lossy(header.as_bytes()));
+        }
+        if let Some(body) = value.body().and_then(reqwest::Body::as_bytes) {
+            request.set_body(body);
+        }
+        request
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matchers::Matchers;
+    use reqwest::header::{CONTENT_TYPE, HeaderValue};
+    use reqwest::{Method, Url};
+
+    fn outgoing() -> reqwest::Request {
+        let url = Url::parse("https://api.example.com/v1/users?page=2").unwrap();
+        let mut request = reqwest::Request::new(Method::POST, url);
+        request
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        *request.body_mut() = Some(r#"{"name":"Bob"}"#.into());
+        request
+    }
+
+    #[test]
+    fn converts_outgoing_request() {
+        let request = Request::from(&outgoing());
+
+        assert_eq!(
+            request,
+            Request::from("/v1/users?page=2")
+                .with_method("POST")
+                .with_scheme("https")
+                .with_host("api.example.com")
+                .with_header("Content-Type", "application/json")
+                .with_body(r#"{"name":"Bob"}"#)
+        );
+    }
+
+    #[test]
+    fn validates_before_sending() {
+        let matchers = Matchers::new()
+            .method("POST")
+            .host("api.example.com")
+            .path("/v1/users")
+            .query_eq("page", "2")
+            .content_type("application/json");
+
+        assert_eq!(matchers.validate(&Request::from(&outgoing())), Ok(()));
+    }
+}
@@ -1,3 +1,58 @@
 #![allow(unused)]
+#[cfg(feature = "proptest")]
+mod arbitrary;
+mod body;
+mod curl;
+#[cfg(feature = "fixtures")]
+mod fixtures;
+#[cfg(feature = "har")]
+mod har;
+mod headers;
+mod interop;
 mod matchers;
+mod raw;
+mod redaction;
 mod request;
+mod response;
+mod router;
+#[cfg(feature = "server")]
+mod server;
+
+pub use body::Body;
+pub use curl::CurlError;
+#[cfg(feature = "fixtures")]
+pub use fixtures::{FixtureError, Fixtures};
+#[cfg(feature = "har")]
+pub use har::HarError;
+pub use headers::Headers;
+#[cfg(feature = "hyper")]
+pub use interop::CollectError;
+#[cfg(feature = "openapi")]
+pub use matchers::OpenApiError;
+pub use matchers::{
+    CaptureOptions, Conflict, Coverage, DslError, HashAlgorithm, Match, MatchScore, Matcher,
+    MatcherCoverage, Matchers, Mismatch, MismatchReport, Reason, ValueFormat, Weighted,
+};
+#[cfg(feature = "pact")]
+pub use matchers::{PactError, PactInteraction, PactResponse};
+pub use raw::{ParseLimits, RawRequestError};
+pub use redaction::{REDACTED, Redaction};
+pub use request::{
+    BodyError, BuildError, Connection, Difference, HttpVersion, ParseError, Part, Request,
+    RequestBuilder, RequestDiff, TlsInfo,
+};
+pub use response::Response;
+pub use router::{Candidate, Router};
+#[cfg(all(feature = "server", feature = "serde"))]
+pub use server::Mapping;
+#[cfg(feature = "server")]
+pub use server::{
+    Chunking, Delay, EventStream, Journal, MockServer, NearMiss, Proxy, ProxyError, ProxyMode,
+    Received, Scenario, SseEvent, Stub, StubId, Times, VerificationError,
+};
+#[cfg(feature = "record")]
+pub use server::{MappingFormat, Recording};
+#[cfg(feature = "tls")]
+pub use server::{TlsConfig, TlsError};
+#[cfg(feature = "websocket")]
+pub use server::{WebSocket, WsExpectation, WsMessage};
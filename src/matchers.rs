@@ -1,23 +1,313 @@
-use crate::request::Request;
+use crate::request::{HttpVersion, Request};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use hmac::{Hmac, KeyInit, Mac};
+use regex::Regex;
+use regex::bytes::Regex as BytesRegex;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
+mod builder;
+mod capture;
+mod compose;
+mod consistency;
+mod coverage;
+mod dsl;
+#[cfg(feature = "compression")]
+mod encoding;
+mod explain;
+#[cfg(feature = "graphql")]
+mod graphql;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "openapi")]
+mod openapi;
+#[cfg(feature = "pact")]
+mod pact;
+#[cfg(feature = "protobuf")]
+mod protobuf;
+mod report;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "xml")]
+mod xml;
+
+pub use capture::CaptureOptions;
+pub use consistency::Conflict;
+pub use coverage::{Coverage, MatcherCoverage};
+pub use dsl::DslError;
+#[cfg(feature = "openapi")]
+pub use openapi::OpenApiError;
+#[cfg(feature = "pact")]
+pub use pact::{PactError, PactInteraction, PactResponse};
+pub use report::{MatchScore, Mismatch, MismatchReport, Reason};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum HashAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl HashAlgorithm {
+    /// Returns the lowercase hex digest of `data`.
+    pub fn hex_digest(&self, data: &[u8]) -> String {
+        let digest = match self {
+            HashAlgorithm::Sha256 => Sha256::digest(data).to_vec(),
+            HashAlgorithm::Sha384 => Sha384::digest(data).to_vec(),
+            HashAlgorithm::Sha512 => Sha512::digest(data).to_vec(),
+        };
+
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    /// Returns the lowercase hex HMAC of `data` keyed with `secret`.
+    pub fn hex_hmac(&self, secret: &[u8], data: &[u8]) -> String {
+        fn mac<D: Mac + KeyInit>(secret: &[u8], data: &[u8]) -> Vec<u8> {
+            let mut mac =
+                <D as KeyInit>::new_from_slice(secret).expect("HMAC accepts any key length");
+            mac.update(data);
+            mac.finalize().into_bytes().to_vec()
+        }
+
+        let digest = match self {
+            HashAlgorithm::Sha256 => mac::<Hmac<Sha256>>(secret, data),
+            HashAlgorithm::Sha384 => mac::<Hmac<Sha384>>(secret, data),
+            HashAlgorithm::Sha512 => mac::<Hmac<Sha512>>(secret, data),
+        };
+
+        digest.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            HashAlgorithm::Sha256 => "sha256",
+            HashAlgorithm::Sha384 => "sha384",
+            HashAlgorithm::Sha512 => "sha512",
+        }
+    }
+}
+
+/// Shapes a value can be asserted to have without pinning the exact value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum ValueFormat {
+    /// A hyphenated UUID such as `123e4567-e89b-12d3-a456-426614174000`.
+    Uuid,
+    /// A base-10 integer with an optional sign.
+    Integer,
+    /// A calendar date in `YYYY-MM-DD` form.
+    Date,
+}
+
+impl ValueFormat {
+    /// Resolves the format name used in path templates, e.g. `int` in `/users/{id:int}`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "uuid" => Some(ValueFormat::Uuid),
+            "int" | "integer" => Some(ValueFormat::Integer),
+            "date" => Some(ValueFormat::Date),
+            _ => None,
+        }
+    }
+
+    pub fn is_valid(&self, value: &str) -> bool {
+        match self {
+            ValueFormat::Uuid => {
+                value.len() == 36
+                    && value.char_indices().all(|(idx, c)| match idx {
+                        8 | 13 | 18 | 23 => c == '-',
+                        _ => c.is_ascii_hexdigit(),
+                    })
+            }
+            ValueFormat::Integer => {
+                let digits = value.strip_prefix(['-', '+']).unwrap_or(value);
+                !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+            }
+            ValueFormat::Date => {
+                let mut parts = value.splitn(3, '-');
+                let (Some(year), Some(month), Some(day)) =
+                    (parts.next(), parts.next(), parts.next())
+                else {
+                    return false;
+                };
+                if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+                    return false;
+                }
+                let (Ok(year), Ok(month), Ok(day)) = (
+                    year.parse::<u32>(),
+                    month.parse::<u32>(),
+                    day.parse::<u32>(),
+                ) else {
+                    return false;
+                };
+                let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+                let days_in_month = match month {
+                    1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+                    4 | 6 | 9 | 11 => 30,
+                    2 if leap => 29,
+                    2 => 28,
+                    _ => return false,
+                };
+                (1..=days_in_month).contains(&day)
+            }
+        }
+    }
+}
+
+/// With the `serde` feature, variants are externally tagged in `snake_case`, e.g.
+/// `{"query_eq": ["page", "2"]}`, and times are written as HTTP dates.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
 pub enum Matcher {
     Method(String),
+    MethodAny(Vec<String>),
+    Scheme(String),
+    /// The request has no scheme, e.g. an origin-form request without `Host` details.
+    SchemeMiss,
+    Host(String),
+    Port(u16),
+    /// Neither the request nor its scheme gives a port.
+    PortMiss,
+    Version(HttpVersion),
     Path(String),
+    PathRegex(String),
+    PathTemplate(String),
+    PathPrefix(String),
+    PathSuffix(String),
     QueryExists(String),
     QueryMiss(String),
     QueryEq(String, String),
+    QueryContainsValue(String, String),
+    QueryEqAll(String, Vec<String>),
+    QueryFormat(String, ValueFormat),
     FragmentEq(String),
     FragmentMiss,
     HeaderExists(String),
     HeaderMiss(String),
+    /// At least one value of the header equals the given one.
     HeaderEq(String, String),
+    /// The header carries exactly these values, in order.
+    HeaderEqAll(String, Vec<String>),
+    CookieExists(String),
+    CookieMiss(String),
+    CookieEq(String, String),
+    BearerToken(String),
+    BasicAuth(String, String),
+    ContentType(String),
+    AcceptsLanguage(String),
+    PrefersLanguage(String),
+    /// A `Range: bytes=start-end` request; `(Some(500), None)` is open-ended and
+    /// `(None, Some(500))` asks for the last 500 bytes.
+    Range(Option<u64>, Option<u64>),
+    /// `If-None-Match` lists a tag weakly equal to the given ETag (or `*`).
+    IfNoneMatch(String),
+    /// `If-Match` lists a tag strongly equal to the given ETag (or `*`).
+    IfMatch(String),
+    /// `If-Modified-Since` is at or after the given last-modified time, so a server would
+    /// answer `304 Not Modified`.
+    IfModifiedSince(
+        #[cfg_attr(feature = "serde", serde(with = "serialize::http_date"))] SystemTime,
+    ),
+    /// `If-Unmodified-Since` is at or after the given last-modified time, so the
+    /// precondition holds.
+    IfUnmodifiedSince(
+        #[cfg_attr(feature = "serde", serde(with = "serialize::http_date"))] SystemTime,
+    ),
+    /// A `Cache-Control` directive, optionally with a value, e.g. `("max-age", Some("60"))`.
+    CacheControl(String, Option<String>),
+    CacheControlMiss(String),
+    BodyExists,
     BodyMiss,
     BodyEq(String),
+    BodyBytesEq(Vec<u8>),
+    BodyBase64Eq(String),
+    BodyHash(HashAlgorithm, String),
+    /// The header (e.g. `X-Hub-Signature-256`) carries the HMAC of the body under the shared
+    /// secret, as bare hex or prefixed with the algorithm like `sha256=<hex>`.
+    HmacSignature(String, HashAlgorithm, String),
+    BodyContains(String),
+    BodyRegex(String),
+    FormFieldExists(String),
+    FormFieldMiss(String),
+    FormFieldEq(String, String),
+    Not(Box<Matcher>),
+    /// Attaches a human-readable label to a matcher; its reports carry the same label.
+    Named(String, Box<Matcher>),
+    AnyOf(Vec<Matcher>),
+    AllOf(Vec<Matcher>),
+    #[cfg(feature = "json")]
+    BodyJsonEq(serde_json::Value),
+    #[cfg(feature = "json")]
+    BodyJsonIncludes(serde_json::Value),
+    #[cfg(feature = "json")]
+    BodyJsonPointer(String, Option<serde_json::Value>),
+    #[cfg(feature = "json")]
+    JsonPath(String, Option<serde_json::Value>),
+    #[cfg(feature = "json")]
+    JsonPathMiss(String),
+    #[cfg(feature = "json-schema")]
+    BodyJsonSchema(serde_json::Value),
+    #[cfg(feature = "graphql")]
+    #[cfg_attr(feature = "serde", serde(rename = "graphql_operation"))]
+    GraphQLOperation(String),
+    /// The GraphQL document, compared after normalizing formatting and comments.
+    #[cfg(feature = "graphql")]
+    #[cfg_attr(feature = "serde", serde(rename = "graphql_query"))]
+    GraphQLQuery(String),
+    #[cfg(feature = "graphql")]
+    #[cfg_attr(feature = "serde", serde(rename = "graphql_variables_eq"))]
+    GraphQLVariablesEq(serde_json::Value),
+    #[cfg(feature = "graphql")]
+    #[cfg_attr(feature = "serde", serde(rename = "graphql_variables_includes"))]
+    GraphQLVariablesIncludes(serde_json::Value),
+    /// The body decodes to this message; fields are compared after decoding, so wire order
+    /// doesn't matter.
+    /// Not serializable: decoding needs a descriptor pool the format can't carry.
+    #[cfg(feature = "protobuf")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    BodyProtobuf(prost_reflect::DynamicMessage),
+    #[cfg(feature = "xml")]
+    BodyXmlEq(String),
+    #[cfg(feature = "xml")]
+    #[cfg_attr(feature = "serde", serde(rename = "body_xpath"))]
+    BodyXPath(String, Option<String>),
+    #[cfg(feature = "xml")]
+    #[cfg_attr(feature = "serde", serde(rename = "body_xpath_miss"))]
+    BodyXPathMiss(String),
 }
 
 impl Matcher {
-    pub fn validate(&self, request: &Request) -> Option<Matcher> {
+    /// Checks `request`, returning a matcher describing what it actually had on mismatch.
+    ///
+    /// With the `compression` feature, bodies are decoded per `Content-Encoding` first.
+    pub(crate) fn observed(&self, request: &Request) -> Option<Matcher> {
+        #[cfg(feature = "compression")]
+        if let Some(decoded) = encoding::decode(request) {
+            return self.check(&decoded);
+        }
+
+        self.check(request)
+    }
+
+    fn check(&self, request: &Request) -> Option<Matcher> {
         match self {
             Matcher::Method(expected)
                 if request.method.to_uppercase() != expected.to_uppercase() =>
@@ -25,32 +315,234 @@ impl Matcher {
                 Some(Matcher::Method(request.method.clone()))
             }
 
+            Matcher::MethodAny(allowed)
+                if !allowed
+                    .iter()
+                    .any(|method| method.eq_ignore_ascii_case(&request.method)) =>
+            {
+                Some(Matcher::Method(request.method.clone()))
+            }
+            Matcher::Scheme(expected) => match &request.scheme {
+                Some(actual) if actual.eq_ignore_ascii_case(expected) => None,
+                Some(actual) => Some(Matcher::Scheme(actual.clone())),
+                None => Some(Matcher::SchemeMiss),
+            },
+            Matcher::SchemeMiss => request.scheme.clone().map(Matcher::Scheme),
+            Matcher::Host(expected) => match request.effective_host() {
+                Some(actual) if actual.eq_ignore_ascii_case(expected) => None,
+                Some(actual) => Some(Matcher::Host(actual.to_string())),
+                None => Some(Matcher::HeaderMiss("Host".into())),
+            },
+            Matcher::Port(expected) => match request.effective_port() {
+                Some(actual) if actual == *expected => None,
+                Some(actual) => Some(Matcher::Port(actual)),
+                None => Some(Matcher::PortMiss),
+            },
+            Matcher::PortMiss => request.effective_port().map(Matcher::Port),
+            Matcher::Version(expected) if request.http_version != *expected => {
+                Some(Matcher::Version(request.http_version))
+            }
             Matcher::Path(expected) if &request.path != expected => {
                 Some(Matcher::Path(request.path.clone()))
             }
+            Matcher::PathRegex(pattern) => match Regex::new(pattern) {
+                Ok(re) if re.is_match(&request.path) => None,
+                _ => Some(Matcher::Path(request.path.clone())),
+            },
+            Matcher::PathTemplate(template)
+                if template_captures(template, &request.path).is_none() =>
+            {
+                Some(Matcher::Path(request.path.clone()))
+            }
+            Matcher::PathPrefix(prefix) if !request.path.starts_with(prefix.as_str()) => {
+                Some(Matcher::Path(request.path.clone()))
+            }
+            Matcher::PathSuffix(suffix) if !request.path.ends_with(suffix.as_str()) => {
+                Some(Matcher::Path(request.path.clone()))
+            }
             Matcher::QueryEq(key, expected_val) => match request.query.get(key) {
-                Some(Some(actual_val)) if actual_val == expected_val => None,
-                Some(Some(actual_val)) => Some(Matcher::QueryEq(key.clone(), actual_val.clone())),
-                Some(None) => Some(Matcher::QueryExists(key.clone())),
+                Some(values) if values.len() == 1 && values[0].as_ref() == Some(expected_val) => {
+                    None
+                }
+                Some(values) => Some(query_report(key, values)),
+                None => Some(Matcher::QueryMiss(key.clone())),
+            },
+            Matcher::QueryContainsValue(key, expected_val) => match request.query.get(key) {
+                Some(values) if values.contains(&Some(expected_val.clone())) => None,
+                Some(values) => Some(query_report(key, values)),
+                None => Some(Matcher::QueryMiss(key.clone())),
+            },
+            Matcher::QueryEqAll(key, expected_vals) => match request.query.get(key) {
+                Some(values)
+                    if values.len() == expected_vals.len()
+                        && values
+                            .iter()
+                            .zip(expected_vals)
+                            .all(|(actual, expected)| actual.as_ref() == Some(expected)) =>
+                {
+                    None
+                }
+                Some(values) => Some(query_report(key, values)),
+                None => Some(Matcher::QueryMiss(key.clone())),
+            },
+            Matcher::QueryFormat(key, format) => match request.query.get(key) {
+                Some(values)
+                    if values
+                        .iter()
+                        .all(|v| v.as_deref().is_some_and(|v| format.is_valid(v))) =>
+                {
+                    None
+                }
+                Some(values) => Some(query_report(key, values)),
                 None => Some(Matcher::QueryMiss(key.clone())),
             },
             Matcher::QueryExists(key) if !request.query.contains_key(key) => {
                 Some(Matcher::QueryMiss(key.clone()))
             }
-            Matcher::QueryMiss(key) if request.query.contains_key(key) => {
-                Some(Matcher::QueryExists(key.clone()))
+            Matcher::QueryMiss(key) => request
+                .query
+                .get(key)
+                .map(|values| query_report(key, values)),
+            Matcher::HeaderEq(key, expected_val) => match &*header_values(request, key) {
+                [] => Some(Matcher::HeaderMiss(key.clone())),
+                values if values.contains(expected_val) => None,
+                values => Some(header_report(key, values)),
+            },
+            Matcher::HeaderEqAll(key, expected_vals) => match &*header_values(request, key) {
+                [] => Some(Matcher::HeaderMiss(key.clone())),
+                values if values == expected_vals.as_slice() => None,
+                values => Some(header_report(key, values)),
+            },
+            Matcher::HeaderExists(key) if header_values(request, key).is_empty() => {
+                Some(Matcher::HeaderMiss(key.clone()))
             }
-            Matcher::HeaderEq(key, expected_val) => match request.headers.get(key) {
+            Matcher::HeaderMiss(key) => match &*header_values(request, key) {
+                [] => None,
+                values => Some(header_report(key, values)),
+            },
+            Matcher::CookieEq(name, expected_val) => match request.cookie(name) {
                 Some(actual_val) if actual_val == expected_val => None,
-                Some(actual_val) => Some(Matcher::HeaderEq(key.clone(), actual_val.clone())),
-                None => Some(Matcher::HeaderMiss(key.clone())),
+                Some(actual_val) => Some(Matcher::CookieEq(name.clone(), actual_val.to_string())),
+                None => Some(Matcher::CookieMiss(name.clone())),
             },
-            Matcher::HeaderExists(key) if !request.headers.contains_key(key) => {
-                Some(Matcher::HeaderMiss(key.clone()))
+            Matcher::CookieExists(name) if request.cookie(name).is_none() => {
+                Some(Matcher::CookieMiss(name.clone()))
+            }
+            Matcher::CookieMiss(name) if request.cookie(name).is_some() => {
+                Some(Matcher::CookieExists(name.clone()))
             }
-            Matcher::HeaderMiss(key) if request.headers.contains_key(key) => {
-                Some(Matcher::HeaderExists(key.clone()))
+            Matcher::BearerToken(_) | Matcher::BasicAuth(_, _) => match authorization(request) {
+                Some(actual) if &actual == self => None,
+                Some(actual) => Some(actual),
+                None => Some(Matcher::HeaderMiss("Authorization".into())),
+            },
+            Matcher::ContentType(expected) => match request.header("Content-Type") {
+                Some(actual) if media_type_matches(expected, actual) => None,
+                Some(actual) => Some(Matcher::ContentType(actual.clone())),
+                None => Some(Matcher::HeaderMiss("Content-Type".into())),
+            },
+            Matcher::AcceptsLanguage(tag) | Matcher::PrefersLanguage(tag) => {
+                let Some(header) = request.header("Accept-Language") else {
+                    return Some(Matcher::HeaderMiss("Accept-Language".into()));
+                };
+                let ranges = language_ranges(header);
+                let satisfied = match self {
+                    Matcher::PrefersLanguage(_) => ranges
+                        .first()
+                        .is_some_and(|(range, q)| *q > 0.0 && language_matches(range, tag)),
+                    _ => ranges
+                        .iter()
+                        .filter(|(range, _)| language_matches(range, tag))
+                        .max_by_key(|(range, _)| range.len())
+                        .is_some_and(|(_, q)| *q > 0.0),
+                };
+
+                match ranges.first() {
+                    _ if satisfied => None,
+                    Some((range, _)) => Some(Matcher::PrefersLanguage(range.to_string())),
+                    None => Some(Matcher::HeaderEq("Accept-Language".into(), header.clone())),
+                }
             }
+            Matcher::Range(start, end) => {
+                let Some(header) = request.header("Range") else {
+                    return Some(Matcher::HeaderMiss("Range".into()));
+                };
+
+                match byte_ranges(header) {
+                    Some(ranges) if ranges.contains(&(*start, *end)) => None,
+                    Some(ranges) if !ranges.is_empty() => {
+                        Some(Matcher::Range(ranges[0].0, ranges[0].1))
+                    }
+                    _ => Some(Matcher::HeaderEq("Range".into(), header.clone())),
+                }
+            }
+            Matcher::IfNoneMatch(etag) | Matcher::IfMatch(etag) => {
+                let (name, strong) = match self {
+                    Matcher::IfMatch(_) => ("If-Match", true),
+                    _ => ("If-None-Match", false),
+                };
+                let Some(header) = request.header(name) else {
+                    return Some(Matcher::HeaderMiss(name.into()));
+                };
+                let expected = parse_etag(etag);
+                let tags: Vec<&str> = header.split(',').map(str::trim).collect();
+                let matched = tags.iter().any(|tag| {
+                    *tag == "*"
+                        || parse_etag(tag).1 == expected.1
+                            && (!strong || !expected.0 && !parse_etag(tag).0)
+                });
+
+                if matched {
+                    return None;
+                }
+
+                // Weak tags can never satisfy If-Match, so only report a tag that would.
+                let tag = tags[0].to_string();
+                Some(match self {
+                    Matcher::IfMatch(_) if !tag.is_empty() && !parse_etag(&tag).0 => {
+                        Matcher::IfMatch(tag)
+                    }
+                    Matcher::IfNoneMatch(_) if !tag.is_empty() => Matcher::IfNoneMatch(tag),
+                    _ => Matcher::HeaderEq(name.into(), header.clone()),
+                })
+            }
+            Matcher::IfModifiedSince(last_modified) | Matcher::IfUnmodifiedSince(last_modified) => {
+                let name = match self {
+                    Matcher::IfModifiedSince(_) => "If-Modified-Since",
+                    _ => "If-Unmodified-Since",
+                };
+                let Some(header) = request.header(name) else {
+                    return Some(Matcher::HeaderMiss(name.into()));
+                };
+
+                match parse_http_date(header) {
+                    Some(date) if date >= *last_modified => None,
+                    Some(date) => Some(match self {
+                        Matcher::IfModifiedSince(_) => Matcher::IfModifiedSince(date),
+                        _ => Matcher::IfUnmodifiedSince(date),
+                    }),
+                    None => Some(Matcher::HeaderEq(name.into(), header.clone())),
+                }
+            }
+            Matcher::CacheControl(name, expected) => {
+                let Some(header) = request.header("Cache-Control") else {
+                    return Some(Matcher::HeaderMiss("Cache-Control".into()));
+                };
+
+                match cache_directive(header, name) {
+                    Some(actual)
+                        if expected.is_none() || actual.as_deref() == expected.as_deref() =>
+                    {
+                        None
+                    }
+                    Some(actual) => Some(Matcher::CacheControl(name.clone(), actual)),
+                    None => Some(Matcher::CacheControlMiss(name.clone())),
+                }
+            }
+            Matcher::CacheControlMiss(name) => request
+                .header("Cache-Control")
+                .and_then(|header| cache_directive(header, name))
+                .map(|actual| Matcher::CacheControl(name.clone(), actual)),
             Matcher::FragmentEq(expected) => match &request.fragment {
                 Some(actual) if actual == expected => None,
                 Some(actual) => Some(Matcher::FragmentEq(actual.clone())),
@@ -59,45 +551,732 @@ impl Matcher {
             Matcher::FragmentMiss if request.fragment.is_some() => {
                 Some(Matcher::FragmentEq(request.fragment.clone().unwrap()))
             }
-            Matcher::BodyEq(expected) => match &request.body {
+            Matcher::BodyEq(expected) => match request.body.bytes().as_deref() {
+                Some(actual) if actual == expected.as_bytes() => None,
+                Some(actual) => Some(body_exact(actual)),
+                None => Some(Matcher::BodyMiss),
+            },
+            Matcher::BodyBytesEq(expected) => match request.body.bytes().as_deref() {
                 Some(actual) if actual == expected => None,
-                Some(actual) => Some(Matcher::BodyEq(actual.clone())),
+                Some(actual) => Some(Matcher::BodyBytesEq(actual.to_vec())),
+                None => Some(Matcher::BodyMiss),
+            },
+            Matcher::BodyBase64Eq(expected) => match request.body.bytes().as_deref() {
+                Some(actual) if BASE64.decode(expected).is_ok_and(|e| e == actual) => None,
+                Some(actual) => Some(Matcher::BodyBase64Eq(BASE64.encode(actual))),
                 None => Some(Matcher::BodyMiss),
             },
-            Matcher::BodyMiss if request.body.is_some() => {
-                Some(Matcher::BodyEq(request.body.clone().unwrap()))
+            Matcher::BodyHash(algorithm, expected) => match request.body.bytes().as_deref() {
+                Some(actual) => {
+                    let digest = algorithm.hex_digest(actual);
+                    if digest.eq_ignore_ascii_case(expected) {
+                        None
+                    } else {
+                        Some(Matcher::BodyHash(*algorithm, digest))
+                    }
+                }
+                None => Some(Matcher::BodyMiss),
+            },
+            Matcher::HmacSignature(header, algorithm, secret) => {
+                let Some(actual) = request.header(header) else {
+                    return Some(Matcher::HeaderMiss(header.clone()));
+                };
+                let signature = actual.trim();
+                let signature = signature
+                    .split_once('=')
+                    .filter(|(prefix, _)| prefix.eq_ignore_ascii_case(algorithm.name()))
+                    .map_or(signature, |(_, hex)| hex);
+                let expected = algorithm.hex_hmac(
+                    secret.as_bytes(),
+                    request.body.bytes().as_deref().unwrap_or_default(),
+                );
+
+                if signature.eq_ignore_ascii_case(&expected) {
+                    None
+                } else {
+                    Some(Matcher::HeaderEq(header.clone(), actual.clone()))
+                }
+            }
+            Matcher::BodyExists if request.body.is_empty() => Some(Matcher::BodyMiss),
+            Matcher::BodyMiss => request.body.bytes().as_deref().map(body_exact),
+            Matcher::BodyContains(needle) => match request.body.bytes().as_deref() {
+                Some(actual)
+                    if needle.is_empty()
+                        || actual
+                            .windows(needle.len())
+                            .any(|window| window == needle.as_bytes()) =>
+                {
+                    None
+                }
+                Some(actual) => Some(body_preview(actual)),
+                None => Some(Matcher::BodyMiss),
+            },
+            Matcher::BodyRegex(pattern) => {
+                match (request.body.bytes().as_deref(), BytesRegex::new(pattern)) {
+                    (Some(actual), Ok(re)) if re.is_match(actual) => None,
+                    (Some(actual), _) => Some(body_preview(actual)),
+                    (None, _) => Some(Matcher::BodyMiss),
+                }
+            }
+            Matcher::FormFieldEq(name, expected_val) => match form_field(request, name) {
+                Some(actual_val) if &actual_val == expected_val => None,
+                Some(actual_val) => Some(Matcher::FormFieldEq(name.clone(), actual_val)),
+                None => Some(Matcher::FormFieldMiss(name.clone())),
+            },
+            Matcher::FormFieldExists(name) if form_field(request, name).is_none() => {
+                Some(Matcher::FormFieldMiss(name.clone()))
+            }
+            Matcher::FormFieldMiss(name) if form_field(request, name).is_some() => {
+                Some(Matcher::FormFieldExists(name.clone()))
+            }
+            // The inner matcher itself is the report: it passed although it wasn't supposed to.
+            Matcher::Not(inner) => match inner.check(request) {
+                Some(_) => None,
+                None => Some(inner.as_ref().clone()),
+            },
+            Matcher::Named(label, inner) => inner
+                .check(request)
+                .map(|report| Matcher::Named(label.clone(), Box::new(report))),
+            Matcher::AnyOf(alternatives) => {
+                let mut nearest: Option<(usize, Matcher)> = None;
+                for alternative in alternatives {
+                    let report = alternative.check(request)?;
+                    let failures = alternative.failures(request);
+                    if nearest.as_ref().is_none_or(|(best, _)| failures < *best) {
+                        nearest = Some((failures, report));
+                    }
+                }
+
+                Some(nearest.map_or_else(|| self.clone(), |(_, report)| report))
+            }
+            Matcher::AllOf(matchers) => {
+                let mut reports: Vec<Matcher> = matchers
+                    .iter()
+                    .filter_map(|matcher| matcher.check(request))
+                    .collect();
+
+                match reports.len() {
+                    0 => None,
+                    1 => reports.pop(),
+                    _ => Some(Matcher::AllOf(reports)),
+                }
+            }
+            #[cfg(feature = "json")]
+            Matcher::BodyJsonEq(expected) => json::validate_eq(expected, request),
+            #[cfg(feature = "json")]
+            Matcher::BodyJsonIncludes(expected) => json::validate_includes(expected, request),
+            #[cfg(feature = "json")]
+            Matcher::BodyJsonPointer(pointer, expected) => {
+                json::validate_pointer(pointer, expected, request)
+            }
+            #[cfg(feature = "json")]
+            Matcher::JsonPath(path, expected) => json::validate_path(path, expected, request),
+            #[cfg(feature = "json")]
+            Matcher::JsonPathMiss(path) => json::validate_path_miss(path, request),
+            #[cfg(feature = "json-schema")]
+            Matcher::BodyJsonSchema(schema) => json::validate_schema(schema, request),
+            #[cfg(feature = "graphql")]
+            Matcher::GraphQLOperation(expected) => graphql::validate_operation(expected, request),
+            #[cfg(feature = "graphql")]
+            Matcher::GraphQLQuery(expected) => graphql::validate_query(expected, request),
+            #[cfg(feature = "graphql")]
+            Matcher::GraphQLVariablesEq(expected) => {
+                graphql::validate_variables(expected, true, request)
+            }
+            #[cfg(feature = "graphql")]
+            Matcher::GraphQLVariablesIncludes(expected) => {
+                graphql::validate_variables(expected, false, request)
             }
+            #[cfg(feature = "protobuf")]
+            Matcher::BodyProtobuf(expected) => protobuf::validate(expected, request),
+            #[cfg(feature = "xml")]
+            Matcher::BodyXmlEq(expected) => xml::validate_eq(expected, request),
+            #[cfg(feature = "xml")]
+            Matcher::BodyXPath(expr, expected) => xml::validate_xpath(expr, expected, request),
+            #[cfg(feature = "xml")]
+            Matcher::BodyXPathMiss(expr) => xml::validate_xpath_miss(expr, request),
+            _ => None,
+        }
+    }
+
+    /// How specific the matcher is when ranking sets, see [`Matchers::specificity`].
+    pub fn weight(&self) -> u32 {
+        match self {
+            Matcher::Path(_) => 4,
+            Matcher::PathTemplate(_) => 3,
+            Matcher::PathRegex(_) | Matcher::PathPrefix(_) | Matcher::PathSuffix(_) => 2,
+            Matcher::Named(_, inner) => inner.weight(),
+            Matcher::AllOf(matchers) => matchers.iter().map(Matcher::weight).sum(),
+            Matcher::AnyOf(alternatives) => {
+                alternatives.iter().map(Matcher::weight).min().unwrap_or(0)
+            }
+            _ => 1,
+        }
+    }
+
+    /// Labels `matcher` so failure reports can refer to it, e.g. `"auth header"`.
+    pub fn named<S: Into<String>>(label: S, matcher: Matcher) -> Matcher {
+        Matcher::Named(label.into(), Box::new(matcher))
+    }
+
+    /// Builds a [`Matcher::BodyProtobuf`] from a message generated with `prost-reflect` support.
+    #[cfg(feature = "protobuf")]
+    pub fn protobuf<M: prost_reflect::ReflectMessage>(message: &M) -> Matcher {
+        Matcher::BodyProtobuf(message.transcode_to_dynamic())
+    }
+
+    /// Counts the leaf expectations this matcher fails for `request`, used to rank alternatives.
+    fn failures(&self, request: &Request) -> usize {
+        match self {
+            Matcher::AllOf(matchers) => matchers.iter().map(|m| m.failures(request)).sum(),
+            Matcher::Named(_, inner) => inner.failures(request),
+            matcher if matcher.check(request).is_some() => 1,
+            _ => 0,
+        }
+    }
+
+    /// Returns the values captured by a [`Matcher::PathTemplate`] placeholder, keyed by name.
+    ///
+    /// `None` is returned for any other variant and for templates that don't match the request.
+    pub fn captures(&self, request: &Request) -> Option<HashMap<String, String>> {
+        match self {
+            Matcher::PathTemplate(template) => template_captures(template, &request.path),
+            Matcher::Named(_, inner) => inner.captures(request),
             _ => None,
         }
     }
+
+    /// Describes every JSON Schema constraint a [`Matcher::BodyJsonSchema`] finds violated.
+    ///
+    /// The validation result only points at the first failing location; this lists them all.
+    #[cfg(feature = "json-schema")]
+    pub fn schema_errors(&self, request: &Request) -> Vec<String> {
+        match self {
+            Matcher::BodyJsonSchema(schema) => {
+                #[cfg(feature = "compression")]
+                if let Some(decoded) = encoding::decode(request) {
+                    return json::schema_errors(schema, &decoded);
+                }
+
+                json::schema_errors(schema, request)
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+const BODY_PREVIEW_LEN: usize = 64;
+
+/// Cuts `body` down to a preview short enough for mismatch reports.
+fn truncate(body: &str) -> &str {
+    match body.char_indices().nth(BODY_PREVIEW_LEN) {
+        Some((idx, _)) => &body[..idx],
+        None => body,
+    }
+}
+
+/// Describes the values a query parameter actually had as precisely as a matcher can.
+fn query_report(key: &str, values: &[Option<String>]) -> Matcher {
+    let present: Option<Vec<String>> = values.iter().cloned().collect();
+    match present {
+        Some(mut values) if values.len() == 1 => Matcher::QueryEq(key.into(), values.remove(0)),
+        Some(values) if !values.is_empty() => Matcher::QueryEqAll(key.into(), values),
+        _ => Matcher::QueryExists(key.into()),
+    }
+}
+
+fn header_report(key: &str, values: &[String]) -> Matcher {
+    match values {
+        [value] => Matcher::HeaderEq(key.into(), value.clone()),
+        values => Matcher::HeaderEqAll(key.into(), values.to_vec()),
+    }
+}
+
+/// Describes an unexpected body exactly, as text when it is valid UTF-8.
+fn body_exact(body: &[u8]) -> Matcher {
+    match std::str::from_utf8(body) {
+        Ok(text) => Matcher::BodyEq(text.to_string()),
+        Err(_) => Matcher::BodyBytesEq(body.to_vec()),
+    }
+}
+
+/// Describes an unexpected body for a report: text is truncated, binary is kept as-is.
+fn body_preview(body: &[u8]) -> Matcher {
+    match std::str::from_utf8(body) {
+        Ok(text) => Matcher::BodyContains(truncate(text).to_string()),
+        Err(_) => Matcher::BodyBytesEq(body.to_vec()),
+    }
+}
+
+/// A header's values, reading HTTP/2 pseudo-headers such as `:authority` from the fields.
+fn header_values<'a>(request: &'a Request, name: &str) -> Cow<'a, [String]> {
+    match request.pseudo_header(name) {
+        Some(value) => Cow::Owned(vec![value]),
+        None => Cow::Borrowed(request.header_values(name)),
+    }
+}
+
+/// Decodes the first occurrence of a field in an `application/x-www-form-urlencoded` body.
+fn form_field(request: &Request, name: &str) -> Option<String> {
+    form_urlencoded::parse(request.body.bytes().as_deref()?)
+        .find(|(k, _)| k == name)
+        .map(|(_, v)| v.into_owned())
+}
+
+/// Parses the `Authorization` header into the matcher describing its credentials.
+///
+/// Unknown schemes and undecodable Basic credentials fall back to [`Matcher::HeaderEq`].
+fn authorization(request: &Request) -> Option<Matcher> {
+    let value = request.header("Authorization")?;
+    let (scheme, credentials) = value.trim().split_once(' ').unwrap_or((value, ""));
+    let credentials = credentials.trim();
+
+    let parsed = if scheme.eq_ignore_ascii_case("Bearer") {
+        Some(Matcher::BearerToken(credentials.to_string()))
+    } else if scheme.eq_ignore_ascii_case("Basic") {
+        BASE64
+            .decode(credentials)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .and_then(|decoded| {
+                decoded
+                    .split_once(':')
+                    .map(|(user, pass)| Matcher::BasicAuth(user.to_string(), pass.to_string()))
+            })
+    } else {
+        None
+    };
+
+    Some(parsed.unwrap_or_else(|| Matcher::HeaderEq("Authorization".into(), value.clone())))
+}
+
+/// Splits a media type into its lowercased `type/subtype` essence and its parameters.
+fn parse_media_type(value: &str) -> (String, Vec<(String, String)>) {
+    let mut parts = value.split(';');
+    let essence = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(k, v)| {
+            (
+                k.trim().to_ascii_lowercase(),
+                v.trim().trim_matches('"').to_string(),
+            )
+        })
+        .collect();
+
+    (essence, params)
+}
+
+/// Compares media types by essence; only the parameters present in `expected` are asserted.
+fn media_type_matches(expected: &str, actual: &str) -> bool {
+    let (expected_essence, expected_params) = parse_media_type(expected);
+    let (actual_essence, actual_params) = parse_media_type(actual);
+
+    expected_essence == actual_essence
+        && expected_params.iter().all(|(key, expected_val)| {
+            actual_params.iter().any(|(k, v)| {
+                k == key
+                    && if key == "charset" {
+                        v.eq_ignore_ascii_case(expected_val)
+                    } else {
+                        v == expected_val
+                    }
+            })
+        })
+}
+
+/// Parses `Accept-Language` into language ranges ordered from most to least preferred.
+fn language_ranges(header: &str) -> Vec<(&str, f32)> {
+    let mut ranges: Vec<(&str, f32)> = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let range = parts.next()?.trim();
+            let q = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse().ok())
+                .unwrap_or(1.0);
+            (!range.is_empty()).then_some((range, q))
+        })
+        .collect();
+    ranges.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    ranges
+}
+
+/// Basic filtering from RFC 4647: `en` covers `en-US`, and `*` covers everything.
+fn language_matches(range: &str, tag: &str) -> bool {
+    range == "*"
+        || range.eq_ignore_ascii_case(tag)
+        || tag.len() > range.len()
+            && tag.as_bytes()[range.len()] == b'-'
+            && tag[..range.len()].eq_ignore_ascii_case(range)
+}
+
+/// Parses `bytes=0-499, 1000-` into `(start, end)` pairs; `None` for other units or bad syntax.
+fn byte_ranges(header: &str) -> Option<Vec<(Option<u64>, Option<u64>)>> {
+    let (unit, ranges) = header.trim().split_once('=')?;
+    if !unit.trim().eq_ignore_ascii_case("bytes") {
+        return None;
+    }
+
+    ranges
+        .split(',')
+        .map(|range| {
+            let (start, end) = range.trim().split_once('-')?;
+            let parse = |bound: &str| match bound.trim() {
+                "" => Some(None),
+                bound => bound.parse().ok().map(Some),
+            };
+            match (parse(start)?, parse(end)?) {
+                (None, None) => None,
+                (Some(start), Some(end)) if start > end => None,
+                bounds => Some(bounds),
+            }
+        })
+        .collect()
+}
+
+/// Finds a `Cache-Control` directive by name, returning its unquoted value if it has one.
+fn cache_directive(header: &str, name: &str) -> Option<Option<String>> {
+    header.split(',').find_map(|directive| {
+        let (key, value) = match directive.split_once('=') {
+            Some((key, value)) => (key, Some(value.trim().trim_matches('"').to_string())),
+            None => (directive, None),
+        };
+        key.trim().eq_ignore_ascii_case(name).then_some(value)
+    })
+}
+
+/// Splits an entity tag into its weakness flag and opaque value; quotes are optional.
+fn parse_etag(tag: &str) -> (bool, &str) {
+    let tag = tag.trim();
+    let (weak, opaque) = match tag.strip_prefix("W/") {
+        Some(opaque) => (true, opaque),
+        None => (false, tag),
+    };
+    (weak, opaque.trim_matches('"'))
+}
+
+/// Parses an IMF-fixdate such as `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let (_, date) = value.trim().split_once(", ")?;
+    let parts: Vec<&str> = date.split_whitespace().collect();
+    let [day, month, year, time, "GMT"] = parts.as_slice() else {
+        return None;
+    };
+
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|m| m == month)? as i64
+        + 1;
+    let day: i64 = day.parse().ok()?;
+    let year: i64 = year.parse().ok()?;
+    let mut clock = time.split(':').map(|part| part.parse::<i64>().ok());
+    let (Some(Some(hour)), Some(Some(minute)), Some(Some(second)), None) =
+        (clock.next(), clock.next(), clock.next(), clock.next())
+    else {
+        return None;
+    };
+
+    // Days since the Unix epoch for a proleptic Gregorian date (Howard Hinnant's algorithm).
+    let (y, m) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * m + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    let secs = days * 86_400 + hour * 3_600 + minute * 60 + second;
+    Some(SystemTime::UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+}
+
+fn template_captures(template: &str, path: &str) -> Option<HashMap<String, String>> {
+    let mut template_segments = template.split('/');
+    let mut path_segments = path.split('/');
+    let mut captures = HashMap::new();
+
+    loop {
+        match (template_segments.next(), path_segments.next()) {
+            (None, None) => return Some(captures),
+            (Some(expected), Some(actual)) => {
+                match expected.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                    Some(_) if actual.is_empty() => return None,
+                    Some(placeholder) => {
+                        let (name, format) = match placeholder.split_once(':') {
+                            Some((name, format)) => (name, Some(ValueFormat::from_name(format)?)),
+                            None => (placeholder, None),
+                        };
+                        if format.is_some_and(|format| !format.is_valid(actual)) {
+                            return None;
+                        }
+                        captures.insert(name.to_string(), actual.to_string());
+                    }
+                    None if expected != actual => return None,
+                    None => {}
+                }
+            }
+            _ => return None,
+        }
+    }
 }
 
+/// An expectation on a [`Request`].
+///
+/// [`Matcher`] covers the built-in expectations; implement this trait to plug custom ones
+/// into [`Matchers`].
+pub trait Match: Debug + Send + Sync {
+    /// Returns `None` when `request` satisfies the expectation, or a description of what the
+    /// request looked like instead.
+    fn validate(&self, request: &Request) -> Option<Mismatch>;
+
+    /// Describes the expectation as a sentence for logs and failure messages.
+    fn explain(&self) -> String {
+        format!("{self:?}")
+    }
+
+    /// Exposes the built-in matcher behind this expectation, if any, for static analysis.
+    fn as_matcher(&self) -> Option<&Matcher> {
+        None
+    }
+
+    /// How specific the expectation is; [`Matchers::specificity`] sums these to rank sets.
+    fn weight(&self) -> u32 {
+        1
+    }
+}
+
+/// Overrides the [`Match::weight`] of a matcher.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weighted<M> {
+    matcher: M,
+    weight: u32,
+}
+
+impl<M> Weighted<M> {
+    pub fn new(matcher: M, weight: u32) -> Self {
+        Self { matcher, weight }
+    }
+}
+
+impl<M: Match> Match for Weighted<M> {
+    fn validate(&self, request: &Request) -> Option<Mismatch> {
+        self.matcher.validate(request)
+    }
+
+    fn explain(&self) -> String {
+        self.matcher.explain()
+    }
+
+    fn as_matcher(&self) -> Option<&Matcher> {
+        self.matcher.as_matcher()
+    }
+
+    fn weight(&self) -> u32 {
+        self.weight
+    }
+}
+
+impl Match for Matcher {
+    fn validate(&self, request: &Request) -> Option<Mismatch> {
+        self.observed(request)
+            .map(|observed| Mismatch::between(self, &observed))
+    }
+
+    fn explain(&self) -> String {
+        Matcher::explain(self)
+    }
+
+    fn as_matcher(&self) -> Option<&Matcher> {
+        Some(self)
+    }
+
+    fn weight(&self) -> u32 {
+        Matcher::weight(self)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct Matchers {
-    inner: Vec<Matcher>,
+    inner: Vec<Arc<dyn Match>>,
+    priority: i32,
+    normalize_paths: bool,
+    usage: coverage::Usage,
 }
 
 impl Matchers {
-    pub fn add(&mut self, matcher: Matcher) {}
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a built-in [`Matcher`] or any custom [`Match`] implementation.
+    pub fn add<M: Match + 'static>(&mut self, matcher: M) {
+        self.inner.push(Arc::new(matcher));
+    }
+
+    pub fn with<M: Match + 'static>(mut self, matcher: M) -> Self {
+        self.add(matcher);
+        self
+    }
+
+    /// Appends `matcher` counting `weight` towards [`Matchers::specificity`].
+    pub fn add_weighted<M: Match + 'static>(&mut self, matcher: M, weight: u32) {
+        self.add(Weighted::new(matcher, weight));
+    }
+
+    /// Explicit rank among sets matching the same request; higher wins before specificity
+    /// is considered. Defaults to `0`.
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: i32) {
+        self.priority = priority;
+    }
+
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.set_priority(priority);
+        self
+    }
+
+    /// Matches requests as if their path were normalized with [`Request::normalize_path`],
+    /// so `/a/b/../c` matches a set expecting `/a/c`.
+    pub fn with_path_normalization(mut self) -> Self {
+        self.normalize_paths = true;
+        self
+    }
+
+    fn prepare<'a>(&self, request: &'a Request) -> Cow<'a, Request> {
+        if self.normalize_paths {
+            Cow::Owned(request.clone().with_normalized_path())
+        } else {
+            Cow::Borrowed(request)
+        }
+    }
+
+    /// The summed weight of all matchers: exact paths outweigh templates, which outweigh
+    /// regexes and prefixes, and every further matcher adds to the total.
+    pub fn specificity(&self) -> u32 {
+        self.inner.iter().map(|matcher| matcher.weight()).sum()
+    }
 
     pub fn is_matched(&self, request: &Request) -> bool {
+        let request = &self.prepare(request);
         self.inner
             .iter()
-            .all(|matcher| matcher.validate(request).is_none())
+            .enumerate()
+            .all(|(index, matcher)| self.check_at(index, matcher.as_ref(), request).is_none())
     }
 
-    pub fn validate(&self, request: &Request) -> Option<Vec<Matcher>> {
-        let errors: Vec<Matcher> = self
+    pub fn validate(&self, request: &Request) -> Result<(), MismatchReport> {
+        let request = &self.prepare(request);
+        let mismatches: Vec<Mismatch> = self
             .inner
             .iter()
-            .filter_map(|matcher| matcher.validate(request))
+            .enumerate()
+            .filter_map(|(index, matcher)| self.check_at(index, matcher.as_ref(), request))
             .collect();
 
-        if errors.is_empty() {
-            None
+        if mismatches.is_empty() {
+            Ok(())
         } else {
-            Some(errors)
+            Err(MismatchReport::new(mismatches))
+        }
+    }
+
+    /// Measures how many of the matchers `request` satisfies, for ranking near misses.
+    pub fn score(&self, request: &Request) -> MatchScore {
+        let request = &self.prepare(request);
+        let satisfied = self
+            .inner
+            .iter()
+            .enumerate()
+            .filter(|(index, matcher)| self.check_at(*index, matcher.as_ref(), request).is_none())
+            .count();
+        MatchScore::new(satisfied, self.inner.len())
+    }
+
+    /// Like [`Matchers::validate`] but stops at the first failing matcher, for routing many
+    /// stubs where only the verdict matters.
+    pub fn validate_first(&self, request: &Request) -> Result<(), Mismatch> {
+        let request = &self.prepare(request);
+        match self
+            .inner
+            .iter()
+            .enumerate()
+            .find_map(|(index, matcher)| self.check_at(index, matcher.as_ref(), request))
+        {
+            Some(mismatch) => Err(mismatch),
+            None => Ok(()),
         }
     }
+
+    /// The values captured by every path template in the set, see [`Matcher::captures`].
+    pub fn captures(&self, request: &Request) -> HashMap<String, String> {
+        let request = &self.prepare(request);
+        self.inner
+            .iter()
+            .filter_map(|matcher| matcher.as_matcher()?.captures(request))
+            .flatten()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Arc<dyn Match>> {
+        self.inner.iter()
+    }
+}
+
+impl<M: Match + 'static> From<Vec<M>> for Matchers {
+    fn from(value: Vec<M>) -> Self {
+        value.into_iter().collect()
+    }
+}
+
+impl<M: Match + 'static> FromIterator<M> for Matchers {
+    fn from_iter<I: IntoIterator<Item = M>>(iter: I) -> Self {
+        let mut matchers = Matchers::new();
+        matchers.extend(iter);
+        matchers
+    }
+}
+
+impl<M: Match + 'static> Extend<M> for Matchers {
+    fn extend<I: IntoIterator<Item = M>>(&mut self, iter: I) {
+        for matcher in iter {
+            self.add(matcher);
+        }
+    }
+}
+
+impl IntoIterator for Matchers {
+    type Item = Arc<dyn Match>;
+    type IntoIter = std::vec::IntoIter<Arc<dyn Match>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.inner.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Matchers {
+    type Item = &'a Arc<dyn Match>;
+    type IntoIter = std::slice::Iter<'a, Arc<dyn Match>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
 }
 
 #[cfg(test)]
@@ -115,18 +1294,62 @@ mod test {
     // #[case::method(method("POST"), method("GET"), ...)]  // instead of
     // #[case::method(Matcher::Method("POST".into()), Matcher::Method("GET".into()), ...)]
 
+    fn matchers(inner: &[Matcher]) -> Matchers {
+        inner.iter().cloned().collect()
+    }
+
     fn method(method: &str) -> Matcher {
         Matcher::Method(method.into())
     }
 
+    fn method_any(methods: &[&str]) -> Matcher {
+        Matcher::MethodAny(methods.iter().map(|m| m.to_string()).collect())
+    }
+
+    fn scheme(scheme: &str) -> Matcher {
+        Matcher::Scheme(scheme.into())
+    }
+
+    fn host(host: &str) -> Matcher {
+        Matcher::Host(host.into())
+    }
+
     fn path(path: &str) -> Matcher {
         Matcher::Path(path.into())
     }
 
+    fn path_re(pattern: &str) -> Matcher {
+        Matcher::PathRegex(pattern.into())
+    }
+
+    fn path_tpl(template: &str) -> Matcher {
+        Matcher::PathTemplate(template.into())
+    }
+
+    fn path_prefix(prefix: &str) -> Matcher {
+        Matcher::PathPrefix(prefix.into())
+    }
+
+    fn path_suffix(suffix: &str) -> Matcher {
+        Matcher::PathSuffix(suffix.into())
+    }
+
     fn q_eq(key: &str, val: &str) -> Matcher {
         Matcher::QueryEq(key.into(), val.into())
     }
 
+    fn q_contains(key: &str, val: &str) -> Matcher {
+        Matcher::QueryContainsValue(key.into(), val.into())
+    }
+
+    fn q_all(key: &str, vals: &[&str]) -> Matcher {
+        Matcher::QueryEqAll(key.into(), vals.iter().map(|v| v.to_string()).collect())
+    }
+
+    fn q_fmt(key: &str, format: ValueFormat) -> Matcher {
+        Matcher::QueryFormat(key.into(), format)
+    }
+
     fn q_ex(key: &str) -> Matcher {
         Matcher::QueryExists(key.into())
     }
@@ -139,6 +1362,10 @@ mod test {
         Matcher::HeaderEq(key.into(), val.into())
     }
 
+    fn h_eq_all(key: &str, vals: &[&str]) -> Matcher {
+        Matcher::HeaderEqAll(key.into(), vals.iter().map(|v| v.to_string()).collect())
+    }
+
     fn h_ex(key: &str) -> Matcher {
         Matcher::HeaderExists(key.into())
     }
@@ -147,6 +1374,99 @@ mod test {
         Matcher::HeaderMiss(key.into())
     }
 
+    fn c_eq(name: &str, val: &str) -> Matcher {
+        Matcher::CookieEq(name.into(), val.into())
+    }
+
+    fn c_ex(name: &str) -> Matcher {
+        Matcher::CookieExists(name.into())
+    }
+
+    fn c_miss(name: &str) -> Matcher {
+        Matcher::CookieMiss(name.into())
+    }
+
+    fn form_eq(name: &str, val: &str) -> Matcher {
+        Matcher::FormFieldEq(name.into(), val.into())
+    }
+
+    fn form_ex(name: &str) -> Matcher {
+        Matcher::FormFieldExists(name.into())
+    }
+
+    fn form_miss(name: &str) -> Matcher {
+        Matcher::FormFieldMiss(name.into())
+    }
+
+    fn not(matcher: Matcher) -> Matcher {
+        Matcher::Not(Box::new(matcher))
+    }
+
+    fn any_of(matchers: &[Matcher]) -> Matcher {
+        Matcher::AnyOf(matchers.to_vec())
+    }
+
+    fn all_of(matchers: &[Matcher]) -> Matcher {
+        Matcher::AllOf(matchers.to_vec())
+    }
+
+    fn bearer(token: &str) -> Matcher {
+        Matcher::BearerToken(token.into())
+    }
+
+    fn basic(user: &str, pass: &str) -> Matcher {
+        Matcher::BasicAuth(user.into(), pass.into())
+    }
+
+    fn auth(value: &str) -> Request {
+        Request::default().with_header("Authorization", value)
+    }
+
+    fn c_type(media_type: &str) -> Matcher {
+        Matcher::ContentType(media_type.into())
+    }
+
+    fn with_c_type(value: &str) -> Request {
+        Request::default().with_header("Content-Type", value)
+    }
+
+    fn accepts_lang(tag: &str) -> Matcher {
+        Matcher::AcceptsLanguage(tag.into())
+    }
+
+    fn prefers_lang(tag: &str) -> Matcher {
+        Matcher::PrefersLanguage(tag.into())
+    }
+
+    fn with_lang(value: &str) -> Request {
+        Request::default().with_header("Accept-Language", value)
+    }
+
+    fn with_range(value: &str) -> Request {
+        Request::default().with_header("Range", value)
+    }
+
+    fn if_none_match(etag: &str) -> Matcher {
+        Matcher::IfNoneMatch(etag.into())
+    }
+
+    fn if_match(etag: &str) -> Matcher {
+        Matcher::IfMatch(etag.into())
+    }
+
+    /// `Sun, 06 Nov 1994 08:49:37 GMT`
+    fn nov_1994() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(784_111_777)
+    }
+
+    fn cache(name: &str, value: Option<&str>) -> Matcher {
+        Matcher::CacheControl(name.into(), value.map(String::from))
+    }
+
+    fn with_cache(value: &str) -> Request {
+        Request::default().with_header("Cache-Control", value)
+    }
+
     fn f_eq(fragment: &str) -> Matcher {
         Matcher::FragmentEq(fragment.into())
     }
@@ -163,33 +1483,248 @@ mod test {
         Matcher::BodyMiss
     }
 
+    fn b_exists() -> Matcher {
+        Matcher::BodyExists
+    }
+
+    fn b_bytes(body: &[u8]) -> Matcher {
+        Matcher::BodyBytesEq(body.to_vec())
+    }
+
+    fn b_base64(body: &str) -> Matcher {
+        Matcher::BodyBase64Eq(body.into())
+    }
+
+    fn b_hash(algorithm: HashAlgorithm, digest: &str) -> Matcher {
+        Matcher::BodyHash(algorithm, digest.into())
+    }
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    fn hmac(secret: &str) -> Matcher {
+        Matcher::HmacSignature(
+            "X-Hub-Signature-256".into(),
+            HashAlgorithm::Sha256,
+            secret.into(),
+        )
+    }
+
+    fn with_signature(signature: &str) -> Request {
+        Request::default()
+            .with_body("The quick brown fox jumps over the lazy dog")
+            .with_header("X-Hub-Signature-256", signature)
+    }
+
+    const FOX_HMAC: &str = "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8";
+
+    fn b_contains(needle: &str) -> Matcher {
+        Matcher::BodyContains(needle.into())
+    }
+
+    fn b_re(pattern: &str) -> Matcher {
+        Matcher::BodyRegex(pattern.into())
+    }
+
     #[rstest]
     #[case::method(method("post"), method("GET"), Request::default())]
     #[case::method(method("PUT"), method("POST"), Request::default().with_method("POST"))]
+    #[case::method_any(method_any(&["GET", "HEAD"]), method("POST"), Request::default().with_method("POST"))]
+    #[case::method_any(method_any(&[]), method("GET"), Request::default())]
+    #[case::scheme(scheme("https"), scheme("http"), Request::default().with_scheme("http"))]
+    #[case::scheme(scheme("https"), Matcher::SchemeMiss, Request::default())]
+    #[case::host(host("api.example.com"), host("example.com"), Request::default().with_host("example.com"))]
+    #[case::host(host("api.example.com"), host("admin.example.com"), Request::default().with_header("Host", "admin.example.com:8080"))]
+    #[case::host(host("api.example.com"), h_miss("Host"), Request::default())]
+    #[case::port(Matcher::Port(8443), Matcher::Port(443), Request::default().with_scheme("https").with_host("example.com"))]
+    #[case::port(Matcher::Port(80), Matcher::PortMiss, Request::default())]
+    #[case::version(
+        Matcher::Version(HttpVersion::Http2),
+        Matcher::Version(HttpVersion::Http11),
+        Request::default()
+    )]
+    #[case::version(Matcher::Version(HttpVersion::Http11), Matcher::Version(HttpVersion::Http10), Request::default().with_http_version(HttpVersion::Http10))]
     #[case::path(path("/invalid/path"), path("/some/path"), "/some/path".into())]
     #[case::path(path("/some"), path("/"), Request::default())]
+    #[case::path_regex(path_re(r"^/users/\d+$"), path("/users/abc"), "/users/abc".into())]
+    #[case::path_regex(path_re("(unclosed"), path("/users/1"), "/users/1".into())]
+    #[case::path_template(path_tpl("/users/{id}"), path("/users/1/posts"), "/users/1/posts".into())]
+    #[case::path_template(path_tpl("/users/{id}/posts"), path("/users//posts"), "/users//posts".into())]
+    #[case::path_template(path_tpl("/users/{id}"), path("/posts/1"), "/posts/1".into())]
+    #[case::path_template_format(path_tpl("/users/{id:int}"), path("/users/abc"), "/users/abc".into())]
+    #[case::path_template_format(path_tpl("/orders/{id:uuid}"), path("/orders/42"), "/orders/42".into())]
+    #[case::path_template_format(path_tpl("/reports/{day:date}"), path("/reports/2024-02-30"), "/reports/2024-02-30".into())]
+    #[case::path_template_format(path_tpl("/users/{id:unknown}"), path("/users/1"), "/users/1".into())]
+    #[case::path_prefix(path_prefix("/api/v1/"), path("/api/v2/users"), "/api/v2/users".into())]
+    #[case::path_suffix(path_suffix(".json"), path("/report.xml"), "/report.xml".into())]
     #[case::query(q_eq("q_key", "q2_val"), q_eq("q_key", "q_val"), "/?q_key=q_val".into())]
-    #[case::query(q_miss("q_key"), q_ex("q_key"), "/?q_key=q_val".into())]
+    #[case::query(q_miss("q_key"), q_eq("q_key", "q_val"), "/?q_key=q_val".into())]
     #[case::query(q_ex("miss_key"), q_miss("miss_key"), "/?q_key=q_val".into())]
     #[case::query(q_eq("miss_key", "some_val"), q_miss("miss_key"), "/?q_key=q_val".into())]
     #[case::query(q_miss("exists_key"), q_ex("exists_key"), "/?q_key=q_val&exists_key".into())]
+    #[case::query_repeated(q_eq("tag", "a"), q_all("tag", &["a", "b"]), "/?tag=a&tag=b".into())]
+    #[case::query_repeated(q_eq("tag", "a"), q_ex("tag"), "/?tag=a&tag".into())]
+    #[case::query_repeated(q_contains("tag", "c"), q_all("tag", &["a", "b"]), "/?tag=a&tag=b".into())]
+    #[case::query_repeated(q_contains("tag", "c"), q_eq("tag", "a"), "/?tag=a".into())]
+    #[case::query_repeated(q_contains("tag", "c"), q_miss("tag"), "/".into())]
+    #[case::query_repeated(q_all("tag", &["b", "a"]), q_all("tag", &["a", "b"]), "/?tag=a&tag=b".into())]
+    #[case::query_repeated(q_all("tag", &["a"]), q_all("tag", &["a", "b"]), "/?tag=a&tag=b".into())]
+    #[case::query_repeated(q_all("tag", &["a"]), q_miss("tag"), "/?other=a".into())]
+    #[case::query_format(q_fmt("page", ValueFormat::Integer), q_eq("page", "two"), "/?page=two".into())]
+    #[case::query_format(q_fmt("page", ValueFormat::Integer), q_ex("page"), "/?page".into())]
+    #[case::query_format(q_fmt("id", ValueFormat::Uuid), q_all("id", &["123e4567-e89b-12d3-a456-426614174000", "nope"]), "/?id=123e4567-e89b-12d3-a456-426614174000&id=nope".into())]
+    #[case::query_format(q_fmt("since", ValueFormat::Date), q_miss("since"), "/".into())]
     #[case::fragment(f_eq("anchor-incorrect"), f_eq("anchor"), "/path#anchor".into())]
     #[case::fragment(f_miss(), f_eq("anchor"), "/path#anchor".into())]
     #[case::fragment(f_eq("anchor"), f_miss(), "/path".into())]
     #[case::header(h_eq("eq-header", "eq-incorrect-value"), h_eq("eq-header", "eq-value"), Request::default().with_header("eq-header", "eq-value"))]
-    #[case::header(h_miss("eq-header"), h_ex("eq-header"), Request::default().with_header("eq-header", "eq-value"))]
+    #[case::header(h_miss("eq-header"), h_eq("eq-header", "eq-value"), Request::default().with_header("eq-header", "eq-value"))]
     #[case::header(h_ex("miss-header"), h_miss("miss-header"), Request::default().with_header("eq-header", "eq-value"))]
     #[case::header(h_eq("miss-header", "some-miss-val"), h_miss("miss-header"), Request::default().with_header("eq-header", "eq-value"))]
-    #[case::header(h_miss("exists-header"), h_ex("exists-header"), Request::default().with_header("exists-header", "some-exists-value"))]
+    #[case::header(h_miss("exists-header"), h_eq("exists-header", "some-exists-value"), Request::default().with_header("exists-header", "some-exists-value"))]
+    #[case::header_any(h_eq("Via", "1.1 c"), h_eq_all("Via", &["1.1 a", "1.1 b"]), Request::default().with_header("Via", "1.1 a").with_appended_header("Via", "1.1 b"))]
+    #[case::header_all(h_eq_all("Accept", &["*/*"]), h_eq_all("Accept", &["*/*", "text/plain"]), Request::default().with_header("Accept", "*/*").with_appended_header("Accept", "text/plain"))]
+    #[case::header_all(h_eq_all("Accept", &["*/*"]), h_miss("Accept"), Request::default())]
+    #[case::header_repeated(h_miss("Set-Cookie"), h_eq_all("Set-Cookie", &["a=1", "b=2"]), Request::default().with_header("Set-Cookie", "a=1").with_appended_header("Set-Cookie", "b=2"))]
+    #[case::header_case(h_eq("content-type", "text/plain"), h_eq("content-type", "application/json"), Request::default().with_header("Content-Type", "application/json"))]
+    #[case::header_case(h_miss("X-Request-Id"), h_eq("X-Request-Id", "42"), Request::default().with_header("x-request-id", "42"))]
+    #[case::pseudo_header(h_eq(":authority", "api.test"), h_eq(":authority", "api.example.com"), Request::default().with_host("api.example.com"))]
+    #[case::cookie(c_eq("session", "wrong"), c_eq("session", "abc"), Request::default().with_header("Cookie", "theme=dark; session=abc"))]
+    #[case::cookie(c_eq("session", "abc"), c_miss("session"), Request::default().with_header("Cookie", "theme=dark"))]
+    #[case::cookie(c_ex("session"), c_miss("session"), Request::default())]
+    #[case::cookie(c_miss("theme"), c_ex("theme"), Request::default().with_header("cookie", "theme=dark;session=abc"))]
+    #[case::bearer(bearer("expected"), bearer("actual"), auth("Bearer actual"))]
+    #[case::bearer(bearer("token"), h_miss("Authorization"), Request::default())]
+    #[case::bearer(bearer("token"), basic("user", "pass"), auth("Basic dXNlcjpwYXNz"))]
+    #[case::bearer(
+        bearer("token"),
+        h_eq("Authorization", "Digest abc"),
+        auth("Digest abc")
+    )]
+    #[case::basic(
+        basic("user", "wrong"),
+        basic("user", "pass"),
+        auth("Basic dXNlcjpwYXNz")
+    )]
+    #[case::basic(basic("user", "pass"), bearer("token"), auth("bearer token"))]
+    #[case::basic(
+        basic("user", "pass"),
+        h_eq("Authorization", "Basic !!!"),
+        auth("Basic !!!")
+    )]
+    #[case::basic(basic("user", "pass"), h_miss("Authorization"), Request::default())]
+    #[case::content_type(
+        c_type("application/json"),
+        c_type("text/plain"),
+        with_c_type("text/plain")
+    )]
+    #[case::content_type(c_type("application/json"), h_miss("Content-Type"), Request::default())]
+    #[case::content_type(
+        c_type("text/html; charset=utf-8"),
+        c_type("text/html; charset=latin1"),
+        with_c_type("text/html; charset=latin1")
+    )]
+    #[case::content_type(
+        c_type("multipart/form-data; boundary=abc"),
+        c_type("multipart/form-data"),
+        with_c_type("multipart/form-data")
+    )]
+    #[case::accept_language(
+        accepts_lang("de"),
+        prefers_lang("fr-CH"),
+        with_lang("fr-CH, fr;q=0.9, en;q=0.8")
+    )]
+    #[case::accept_language(
+        accepts_lang("en-US"),
+        prefers_lang("en"),
+        with_lang("en;q=0.8, en-US;q=0")
+    )]
+    #[case::accept_language(prefers_lang("en"), prefers_lang("fr"), with_lang("en;q=0.5, fr"))]
+    #[case::accept_language(prefers_lang("en"), h_miss("Accept-Language"), Request::default())]
+    #[case::range(
+        Range(Some(0), Some(99)),
+        Range(Some(0), Some(499)),
+        with_range("bytes=0-499")
+    )]
+    #[case::range(
+        Range(Some(500), None),
+        Range(None, Some(500)),
+        with_range("bytes=-500")
+    )]
+    #[case::range(Range(Some(0), None), h_miss("Range"), Request::default())]
+    #[case::range(
+        Range(Some(0), None),
+        h_eq("Range", "items=0-5"),
+        with_range("items=0-5")
+    )]
+    #[case::range(
+        Range(Some(0), None),
+        h_eq("Range", "bytes=9-1"),
+        with_range("bytes=9-1")
+    )]
+    #[case::if_none_match(if_none_match("\"v2\""), if_none_match("\"v1\""), Request::default().with_header("If-None-Match", "\"v1\", \"v0\""))]
+    #[case::if_none_match(if_none_match("v1"), h_miss("If-None-Match"), Request::default())]
+    #[case::if_match(if_match("\"v1\""), h_eq("If-Match", "W/\"v1\""), Request::default().with_header("If-Match", "W/\"v1\""))]
+    #[case::if_match(if_match("W/\"v1\""), if_match("\"v1\""), Request::default().with_header("If-Match", "\"v1\""))]
+    #[case::if_modified_since(IfModifiedSince(nov_1994() + Duration::from_secs(1)), IfModifiedSince(nov_1994()), Request::default().with_header("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT"))]
+    #[case::if_modified_since(IfModifiedSince(nov_1994()), h_eq("If-Modified-Since", "yesterday"), Request::default().with_header("If-Modified-Since", "yesterday"))]
+    #[case::if_unmodified_since(
+        IfUnmodifiedSince(nov_1994()),
+        h_miss("If-Unmodified-Since"),
+        Request::default()
+    )]
+    #[case::cache_control(
+        cache("max-age", Some("60")),
+        cache("max-age", Some("0")),
+        with_cache("no-cache, max-age=0")
+    )]
+    #[case::cache_control(cache("no-store", None), CacheControlMiss("no-store".into()), with_cache("no-cache"))]
+    #[case::cache_control(cache("no-store", None), h_miss("Cache-Control"), Request::default())]
+    #[case::cache_control(CacheControlMiss("no-cache".into()), cache("no-cache", None), with_cache("max-age=60, No-Cache"))]
     #[case::body(b_eq("some body"), b_miss(), Request::default())]
     #[case::body(b_eq("some incorrect body"), b_eq("some body"), Request::default().with_body("some body"))]
     #[case::body(b_miss(), b_eq("some body"), Request::default().with_body("some body"))]
+    #[case::body(b_miss(), b_bytes(&[0xff, 0x00]), Request::default().with_body([0xff, 0x00]))]
+    #[case::body_exists(b_exists(), b_miss(), Request::default())]
+    #[case::body_empty_text(b_miss(), b_eq(""), Request::default().with_body(""))]
+    #[case::body(b_eq("text"), b_bytes(&[0xff, 0x00]), Request::default().with_body([0xff, 0x00]))]
+    #[case::body_bytes(b_bytes(&[1, 2, 3]), b_bytes(&[1, 2]), Request::default().with_body([1, 2]))]
+    #[case::body_bytes(b_bytes(&[1, 2, 3]), b_miss(), Request::default())]
+    #[case::body_base64(b_base64("AQID"), b_base64("AQI="), Request::default().with_body([1, 2]))]
+    #[case::body_base64(b_base64("not base64!"), b_base64("AQI="), Request::default().with_body([1, 2]))]
+    #[case::body_base64(b_base64("AQI="), b_miss(), Request::default())]
+    #[case::body_hash(b_hash(HashAlgorithm::Sha256, "00"), b_hash(HashAlgorithm::Sha256, ABC_SHA256), Request::default().with_body("abc"))]
+    #[case::body_hash(
+        b_hash(HashAlgorithm::Sha256, ABC_SHA256),
+        b_miss(),
+        Request::default()
+    )]
+    #[case::hmac(hmac("other"), h_eq("X-Hub-Signature-256", &format!("sha256={FOX_HMAC}")), with_signature(&format!("sha256={FOX_HMAC}")))]
+    #[case::hmac(hmac("key"), h_miss("X-Hub-Signature-256"), Request::default().with_body("payload"))]
+    #[case::body_contains(b_contains("error"), b_contains("all good"), Request::default().with_body("all good"))]
+    #[case::body_contains(b_contains("error"), b_bytes(&[0xff, 0xfe]), Request::default().with_body([0xff, 0xfe]))]
+    #[case::body_contains(b_contains("error"), b_miss(), Request::default())]
+    #[case::body_contains(b_contains("needle"), b_contains(&"x".repeat(64)), Request::default().with_body("x".repeat(100)))]
+    #[case::body_regex(b_re(r#""id":\s*\d+"#), b_contains(r#"{"id": "abc"}"#), Request::default().with_body(r#"{"id": "abc"}"#))]
+    #[case::body_regex(b_re("[unclosed"), b_contains("[unclosed"), Request::default().with_body("[unclosed"))]
+    #[case::body_regex(b_re(".*"), b_miss(), Request::default())]
+    #[case::not(not(method("GET")), method("GET"), Request::default())]
+    #[case::not(not(h_eq("X-Mode", "debug")), h_eq("X-Mode", "debug"), Request::default().with_header("X-Mode", "debug"))]
+    #[case::not(not(b_eq("null")), b_eq("null"), Request::default().with_body("null"))]
+    #[case::not(not(not(path("/a"))), not(path("/a")), "/b".into())]
+    #[case::any_of(any_of(&[method("GET"), method("HEAD")]), method("POST"), Request::default().with_method("POST"))]
+    #[case::any_of(any_of(&[all_of(&[method("POST"), path("/a"), h_ex("X-Key")]), all_of(&[method("POST"), path("/b")])]), path("/c"), Request::from("/c").with_method("POST"))]
+    #[case::all_of(all_of(&[method("GET"), path("/a")]), path("/b"), "/b".into())]
+    #[case::all_of(all_of(&[method("PUT"), path("/a")]), all_of(&[method("GET"), path("/b")]), "/b".into())]
+    #[case::form(form_eq("name", "Bob"), form_eq("name", "John Doe"), Request::default().with_body("name=John+Doe&age=42"))]
+    #[case::form(form_eq("email", "a@b.c"), form_miss("email"), Request::default().with_body("name=Bob"))]
+    #[case::form(form_ex("name"), form_miss("name"), Request::default())]
+    #[case::form(form_miss("age"), form_ex("age"), Request::default().with_body("name=Bob&age=42"))]
     fn validate_once_matcher(
         #[case] invalid_matcher: Matcher,
         #[case] valid_matcher: Matcher,
         #[case] request: Request,
     ) {
-        let report = invalid_matcher.validate(&request);
+        let report = invalid_matcher.observed(&request);
         assert_eq!(
             report,
             Some(valid_matcher.clone()),
@@ -199,7 +1734,7 @@ mod test {
             request
         );
 
-        let result = valid_matcher.validate(&request);
+        let result = valid_matcher.observed(&request);
         assert!(
             result.is_none(),
             "Valid matcher {:?} should pass validation (return None) for request: {}",
@@ -208,26 +1743,115 @@ mod test {
         );
     }
 
+    #[rstest]
+    #[case::dot_segments("/a/b/../c", true)]
+    #[case::slashes("//a/c/", true)]
+    #[case::other("/a/b", false)]
+    fn path_normalization(#[case] path: &str, #[case] matched: bool) {
+        let matchers = Matchers::new().path("/a/c");
+        let normalizing = matchers.clone().with_path_normalization();
+        let request = Request::from("/").with_path(path);
+
+        assert!(!matchers.is_matched(&request));
+        assert_eq!(normalizing.is_matched(&request), matched);
+        assert_eq!(normalizing.validate(&request).is_ok(), matched);
+    }
+
+    #[rstest]
+    #[case::single("/users/{id}", "/users/42", &[("id", "42")])]
+    #[case::multiple("/users/{id}/posts/{post_id}", "/users/42/posts/7", &[("id", "42"), ("post_id", "7")])]
+    #[case::literal("/health", "/health", &[])]
+    #[case::format("/orders/{id:uuid}/items/{n:int}", "/orders/123e4567-e89b-12d3-a456-426614174000/items/3", &[("id", "123e4567-e89b-12d3-a456-426614174000"), ("n", "3")])]
+    fn path_template_captures(
+        #[case] template: &str,
+        #[case] uri: &str,
+        #[case] expected: &[(&str, &str)],
+    ) {
+        let expected: HashMap<String, String> = expected
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        assert_eq!(
+            path_tpl(template).captures(&uri.into()),
+            Some(expected),
+            "Template {template:?} should capture segments from {uri:?}"
+        );
+    }
+
+    #[test]
+    fn set_captures_from_its_templates() {
+        let matchers = Matchers::new()
+            .method("GET")
+            .path_template("/users/{id}")
+            .with_path_normalization();
+
+        assert_eq!(
+            matchers.captures(&"/users//42".into()),
+            HashMap::from([("id".to_string(), "42".to_string())])
+        );
+    }
+
     #[rstest::rstest]
     #[case::empty(&[], Request::default())]
     #[case::method(&[method("GET")], Request::default())]
     #[case::method_path(&[method("POST"), path("/some/path")], Request::from("/some/path").with_method("POST"))]
+    #[case::authority(&[scheme("HTTPS"), host("API.example.com"), Matcher::Port(8443)], Request::default().with_scheme("https").with_host("api.example.com").with_port(8443))]
+    #[case::virtual_host(&[host("tenant.example.com"), Matcher::Port(8080)], Request::default().with_header("Host", "tenant.example.com:8080"))]
+    #[case::method_any(&[method_any(&["put", "PATCH"])], Request::default().with_method("PATCH"))]
+    #[case::method_any(&[method_any(&["GET", "HEAD"])], Request::default().with_method("head"))]
+    #[case::path_regex(&[path_re(r"^/users/\d+/posts$")], "/users/42/posts".into())]
+    #[case::path_template(&[path_tpl("/users/{id}/posts/{post_id}")], "/users/42/posts/7".into())]
+    #[case::path_prefix_suffix(&[path_prefix("/api/v1/"), path_suffix(".json")], "/api/v1/users.json".into())]
     #[case::query(&[q_eq("key-eq", "val-eq")], "/?key-eq=val-eq".into())]
     #[case::query(&[q_ex("key-exists")], "/?key-exists".into())]
     #[case::query(&[q_miss("miss-key")], "/?key-exists=some-val".into())]
+    #[case::query_format(&[q_fmt("id", ValueFormat::Uuid), q_fmt("page", ValueFormat::Integer), q_fmt("offset", ValueFormat::Integer), q_fmt("since", ValueFormat::Date)], "/?id=123E4567-E89B-12D3-A456-426614174000&page=2&offset=-10&since=2024-02-29".into())]
+    #[case::query_repeated(&[q_contains("id", "2"), q_all("id", &["1", "2", "3"]), q_ex("id")], "/?id=1&id=2&id=3".into())]
     #[case::query_with_method_path(&[method("PUT"), path("/path/with/query"), q_eq("key-eq", "val-eq"), q_ex("key-exists"), q_miss("miss-key")], Request::from("/path/with/query?key-eq=val-eq&key-exists=some-val").with_method("PUT"))]
     #[case::header(&[h_eq("key-eq", "val-eq")], Request::default().with_header("key-eq", "val-eq"))]
     #[case::header(&[h_ex("key-exists")], Request::default().with_header("key-exists", "some-value"))]
     #[case::header(&[h_miss("miss-key")], Request::default())]
+    #[case::header_any(&[h_eq("Via", "1.1 b")], Request::default().with_header("Via", "1.1 a").with_appended_header("Via", "1.1 b"))]
+    #[case::pseudo_header(&[h_eq(":path", "/v1?x=1"), h_eq(":scheme", "https"), h_miss(":protocol")], Request::from("https://api.example.com/v1?x=1"))]
+    #[case::header_all(&[h_eq_all("Accept", &["*/*", "*/*"])], Request::default().with_header("Accept", "*/*").with_appended_header("Accept", "*/*"))]
     #[case::header_with_method_path(&[method("GET"), path("/path/with/header"), h_eq("key-eq", "val-eq"), h_ex("key-exists"), h_miss("miss-key")], Request::from("/path/with/header").with_header("key-eq", "val-eq").with_header("key-exists", "some-value"))]
+    #[case::header_case(&[h_eq("content-type", "application/json"), h_ex("ACCEPT")], Request::default().with_header("Content-Type", "application/json").with_header("Accept", "*/*"))]
+    #[case::cookie(&[c_eq("session", "abc"), c_ex("theme"), c_miss("tracking")], Request::default().with_header("Cookie", "session=abc; theme=dark"))]
+    #[case::auth(&[bearer("abc.def.ghi")], auth("Bearer abc.def.ghi"))]
+    #[case::auth(&[basic("Aladdin", "open sesame")], auth("Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ=="))]
+    #[case::content_type(&[c_type("application/json")], with_c_type("application/json; charset=utf-8"))]
+    #[case::content_type(&[c_type("Application/JSON")], with_c_type("application/json"))]
+    #[case::content_type(&[c_type("text/html; charset=UTF-8")], with_c_type("text/html;charset=\"utf-8\""))]
+    #[case::content_type(&[c_type("multipart/form-data; boundary=xyz")], with_c_type("multipart/form-data; charset=utf-8; boundary=xyz"))]
+    #[case::accept_language(&[prefers_lang("fr-CH"), accepts_lang("fr-FR"), accepts_lang("EN")], with_lang("fr-CH, fr;q=0.9, en;q=0.8, de;q=0"))]
+    #[case::accept_language(&[prefers_lang("en-GB"), accepts_lang("ja")], with_lang("en, *;q=0.1"))]
+    #[case::range(&[Range(Some(1024), None)], with_range("bytes=1024-"))]
+    #[case::range(&[Range(Some(100), Some(150)), Range(Some(0), Some(50))], with_range("Bytes = 0-50, 100-150"))]
+    #[case::if_none_match(&[if_none_match("W/\"abc\""), if_none_match("abc")], Request::default().with_header("If-None-Match", "\"xyz\", \"abc\""))]
+    #[case::if_none_match(&[if_none_match("\"anything\"")], Request::default().with_header("If-None-Match", "*"))]
+    #[case::if_match(&[if_match("\"abc\"")], Request::default().with_header("If-Match", "\"abc\""))]
+    #[case::conditional_dates(&[IfModifiedSince(nov_1994()), IfModifiedSince(nov_1994() - Duration::from_secs(3600)), IfUnmodifiedSince(nov_1994())], Request::default().with_header("If-Modified-Since", "Sun, 06 Nov 1994 08:49:37 GMT").with_header("If-Unmodified-Since", "Mon, 07 Nov 1994 00:00:00 GMT"))]
+    #[case::cache_control(&[cache("max-age", Some("60")), cache("no-cache", None), cache("private", Some("x-user")), CacheControlMiss("no-store".into())], with_cache(" private=\"x-user\" ,no-cache,MAX-AGE=60"))]
+    #[case::cache_control(&[CacheControlMiss("no-store".into())], Request::default())]
     #[case::path_fragment(&[path("/path"), f_miss()], "/path".into())]
     #[case::path_fragment(&[path("/path"), f_eq("anchor")], "/path#anchor".into())]
     #[case::path_body(&[path("/without/body"), b_miss()], "/without/body".into())]
     #[case::path_body(&[b_eq("some body")], Request::default().with_body("some body"))]
+    #[case::body_contains(&[b_contains("\"status\":\"ok\"")], Request::default().with_body(r#"{"status":"ok","items":[]}"#))]
+    #[case::body_regex(&[b_re(r"^\{.*\}$")], Request::default().with_body(r#"{"status":"ok"}"#))]
+    #[case::body_hash(&[b_hash(HashAlgorithm::Sha256, &ABC_SHA256.to_uppercase())], Request::default().with_body("abc"))]
+    #[case::hmac(&[hmac("key")], with_signature(&format!("sha256={FOX_HMAC}")))]
+    #[case::hmac(&[hmac("key")], with_signature(&FOX_HMAC.to_uppercase()))]
+    #[case::body_hash(&[b_hash(HashAlgorithm::Sha384, "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7"), b_hash(HashAlgorithm::Sha512, "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f")], Request::default().with_body("abc"))]
+    #[case::not(&[method("POST"), not(b_eq("null")), not(h_ex("X-Debug"))], Request::default().with_method("POST").with_body("{}"))]
+    #[case::any_of(&[any_of(&[h_ex("Authorization"), h_ex("X-Api-Key")])], Request::default().with_header("X-Api-Key", "secret"))]
+    #[case::any_of(&[any_of(&[method("GET"), method("HEAD")]), path("/")], Request::default().with_method("HEAD"))]
+    #[case::all_of(&[all_of(&[]), any_of(&[all_of(&[method("GET"), path("/x")]), path("/")])], Request::default())]
+    #[case::body_binary(&[b_bytes(&[0x89, b'P', b'N', b'G']), b_base64("iVBORw=="), b_contains("PNG"), b_re("(?-u)^.PNG$")], Request::default().with_body([0x89, b'P', b'N', b'G']))]
+    #[case::form(&[form_eq("email", "bob@example.com"), form_eq("note", "a&b c"), form_ex("flag"), form_miss("password")], Request::default().with_body("email=bob%40example.com&note=a%26b+c&flag"))]
     fn valid_matchers(#[case] inner: &[Matcher], #[case] request: Request) {
-        let matchers = Matchers {
-            inner: inner.into_iter().map(|m| m.clone()).collect(),
-        };
+        let matchers = matchers(inner);
 
         assert!(
             matchers.is_matched(&request),
@@ -238,8 +1862,8 @@ mod test {
 
         let result = matchers.validate(&request);
         assert!(
-            result.is_none(),
-            "Matchers {:?} should validate successfully (return None) for request: {}",
+            result.is_ok(),
+            "Matchers {:?} should validate successfully for request: {}",
             matchers.inner,
             request
         )
@@ -250,10 +1874,10 @@ mod test {
     #[case::path(&[method("GET"), path("/wrong")], &[path("/correct")], "/correct".into())]
     #[case::query_eq(&[q_eq("key", "wrong")], &[q_eq("key", "correct")], "/?key=correct".into())]
     #[case::query_exists(&[q_ex("missing")], &[q_miss("missing")], "/?other=value".into())]
-    #[case::query_miss(&[q_miss("present")], &[q_ex("present")], "/?present=value".into())]
+    #[case::query_miss(&[q_miss("present")], &[q_eq("present", "value")], "/?present=value".into())]
     #[case::header_eq(&[h_eq("Content-Type", "wrong")], &[h_eq("Content-Type", "correct")], Request::default().with_header("Content-Type", "correct"))]
     #[case::header_exists(&[h_ex("missing")], &[h_miss("missing")], Request::default())]
-    #[case::header_miss(&[h_miss("present")], &[h_ex("present")], Request::default().with_header("present", "value"))]
+    #[case::header_miss(&[h_miss("present")], &[h_eq("present", "value")], Request::default().with_header("present", "value"))]
     #[case::fragment_eq(&[f_eq("wrong")], &[f_eq("correct")], "/path#correct".into())]
     #[case::fragment_miss(&[f_miss()], &[f_eq("present")], "/path#present".into())]
     #[case::body_eq(&[b_eq("wrong body")], &[b_eq("correct body")], Request::default().with_body("correct body"))]
@@ -266,10 +1890,13 @@ mod test {
         #[case] reports: &[Matcher],
         #[case] request: Request,
     ) {
-        let matchers = Matchers {
-            inner: inner.into_iter().map(|m| m.clone()).collect(),
-        };
-        let expected_reports: Vec<Matcher> = reports.into_iter().map(|m| m.clone()).collect();
+        let matchers = matchers(inner);
+        let expected_reports: Vec<Mismatch> = inner
+            .iter()
+            .filter(|matcher| matcher.observed(&request).is_some())
+            .zip(reports)
+            .map(|(matcher, report)| Mismatch::between(matcher, report))
+            .collect();
 
         assert!(
             !matchers.is_matched(&request),
@@ -278,10 +1905,12 @@ mod test {
             request
         );
 
-        let result = matchers.validate(&request);
+        let result = matchers
+            .validate(&request)
+            .map_err(|report| report.mismatches().to_vec());
         assert_eq!(
             result,
-            Some(expected_reports.clone()),
+            Err(expected_reports.clone()),
             "Matchers {:?} should report errors {:?} for request: {}\nActual result: {:?}",
             matchers.inner,
             expected_reports,
@@ -289,4 +1918,126 @@ mod test {
             result
         );
     }
+
+    #[rstest]
+    #[case::header(
+        h_eq("Authorization", "Bearer abc"),
+        Request::default(),
+        h_miss("Authorization")
+    )]
+    #[case::nested(not(path("/admin")), Request::from("/admin"), path("/admin"))]
+    fn named_matcher_reports_label(
+        #[case] inner: Matcher,
+        #[case] request: Request,
+        #[case] report: Matcher,
+    ) {
+        let matcher = Matcher::named("auth header", inner);
+        let expected = Matcher::named("auth header", report);
+
+        assert_eq!(
+            matcher.observed(&request),
+            Some(expected.clone()),
+            "Named matcher {matcher:?} should wrap the inner report"
+        );
+        assert!(expected.observed(&request).is_none());
+    }
+
+    #[test]
+    fn matchers_collection_conversions() {
+        let mut matchers = Matchers::from(vec![method("GET"), path("/users")]);
+        matchers.extend([h_ex("Accept")]);
+        let request = Request::from("/users").with_header("Accept", "*/*");
+
+        assert_eq!(matchers.len(), 3);
+        assert!(!matchers.is_empty());
+        assert!(Matchers::new().is_empty());
+        assert!(matchers.iter().all(|m| m.validate(&request).is_none()));
+        assert_eq!(
+            (&matchers).into_iter().count(),
+            matchers.clone().into_iter().count()
+        );
+        assert!(
+            [q_ex("page"), q_miss("page")]
+                .into_iter()
+                .collect::<Matchers>()
+                .validate(&Request::default())
+                .is_err_and(|report| report.len() == 1)
+        );
+    }
+
+    #[rstest]
+    #[case::exact_over_prefix(matchers(&[path("/users")]), matchers(&[path_prefix("/users")]))]
+    #[case::template_over_regex(matchers(&[path_tpl("/users/{id}")]), matchers(&[path_re("^/users/")]))]
+    #[case::more_matchers(matchers(&[method("GET"), path_prefix("/u")]), matchers(&[path_prefix("/u")]))]
+    #[case::weighted(Matchers::new().with(Weighted::new(h_ex("X-Tenant"), 10)), matchers(&[path("/users")]))]
+    fn specificity_ranks_sets(#[case] specific: Matchers, #[case] general: Matchers) {
+        assert!(
+            specific.specificity() > general.specificity(),
+            "{:?} should be more specific than {:?}",
+            specific.explain(),
+            general.explain()
+        );
+    }
+
+    #[rstest]
+    #[case::valid(Request::from("/users").with_method("POST"), Ok(()))]
+    #[case::first_only(
+        Request::from("/posts"),
+        Err(Mismatch::wrong("method must be `POST`", "GET"))
+    )]
+    #[case::second(Request::from("/posts").with_method("POST"), Err(Mismatch::wrong("path must equal `/users`", "/posts")))]
+    fn validate_first_stops_at_first_failure(
+        #[case] request: Request,
+        #[case] expected: Result<(), Mismatch>,
+    ) {
+        let matchers = matchers(&[method("POST"), path("/users")]);
+
+        assert_eq!(
+            matchers.validate_first(&request),
+            expected,
+            "Matchers {:?} should report only the first failure for request: {}",
+            matchers.inner,
+            request
+        );
+    }
+
+    /// Accepts only requests whose path has an even number of segments.
+    #[derive(Debug)]
+    struct EvenSegments;
+
+    impl Match for EvenSegments {
+        fn validate(&self, request: &Request) -> Option<Mismatch> {
+            let segments = request.path.split('/').filter(|s| !s.is_empty()).count();
+            if segments % 2 == 0 {
+                None
+            } else {
+                Some(Mismatch::wrong(
+                    "an even number of path segments",
+                    format!("{segments} segments"),
+                ))
+            }
+        }
+    }
+
+    #[rstest]
+    #[case::valid("/users/42", "POST", Ok(()))]
+    #[case::invalid("/users/42/posts", "GET", Err(vec![Mismatch::wrong("an even number of path segments", "3 segments"), Mismatch::wrong("method must be `POST`", "GET")]))]
+    fn custom_matcher(
+        #[case] uri: &str,
+        #[case] request_method: &str,
+        #[case] expected: Result<(), Vec<Mismatch>>,
+    ) {
+        let matchers = Matchers::new().with(EvenSegments).with(method("POST"));
+        let request = Request::from(uri).with_method(request_method);
+
+        assert_eq!(
+            matchers
+                .validate(&request)
+                .map_err(|report| report.mismatches().to_vec()),
+            expected,
+            "Custom and built-in matchers {:?} should be validated together for request: {}",
+            matchers.inner,
+            request
+        );
+    }
 }
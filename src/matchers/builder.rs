@@ -0,0 +1,215 @@
+use super::{HashAlgorithm, Matcher, Matchers, ValueFormat};
+use crate::request::HttpVersion;
+
+/// Fluent shorthands for [`Matchers::with`], one per commonly used [`Matcher`] variant.
+impl Matchers {
+    pub fn method<S: Into<String>>(self, method: S) -> Self {
+        self.with(Matcher::Method(method.into()))
+    }
+
+    pub fn scheme<S: Into<String>>(self, scheme: S) -> Self {
+        self.with(Matcher::Scheme(scheme.into()))
+    }
+
+    pub fn scheme_miss(self) -> Self {
+        self.with(Matcher::SchemeMiss)
+    }
+
+    pub fn host<S: Into<String>>(self, host: S) -> Self {
+        self.with(Matcher::Host(host.into()))
+    }
+
+    pub fn port(self, port: u16) -> Self {
+        self.with(Matcher::Port(port))
+    }
+
+    pub fn port_miss(self) -> Self {
+        self.with(Matcher::PortMiss)
+    }
+
+    pub fn version(self, version: HttpVersion) -> Self {
+        self.with(Matcher::Version(version))
+    }
+
+    pub fn path<S: Into<String>>(self, path: S) -> Self {
+        self.with(Matcher::Path(path.into()))
+    }
+
+    pub fn path_regex<S: Into<String>>(self, pattern: S) -> Self {
+        self.with(Matcher::PathRegex(pattern.into()))
+    }
+
+    pub fn path_template<S: Into<String>>(self, template: S) -> Self {
+        self.with(Matcher::PathTemplate(template.into()))
+    }
+
+    pub fn path_prefix<S: Into<String>>(self, prefix: S) -> Self {
+        self.with(Matcher::PathPrefix(prefix.into()))
+    }
+
+    pub fn path_suffix<S: Into<String>>(self, suffix: S) -> Self {
+        self.with(Matcher::PathSuffix(suffix.into()))
+    }
+
+    pub fn query_exists<S: Into<String>>(self, key: S) -> Self {
+        self.with(Matcher::QueryExists(key.into()))
+    }
+
+    pub fn query_miss<S: Into<String>>(self, key: S) -> Self {
+        self.with(Matcher::QueryMiss(key.into()))
+    }
+
+    pub fn query_eq<K: Into<String>, V: Into<String>>(self, key: K, value: V) -> Self {
+        self.with(Matcher::QueryEq(key.into(), value.into()))
+    }
+
+    pub fn query_format<K: Into<String>>(self, key: K, format: ValueFormat) -> Self {
+        self.with(Matcher::QueryFormat(key.into(), format))
+    }
+
+    pub fn header_exists<S: Into<String>>(self, name: S) -> Self {
+        self.with(Matcher::HeaderExists(name.into()))
+    }
+
+    pub fn header_miss<S: Into<String>>(self, name: S) -> Self {
+        self.with(Matcher::HeaderMiss(name.into()))
+    }
+
+    pub fn header_eq<K: Into<String>, V: Into<String>>(self, name: K, value: V) -> Self {
+        self.with(Matcher::HeaderEq(name.into(), value.into()))
+    }
+
+    pub fn cookie_eq<K: Into<String>, V: Into<String>>(self, name: K, value: V) -> Self {
+        self.with(Matcher::CookieEq(name.into(), value.into()))
+    }
+
+    pub fn cookie_exists<S: Into<String>>(self, name: S) -> Self {
+        self.with(Matcher::CookieExists(name.into()))
+    }
+
+    pub fn cookie_miss<S: Into<String>>(self, name: S) -> Self {
+        self.with(Matcher::CookieMiss(name.into()))
+    }
+
+    pub fn bearer_token<S: Into<String>>(self, token: S) -> Self {
+        self.with(Matcher::BearerToken(token.into()))
+    }
+
+    pub fn basic_auth<U: Into<String>, P: Into<String>>(self, user: U, password: P) -> Self {
+        self.with(Matcher::BasicAuth(user.into(), password.into()))
+    }
+
+    pub fn content_type<S: Into<String>>(self, media_type: S) -> Self {
+        self.with(Matcher::ContentType(media_type.into()))
+    }
+
+    pub fn body_exists(self) -> Self {
+        self.with(Matcher::BodyExists)
+    }
+
+    pub fn body_miss(self) -> Self {
+        self.with(Matcher::BodyMiss)
+    }
+
+    pub fn body_eq<S: Into<String>>(self, body: S) -> Self {
+        self.with(Matcher::BodyEq(body.into()))
+    }
+
+    pub fn body_bytes_eq<B: Into<Vec<u8>>>(self, body: B) -> Self {
+        self.with(Matcher::BodyBytesEq(body.into()))
+    }
+
+    pub fn body_hash<S: Into<String>>(self, algorithm: HashAlgorithm, digest: S) -> Self {
+        self.with(Matcher::BodyHash(algorithm, digest.into()))
+    }
+
+    pub fn body_contains<S: Into<String>>(self, needle: S) -> Self {
+        self.with(Matcher::BodyContains(needle.into()))
+    }
+
+    pub fn body_regex<S: Into<String>>(self, pattern: S) -> Self {
+        self.with(Matcher::BodyRegex(pattern.into()))
+    }
+
+    pub fn form_field_exists<S: Into<String>>(self, name: S) -> Self {
+        self.with(Matcher::FormFieldExists(name.into()))
+    }
+
+    pub fn form_field_miss<S: Into<String>>(self, name: S) -> Self {
+        self.with(Matcher::FormFieldMiss(name.into()))
+    }
+
+    pub fn form_field_eq<K: Into<String>, V: Into<String>>(self, name: K, value: V) -> Self {
+        self.with(Matcher::FormFieldEq(name.into(), value.into()))
+    }
+
+    #[cfg(feature = "json")]
+    pub fn body_json_eq(self, json: serde_json::Value) -> Self {
+        self.with(Matcher::BodyJsonEq(json))
+    }
+
+    #[cfg(feature = "json")]
+    pub fn body_json_includes(self, json: serde_json::Value) -> Self {
+        self.with(Matcher::BodyJsonIncludes(json))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matchers::Mismatch;
+    use crate::request::Request;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::scheme_miss(Matchers::new().scheme_miss(), Matcher::SchemeMiss)]
+    #[case::port_miss(Matchers::new().port_miss(), Matcher::PortMiss)]
+    #[case::path_suffix(Matchers::new().path_suffix(".json"), Matcher::PathSuffix(".json".into()))]
+    #[case::cookie_exists(Matchers::new().cookie_exists("sid"), Matcher::CookieExists("sid".into()))]
+    #[case::cookie_miss(Matchers::new().cookie_miss("sid"), Matcher::CookieMiss("sid".into()))]
+    #[case::form_field_exists(
+        Matchers::new().form_field_exists("name"),
+        Matcher::FormFieldExists("name".into())
+    )]
+    #[case::form_field_miss(
+        Matchers::new().form_field_miss("name"),
+        Matcher::FormFieldMiss("name".into())
+    )]
+    fn builder_adds_matcher(#[case] matchers: Matchers, #[case] expected: Matcher) {
+        let added: Vec<_> = matchers.iter().filter_map(|m| m.as_matcher()).collect();
+
+        assert_eq!(added, [&expected]);
+    }
+
+    #[test]
+    fn builder_collects_matchers_in_order() {
+        let matchers = Matchers::new()
+            .method("POST")
+            .path("/users")
+            .header_eq("Content-Type", "application/json")
+            .body_contains("name");
+        let request = Request::from("/accounts").with_body("{}");
+
+        assert_eq!(
+            matchers
+                .validate(&request)
+                .map_err(|report| report.mismatches().to_vec()),
+            Err(vec![
+                Mismatch::wrong("method must be `POST`", "GET"),
+                Mismatch::wrong("path must equal `/users`", "/accounts"),
+                Mismatch::missing("header `Content-Type` must equal `application/json`"),
+                Mismatch::wrong("body must contain `name`", "{}"),
+            ]),
+            "Every builder call should add one matcher, reported in call order"
+        );
+    }
+
+    #[test]
+    fn add_extends_existing_matchers() {
+        let mut matchers = Matchers::new().method("GET");
+        matchers.add(Matcher::QueryEq("page".into(), "2".into()));
+
+        assert!(matchers.is_matched(&Request::from("/?page=2")));
+        assert!(!matchers.is_matched(&Request::from("/?page=3")));
+    }
+}
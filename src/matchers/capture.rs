@@ -0,0 +1,186 @@
+use super::{Matcher, Matchers, body_exact, header_report, query_report};
+use crate::curl::{self, CurlError};
+use crate::request::Request;
+
+/// Chooses which parts of an example request [`Matchers::from_request`] turns into matchers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureOptions {
+    pub method: bool,
+    pub path: bool,
+    pub query: bool,
+    /// Headers to pin to their observed value; ones the request lacks must stay absent.
+    pub headers: Vec<String>,
+    /// Pins the exact body, or its absence.
+    pub body: bool,
+}
+
+impl Default for CaptureOptions {
+    fn default() -> Self {
+        Self {
+            method: true,
+            path: true,
+            query: true,
+            headers: Vec::new(),
+            body: false,
+        }
+    }
+}
+
+impl CaptureOptions {
+    pub fn with_header<S: Into<String>>(mut self, name: S) -> Self {
+        self.headers.push(name.into());
+        self
+    }
+
+    pub fn with_body(mut self, body: bool) -> Self {
+        self.body = body;
+        self
+    }
+}
+
+impl Matchers {
+    /// Records `request` as a matcher set that accepts it (and requests like it).
+    pub fn from_request(request: &Request, options: &CaptureOptions) -> Matchers {
+        let mut matchers = Matchers::new();
+
+        if options.method {
+            matchers.add(Matcher::Method(request.method.to_uppercase()));
+        }
+        if options.path {
+            matchers.add(Matcher::Path(request.path.clone()));
+        }
+        if options.query {
+            let mut keys: Vec<&String> = request.query.keys().collect();
+            keys.sort();
+            for key in keys {
+                matchers.add(query_report(key, &request.query[key]));
+            }
+        }
+        for name in &options.headers {
+            matchers.add(match request.header_values(name) {
+                [] => Matcher::HeaderMiss(name.clone()),
+                values => header_report(name, values),
+            });
+        }
+        if options.body {
+            matchers.add(match request.body.bytes().as_deref() {
+                Some(body) => body_exact(body),
+                None => Matcher::BodyMiss,
+            });
+        }
+
+        matchers
+    }
+}
+
+impl Matchers {
+    /// Builds the expectations for the request a shared curl command sends: method,
+    /// authority, path, query, every header given with `-H` (or implied by options like `-u`)
+    /// and the body.
+    pub fn from_curl(command: &str) -> Result<Matchers, CurlError> {
+        let request = curl::parse(command)?;
+        let mut headers: Vec<String> = request.headers.names().cloned().collect();
+        headers.sort();
+        let options = CaptureOptions {
+            headers,
+            body: !request.body.is_empty(),
+            ..Default::default()
+        };
+
+        let mut matchers = Matchers::new();
+        if let Some(scheme) = &request.scheme {
+            matchers.add(Matcher::Scheme(scheme.clone()));
+        }
+        if let Some(host) = &request.host {
+            matchers.add(Matcher::Host(host.clone()));
+        }
+        if let Some(port) = request.port {
+            matchers.add(Matcher::Port(port));
+        }
+
+        matchers
+            .inner
+            .extend(Matchers::from_request(&request, &options).inner);
+        Ok(matchers)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn s(value: &str) -> String {
+        value.into()
+    }
+
+    fn example() -> Request {
+        Request::from("/users?tag=a&tag=b&page=2")
+            .with_method("post")
+            .with_header("Content-Type", "application/json")
+            .with_header("X-Trace", "abc")
+            .with_body(r#"{"name":"Bob"}"#)
+    }
+
+    #[test]
+    fn from_curl() {
+        let matchers = Matchers::from_curl(
+            r#"curl -X POST 'https://api.example.com/users?notify=true' -H 'X-Api-Key: k' -d '{"name":"Bob"}'"#,
+        )
+        .unwrap();
+        let captured: Vec<Matcher> = matchers
+            .iter()
+            .filter_map(|m| m.as_matcher().cloned())
+            .collect();
+
+        assert_eq!(
+            captured,
+            vec![
+                Matcher::Scheme(s("https")),
+                Matcher::Host(s("api.example.com")),
+                Matcher::Method(s("POST")),
+                Matcher::Path(s("/users")),
+                Matcher::QueryEq(s("notify"), s("true")),
+                Matcher::HeaderEq(s("Content-Type"), s("application/x-www-form-urlencoded")),
+                Matcher::HeaderEq(s("X-Api-Key"), s("k")),
+                Matcher::BodyEq(s(r#"{"name":"Bob"}"#)),
+            ]
+        );
+        assert_eq!(
+            Matchers::from_curl("ls").map(|m| m.len()),
+            Err(CurlError::NotCurl)
+        );
+    }
+
+    #[rstest]
+    #[case::defaults(CaptureOptions::default(), vec![
+        Matcher::Method(s("POST")),
+        Matcher::Path(s("/users")),
+        Matcher::QueryEq(s("page"), s("2")),
+        Matcher::QueryEqAll(s("tag"), vec![s("a"), s("b")]),
+    ])]
+    #[case::headers_and_body(
+        CaptureOptions { method: false, path: false, query: false, ..Default::default() }
+            .with_header("content-type")
+            .with_header("Authorization")
+            .with_body(true),
+        vec![
+            Matcher::HeaderEq(s("content-type"), s("application/json")),
+            Matcher::HeaderMiss(s("Authorization")),
+            Matcher::BodyEq(s(r#"{"name":"Bob"}"#)),
+        ]
+    )]
+    fn captures_selected_parts(#[case] options: CaptureOptions, #[case] expected: Vec<Matcher>) {
+        let matchers = Matchers::from_request(&example(), &options);
+        let captured: Vec<Matcher> = matchers
+            .iter()
+            .filter_map(|m| m.as_matcher().cloned())
+            .collect();
+
+        assert_eq!(captured, expected);
+        assert!(
+            matchers.is_matched(&example()),
+            "Captured matchers should accept their example"
+        );
+    }
+}
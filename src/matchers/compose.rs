@@ -0,0 +1,156 @@
+use super::{Match, Matchers, Mismatch, Reason};
+use crate::request::Request;
+
+/// A nested set holds when all of its matchers do, so sets can be grouped with
+/// [`Matchers::add`].
+impl Match for Matchers {
+    fn validate(&self, request: &Request) -> Option<Mismatch> {
+        let mut mismatches = self.validate(request).err()?.into_iter();
+        match (mismatches.next(), mismatches.next()) {
+            (Some(only), None) => Some(only),
+            (first, second) => {
+                let actual = first
+                    .into_iter()
+                    .chain(second)
+                    .chain(mismatches)
+                    .map(|mismatch| mismatch.to_string())
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                Some(Mismatch::new(
+                    Match::explain(self),
+                    Reason::Wrong,
+                    Some(actual),
+                ))
+            }
+        }
+    }
+
+    fn explain(&self) -> String {
+        let sentences: Vec<String> = self
+            .iter()
+            .map(|matcher| format!("({})", matcher.explain()))
+            .collect();
+        format!("all must hold: {}", sentences.join(" and "))
+    }
+
+    fn weight(&self) -> u32 {
+        self.specificity()
+    }
+}
+
+/// Holds when at least one of its sets does; built by [`Matchers::or`].
+#[derive(Debug, Clone)]
+struct AnySet(Vec<Matchers>);
+
+impl Match for AnySet {
+    fn validate(&self, request: &Request) -> Option<Mismatch> {
+        // Report the failure of the set that came closest to matching.
+        let nearest = self.0.iter().max_by_key(|set| set.score(request))?;
+        Match::validate(nearest, request)
+    }
+
+    fn explain(&self) -> String {
+        let sentences: Vec<String> = self
+            .0
+            .iter()
+            .map(|set| format!("({})", Match::explain(set)))
+            .collect();
+        format!("at least one must hold: {}", sentences.join(" or "))
+    }
+
+    fn weight(&self) -> u32 {
+        self.0.iter().map(Matchers::specificity).min().unwrap_or(0)
+    }
+}
+
+impl Matchers {
+    /// Appends every matcher of `other`, e.g. to reuse a base set of common headers and
+    /// auth. The higher of the two priorities is kept.
+    pub fn merge(mut self, other: Matchers) -> Self {
+        self.inner.extend(other.inner);
+        self.priority = self.priority.max(other.priority);
+        self
+    }
+
+    /// Builds a set that accepts a request when either `self` or `other` does.
+    pub fn or(self, other: Matchers) -> Self {
+        let priority = self.priority.max(other.priority);
+        let sets = vec![self, other];
+        Matchers::new().with(AnySet(sets)).with_priority(priority)
+    }
+
+    /// Appends `group` as a single nested expectation that holds when all of its matchers do.
+    pub fn group(self, group: Matchers) -> Self {
+        self.with(group)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn base() -> Matchers {
+        Matchers::new()
+            .header_exists("Authorization")
+            .header_eq("Accept", "application/json")
+    }
+
+    fn authorized(path: &str) -> Request {
+        Request::from(path)
+            .with_header("Authorization", "Bearer t")
+            .with_header("Accept", "application/json")
+    }
+
+    #[test]
+    fn merge_reuses_base_set() {
+        let users = base()
+            .with_priority(1)
+            .merge(Matchers::new().path("/users").with_priority(3));
+
+        assert_eq!(users.len(), 3);
+        assert_eq!(users.priority(), 3);
+        assert!(users.is_matched(&authorized("/users")));
+        assert!(!users.is_matched(&Request::from("/users")));
+    }
+
+    #[rstest]
+    #[case::first(Request::from("/users"), true)]
+    #[case::second(Request::from("/accounts").with_method("POST"), true)]
+    #[case::neither(Request::from("/accounts"), false)]
+    fn or_accepts_either_set(#[case] request: Request, #[case] matched: bool) {
+        let matchers = Matchers::new()
+            .path("/users")
+            .or(Matchers::new().method("POST").path("/accounts"));
+
+        assert_eq!(matchers.is_matched(&request), matched);
+    }
+
+    #[test]
+    fn or_reports_nearest_set() {
+        let matchers = Matchers::new()
+            .method("GET")
+            .path("/users")
+            .or(Matchers::new().method("POST").path("/accounts"));
+        let report = matchers.validate(&Request::from("/accounts")).unwrap_err();
+
+        assert_eq!(
+            report.mismatches(),
+            &[Mismatch::wrong("method must be `POST`", "GET")]
+        );
+    }
+
+    #[test]
+    fn nested_group() {
+        let matchers = Matchers::new().path("/users").group(base());
+        let report = matchers.validate(&Request::from("/users")).unwrap_err();
+
+        assert_eq!(matchers.len(), 2);
+        assert_eq!(matchers.specificity(), 6);
+        assert_eq!(
+            report.to_string(),
+            "request did not match 1 expectation(s)\n  - all must hold: (header `Authorization` must be present) and (header `Accept` must equal `application/json`), but got \"header `Authorization` must be present, but it is missing; header `Accept` must equal `application/json`, but it is missing\""
+        );
+        assert!(matchers.is_matched(&authorized("/users")));
+    }
+}
@@ -0,0 +1,214 @@
+use super::{Matcher, Matchers};
+use crate::redaction::Redaction;
+
+/// Two matchers in the same set that no request can satisfy together.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub left: Matcher,
+    pub right: Matcher,
+    pub reason: &'static str,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let redaction = Redaction::current();
+        write!(
+            f,
+            "{} contradicts {}: {}",
+            redaction.mask(&self.left).explain(),
+            redaction.mask(&self.right).explain(),
+            self.reason
+        )
+    }
+}
+
+impl std::error::Error for Conflict {}
+
+impl Matchers {
+    /// Reports pairs of built-in matchers that can never match the same request, such as
+    /// `QueryEq("a", ..)` together with `QueryMiss("a")`. Custom matchers are not inspected.
+    pub fn check_consistency(&self) -> Result<(), Vec<Conflict>> {
+        let mut matchers = Vec::new();
+        for matcher in self.iter().filter_map(|m| m.as_matcher()) {
+            flatten(matcher, &mut matchers);
+        }
+
+        let mut conflicts = Vec::new();
+        for (idx, left) in matchers.iter().enumerate() {
+            for right in &matchers[idx + 1..] {
+                if let Some(reason) =
+                    contradiction(left, right).or_else(|| contradiction(right, left))
+                {
+                    conflicts.push(Conflict {
+                        left: (*left).clone(),
+                        right: (*right).clone(),
+                        reason,
+                    });
+                }
+            }
+        }
+
+        if conflicts.is_empty() {
+            Ok(())
+        } else {
+            Err(conflicts)
+        }
+    }
+}
+
+/// Collects the matchers that must all hold, looking through nested [`Matcher::AllOf`].
+fn flatten<'a>(matcher: &'a Matcher, into: &mut Vec<&'a Matcher>) {
+    match matcher {
+        Matcher::AllOf(matchers) => matchers.iter().for_each(|m| flatten(m, into)),
+        Matcher::Named(_, matcher) => flatten(matcher, into),
+        matcher => into.push(matcher),
+    }
+}
+
+fn contradiction(left: &Matcher, right: &Matcher) -> Option<&'static str> {
+    use Matcher::*;
+
+    match (left, right) {
+        (Not(negated), other) if negated.as_ref() == other => {
+            Some("a matcher is required and negated at once")
+        }
+        (Method(a), Method(b)) if !a.eq_ignore_ascii_case(b) => {
+            Some("a request has a single method")
+        }
+        (Method(a), MethodAny(allowed)) if !allowed.iter().any(|m| m.eq_ignore_ascii_case(a)) => {
+            Some("the method is not among the allowed ones")
+        }
+        (Scheme(a), Scheme(b)) if !a.eq_ignore_ascii_case(b) => {
+            Some("a request has a single scheme")
+        }
+        (Host(a), Host(b)) if !a.eq_ignore_ascii_case(b) => Some("a request has a single host"),
+        (Port(a), Port(b)) if a != b => Some("a request has a single port"),
+        (Scheme(_), SchemeMiss) => Some("the scheme is required and forbidden"),
+        (Port(_), PortMiss) => Some("the port is required and forbidden"),
+        (Version(a), Version(b)) if a != b => Some("a request has a single HTTP version"),
+        (Path(a), Path(b)) if a != b => Some("a request has a single path"),
+        (QueryEq(k1, a), QueryEq(k2, b)) if k1 == k2 && a != b => {
+            Some("the query parameter must hold a single, different value")
+        }
+        (
+            QueryExists(k1)
+            | QueryEq(k1, _)
+            | QueryContainsValue(k1, _)
+            | QueryEqAll(k1, _)
+            | QueryFormat(k1, _),
+            QueryMiss(k2),
+        ) if k1 == k2 => Some("the query parameter is required and forbidden"),
+        (FragmentEq(a), FragmentEq(b)) if a != b => Some("a request has a single fragment"),
+        (FragmentEq(_), FragmentMiss) => Some("the fragment is required and forbidden"),
+        // A repeated header may carry several values, unless they are listed exactly.
+        (HeaderEqAll(k1, a), HeaderEqAll(k2, b)) if k1.eq_ignore_ascii_case(k2) && a != b => {
+            Some("the header must hold two different values")
+        }
+        (HeaderEqAll(k1, values), HeaderEq(k2, value))
+            if k1.eq_ignore_ascii_case(k2) && !values.contains(value) =>
+        {
+            Some("the header must hold two different values")
+        }
+        (HeaderExists(k1) | HeaderEq(k1, _) | HeaderEqAll(k1, _), HeaderMiss(k2))
+            if k1.eq_ignore_ascii_case(k2) =>
+        {
+            Some("the header is required and forbidden")
+        }
+        (CookieEq(k1, a), CookieEq(k2, b)) if k1 == k2 && a != b => {
+            Some("the cookie must hold two different values")
+        }
+        (CookieExists(k1) | CookieEq(k1, _), CookieMiss(k2)) if k1 == k2 => {
+            Some("the cookie is required and forbidden")
+        }
+        (FormFieldEq(k1, a), FormFieldEq(k2, b)) if k1 == k2 && a != b => {
+            Some("the form field must hold two different values")
+        }
+        (FormFieldExists(k1) | FormFieldEq(k1, _), FormFieldMiss(k2)) if k1 == k2 => {
+            Some("the form field is required and forbidden")
+        }
+        (BodyEq(a), BodyEq(b)) if a != b => Some("a request has a single body"),
+        (BodyBytesEq(a), BodyBytesEq(b)) if a != b => Some("a request has a single body"),
+        (matcher, BodyMiss) if requires_body(matcher) => Some("the body is required and forbidden"),
+        _ => None,
+    }
+}
+
+fn requires_body(matcher: &Matcher) -> bool {
+    match matcher {
+        Matcher::BodyExists
+        | Matcher::BodyEq(_)
+        | Matcher::BodyBytesEq(_)
+        | Matcher::BodyBase64Eq(_)
+        | Matcher::BodyHash(..)
+        | Matcher::BodyContains(_)
+        | Matcher::BodyRegex(_)
+        | Matcher::FormFieldExists(_)
+        | Matcher::FormFieldEq(..) => true,
+        #[cfg(feature = "json")]
+        Matcher::BodyJsonEq(_)
+        | Matcher::BodyJsonIncludes(_)
+        | Matcher::BodyJsonPointer(..)
+        | Matcher::JsonPath(..) => true,
+        #[cfg(feature = "xml")]
+        Matcher::BodyXmlEq(_) | Matcher::BodyXPath(..) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn s(value: &str) -> String {
+        value.into()
+    }
+
+    #[rstest]
+    #[case::query(vec![Matcher::QueryEq(s("a"), s("1")), Matcher::QueryMiss(s("a"))])]
+    #[case::query_values(vec![Matcher::QueryEq(s("a"), s("1")), Matcher::QueryEq(s("a"), s("2"))])]
+    #[case::method(vec![Matcher::Method(s("GET")), Matcher::Method(s("POST"))])]
+    #[case::method_any(vec![Matcher::MethodAny(vec![s("PUT"), s("PATCH")]), Matcher::Method(s("GET"))])]
+    #[case::header(vec![Matcher::HeaderMiss(s("authorization")), Matcher::HeaderExists(s("Authorization"))])]
+    #[case::header_values(vec![Matcher::HeaderEq(s("Via"), s("a")), Matcher::HeaderEqAll(s("via"), vec![s("b")])])]
+    #[case::body(vec![Matcher::BodyMiss, Matcher::BodyContains(s("id"))])]
+    #[case::negated(vec![Matcher::Not(Box::new(Matcher::Path(s("/a")))), Matcher::Path(s("/a"))])]
+    #[case::nested(vec![Matcher::Port(80), Matcher::AllOf(vec![Matcher::Port(8080)])])]
+    fn detects_conflicts(#[case] matchers: Vec<Matcher>) {
+        let result = Matchers::from(matchers.clone()).check_consistency();
+
+        assert!(
+            result.as_ref().is_err_and(|conflicts| conflicts.len() == 1),
+            "Matchers {matchers:?} should have exactly one conflict, got {result:?}"
+        );
+    }
+
+    #[rstest]
+    #[case::same_method(vec![Matcher::Method(s("get")), Matcher::Method(s("GET"))])]
+    #[case::different_keys(vec![Matcher::QueryEq(s("a"), s("1")), Matcher::QueryMiss(s("b"))])]
+    #[case::alternatives(vec![Matcher::AnyOf(vec![Matcher::Path(s("/a")), Matcher::Path(s("/b"))])])]
+    #[case::repeated_header(vec![Matcher::HeaderEq(s("Via"), s("a")), Matcher::HeaderEq(s("Via"), s("b"))])]
+    #[case::empty(vec![])]
+    fn accepts_consistent_sets(#[case] matchers: Vec<Matcher>) {
+        let result = Matchers::from(matchers.clone()).check_consistency();
+
+        assert_eq!(result, Ok(()), "Matchers {matchers:?} should be consistent");
+    }
+
+    #[rstest]
+    #[case::query(
+        Matchers::new().query_eq("a", "1").query_miss("a"),
+        "query parameter `a` must equal `1` contradicts query parameter `a` must be absent: \
+         the query parameter is required and forbidden"
+    )]
+    #[case::masked(
+        Matchers::new().header_eq("Authorization", "Bearer secret").header_miss("Authorization"),
+        "header `Authorization` must equal `[REDACTED]` contradicts header `Authorization` must \
+         be absent: the header is required and forbidden"
+    )]
+    fn conflict_display(#[case] matchers: Matchers, #[case] expected: &str) {
+        let conflict = matchers.check_consistency().unwrap_err().remove(0);
+
+        assert_eq!(conflict.to_string(), expected);
+    }
+}
@@ -0,0 +1,167 @@
+use std::sync::{Mutex, PoisonError};
+
+use super::{Match, Matchers, Mismatch};
+use crate::request::Request;
+
+/// Per-matcher evaluation counters of a [`Matchers`] set. Clones start from a snapshot
+/// and count independently.
+#[derive(Debug, Default)]
+pub(super) struct Usage(Mutex<Vec<Counts>>);
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Counts {
+    evaluated: usize,
+    failed: usize,
+}
+
+impl Usage {
+    fn counts(&self) -> std::sync::MutexGuard<'_, Vec<Counts>> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn record(&self, index: usize, failed: bool) {
+        let mut counts = self.counts();
+        if counts.len() <= index {
+            counts.resize(index + 1, Counts::default());
+        }
+        counts[index].evaluated += 1;
+        counts[index].failed += usize::from(failed);
+    }
+}
+
+impl Clone for Usage {
+    fn clone(&self) -> Self {
+        Self(Mutex::new(self.counts().clone()))
+    }
+}
+
+/// How often one matcher of a set was evaluated and failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatcherCoverage {
+    pub explanation: String,
+    pub evaluated: usize,
+    pub failed: usize,
+}
+
+/// Summary of which matchers of a set were ever evaluated and which ever failed, see
+/// [`Matchers::coverage`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coverage {
+    matchers: Vec<MatcherCoverage>,
+}
+
+impl Coverage {
+    /// Entries in the order the matchers were added.
+    pub fn matchers(&self) -> &[MatcherCoverage] {
+        &self.matchers
+    }
+
+    /// Matchers no request ever reached, e.g. behind a failing one in
+    /// [`Matchers::validate_first`].
+    pub fn never_evaluated(&self) -> impl Iterator<Item = &MatcherCoverage> {
+        self.matchers.iter().filter(|entry| entry.evaluated == 0)
+    }
+
+    /// Evaluated matchers that never rejected a request and may be dead expectations.
+    pub fn never_failed(&self) -> impl Iterator<Item = &MatcherCoverage> {
+        self.matchers
+            .iter()
+            .filter(|entry| entry.evaluated > 0 && entry.failed == 0)
+    }
+}
+
+impl std::fmt::Display for Coverage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let evaluated = self.matchers.iter().filter(|e| e.evaluated > 0).count();
+        let failed = self.matchers.iter().filter(|e| e.failed > 0).count();
+        write!(
+            f,
+            "{evaluated}/{total} matchers evaluated, {failed}/{total} ever failed",
+            total = self.matchers.len()
+        )
+    }
+}
+
+impl Matchers {
+    /// Validates the matcher at `index`, counting the evaluation towards [`Matchers::coverage`].
+    pub(super) fn check_at(
+        &self,
+        index: usize,
+        matcher: &dyn Match,
+        request: &Request,
+    ) -> Option<Mismatch> {
+        let mismatch = matcher.validate(request);
+        self.usage.record(index, mismatch.is_some());
+        mismatch
+    }
+
+    /// Reports how often each matcher was evaluated and failed since the set was built.
+    pub fn coverage(&self) -> Coverage {
+        let counts = self.usage.counts();
+        let matchers = self
+            .iter()
+            .enumerate()
+            .map(|(index, matcher)| {
+                let Counts { evaluated, failed } = counts.get(index).copied().unwrap_or_default();
+                MatcherCoverage {
+                    explanation: matcher.explain(),
+                    evaluated,
+                    failed,
+                }
+            })
+            .collect();
+        Coverage { matchers }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn evaluated_and_failed(coverage: &Coverage) -> Vec<(usize, usize)> {
+        coverage
+            .matchers()
+            .iter()
+            .map(|entry| (entry.evaluated, entry.failed))
+            .collect()
+    }
+
+    #[test]
+    fn counts_evaluations_and_failures() {
+        let matchers = Matchers::new().method("GET").path("/users");
+
+        assert!(matchers.validate(&Request::from("/users")).is_ok());
+        assert!(matchers.validate(&Request::from("/posts")).is_err());
+
+        let coverage = matchers.coverage();
+        assert_eq!(evaluated_and_failed(&coverage), vec![(2, 0), (2, 1)]);
+        assert_eq!(coverage.matchers()[0].explanation, "method must be `GET`");
+        assert_eq!(coverage.never_failed().count(), 1);
+        assert_eq!(
+            coverage.to_string(),
+            "2/2 matchers evaluated, 1/2 ever failed"
+        );
+    }
+
+    #[test]
+    fn short_circuit_leaves_matchers_unevaluated() {
+        let matchers = Matchers::new().method("POST").path("/users");
+
+        assert!(matchers.validate_first(&Request::from("/users")).is_err());
+
+        let coverage = matchers.coverage();
+        assert_eq!(evaluated_and_failed(&coverage), vec![(1, 1), (0, 0)]);
+        assert_eq!(coverage.never_evaluated().count(), 1);
+    }
+
+    #[test]
+    fn clones_count_independently() {
+        let matchers = Matchers::new().path("/users");
+        matchers.score(&Request::from("/users"));
+        let copy = matchers.clone();
+        copy.score(&Request::from("/users"));
+
+        assert_eq!(evaluated_and_failed(&matchers.coverage()), vec![(1, 0)]);
+        assert_eq!(evaluated_and_failed(&copy.coverage()), vec![(2, 0)]);
+    }
+}
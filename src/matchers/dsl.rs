@@ -0,0 +1,211 @@
+use super::{Matcher, Matchers};
+
+/// Why a matcher DSL string couldn't be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DslError {
+    UnterminatedQuote,
+    UnknownDirective(String),
+    /// A directive such as `header` came last, without its argument.
+    MissingArgument(String),
+    InvalidPort(String),
+}
+
+impl std::fmt::Display for DslError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DslError::UnterminatedQuote => f.write_str("unterminated quote"),
+            DslError::UnknownDirective(token) => write!(f, "unknown directive {token:?}"),
+            DslError::MissingArgument(directive) => {
+                write!(f, "directive {directive:?} needs an argument")
+            }
+            DslError::InvalidPort(port) => write!(f, "invalid port {port:?}"),
+        }
+    }
+}
+
+impl std::error::Error for DslError {}
+
+impl Matchers {
+    /// Parses the compact matcher DSL, e.g.
+    /// `GET /users/{id}?page=2 header Content-Type=application/json !header X-Debug body~"error"`.
+    ///
+    /// An optional method and path (`{..}` makes it a template, a trailing `*` a prefix) come
+    /// first, followed by directives:
+    /// `header|query|cookie|form NAME[=VALUE]`, `!header|!query|!cookie|!form NAME`,
+    /// `host H`, `scheme S`, `port N`, `body=TEXT`, `body~TEXT` (contains), `body=~REGEX`
+    /// and `!body`. Values containing spaces can be wrapped in double quotes.
+    pub fn parse(input: &str) -> Result<Matchers, DslError> {
+        let tokens = tokenize(input)?;
+        let mut tokens = tokens.into_iter().peekable();
+        let mut matchers = Matchers::new();
+
+        if let Some(method) =
+            tokens.next_if(|t| !t.is_empty() && t.bytes().all(|b| b.is_ascii_uppercase()))
+        {
+            matchers.add(Matcher::Method(method));
+        }
+        if let Some(target) = tokens.next_if(|t| t.starts_with('/')) {
+            add_target(&mut matchers, &target);
+        }
+
+        while let Some(token) = tokens.next() {
+            let mut argument = || {
+                tokens
+                    .next()
+                    .ok_or_else(|| DslError::MissingArgument(token.clone()))
+            };
+            let matcher = match token.as_str() {
+                "header" => pair(argument()?, Matcher::HeaderExists, Matcher::HeaderEq),
+                "!header" => Matcher::HeaderMiss(argument()?),
+                "query" => pair(argument()?, Matcher::QueryExists, Matcher::QueryEq),
+                "!query" => Matcher::QueryMiss(argument()?),
+                "cookie" => pair(argument()?, Matcher::CookieExists, Matcher::CookieEq),
+                "!cookie" => Matcher::CookieMiss(argument()?),
+                "form" => pair(argument()?, Matcher::FormFieldExists, Matcher::FormFieldEq),
+                "!form" => Matcher::FormFieldMiss(argument()?),
+                "host" => Matcher::Host(argument()?),
+                "scheme" => Matcher::Scheme(argument()?),
+                "port" => {
+                    let port = argument()?;
+                    Matcher::Port(port.parse().map_err(|_| DslError::InvalidPort(port))?)
+                }
+                "!body" => Matcher::BodyMiss,
+                _ => {
+                    if let Some(pattern) = token.strip_prefix("body=~") {
+                        Matcher::BodyRegex(pattern.into())
+                    } else if let Some(body) = token.strip_prefix("body=") {
+                        Matcher::BodyEq(body.into())
+                    } else if let Some(needle) = token.strip_prefix("body~") {
+                        Matcher::BodyContains(needle.into())
+                    } else {
+                        return Err(DslError::UnknownDirective(token));
+                    }
+                }
+            };
+            matchers.add(matcher);
+        }
+
+        Ok(matchers)
+    }
+}
+
+impl std::str::FromStr for Matchers {
+    type Err = DslError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Matchers::parse(s)
+    }
+}
+
+fn add_target(matchers: &mut Matchers, target: &str) {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+
+    matchers.add(if let Some(prefix) = path.strip_suffix('*') {
+        Matcher::PathPrefix(prefix.into())
+    } else if path.contains('{') {
+        Matcher::PathTemplate(path.into())
+    } else {
+        Matcher::Path(path.into())
+    });
+
+    for param in query.split('&').filter(|p| !p.is_empty()) {
+        matchers.add(pair(param.into(), Matcher::QueryExists, Matcher::QueryEq));
+    }
+}
+
+/// Builds `eq(name, value)` for `name=value`, or `exists(name)` for a bare name.
+fn pair(
+    argument: String,
+    exists: fn(String) -> Matcher,
+    eq: fn(String, String) -> Matcher,
+) -> Matcher {
+    match argument.split_once('=') {
+        Some((name, value)) => eq(name.into(), value.into()),
+        None => exists(argument),
+    }
+}
+
+/// Splits on whitespace, keeping double-quoted sections (with `\"` escapes) together.
+fn tokenize(input: &str) -> Result<Vec<String>, DslError> {
+    let mut tokens = Vec::new();
+    let mut current: Option<String> = None;
+    let mut chars = input.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                let token = current.get_or_insert_with(String::new);
+                loop {
+                    match chars.next().ok_or(DslError::UnterminatedQuote)? {
+                        '"' => break,
+                        '\\' => token.push(chars.next().ok_or(DslError::UnterminatedQuote)?),
+                        ch => token.push(ch),
+                    }
+                }
+            }
+            ch if ch.is_whitespace() => tokens.extend(current.take()),
+            ch => current.get_or_insert_with(String::new).push(ch),
+        }
+    }
+    tokens.extend(current);
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn s(value: &str) -> String {
+        value.into()
+    }
+
+    fn parsed(input: &str) -> Vec<Matcher> {
+        Matchers::parse(input)
+            .unwrap()
+            .iter()
+            .filter_map(|m| m.as_matcher().cloned())
+            .collect()
+    }
+
+    #[test]
+    fn parses_full_example() {
+        assert_eq!(
+            parsed(
+                r#"GET /users/{id}?page=2 header Content-Type=application/json !header X-Debug body~"error""#
+            ),
+            vec![
+                Matcher::Method(s("GET")),
+                Matcher::PathTemplate(s("/users/{id}")),
+                Matcher::QueryEq(s("page"), s("2")),
+                Matcher::HeaderEq(s("Content-Type"), s("application/json")),
+                Matcher::HeaderMiss(s("X-Debug")),
+                Matcher::BodyContains(s("error")),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::path_only("/health", vec![Matcher::Path(s("/health"))])]
+    #[case::prefix("/api/*", vec![Matcher::PathPrefix(s("/api/"))])]
+    #[case::query_flag("/search?q=rust&exact", vec![Matcher::Path(s("/search")), Matcher::QueryEq(s("q"), s("rust")), Matcher::QueryExists(s("exact"))])]
+    #[case::quoted(r#"header Authorization="Bearer a b""#, vec![Matcher::HeaderEq(s("Authorization"), s("Bearer a b"))])]
+    #[case::escaped(r#"body="say \"hi\"""#, vec![Matcher::BodyEq(s(r#"say "hi""#))])]
+    #[case::regex(r#"body=~"^\{""#, vec![Matcher::BodyRegex(s("^{"))])]
+    #[case::authority("host api.test port 8443 scheme https", vec![Matcher::Host(s("api.test")), Matcher::Port(8443), Matcher::Scheme(s("https"))])]
+    #[case::misses("!query debug !cookie session !form age !body", vec![Matcher::QueryMiss(s("debug")), Matcher::CookieMiss(s("session")), Matcher::FormFieldMiss(s("age")), Matcher::BodyMiss])]
+    #[case::empty("", vec![])]
+    fn parses(#[case] input: &str, #[case] expected: Vec<Matcher>) {
+        assert_eq!(parsed(input), expected, "DSL {input:?} should parse");
+    }
+
+    #[rstest]
+    #[case::quote(r#"body="open"#, DslError::UnterminatedQuote)]
+    #[case::directive("GET /x bogus", DslError::UnknownDirective(s("bogus")))]
+    #[case::argument("header", DslError::MissingArgument(s("header")))]
+    #[case::port("port http", DslError::InvalidPort(s("http")))]
+    fn rejects(#[case] input: &str, #[case] error: DslError) {
+        assert_eq!(input.parse::<Matchers>().map(|m| m.len()), Err(error));
+    }
+}
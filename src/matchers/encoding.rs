@@ -0,0 +1,115 @@
+use crate::request::Request;
+use std::io::Read;
+
+/// Returns a copy of `request` with its body decoded according to `Content-Encoding`.
+///
+/// Yields `None` when there is nothing to decode, or when an encoding is unknown or the body
+/// fails to decode, in which case matchers see the body as it was sent.
+pub(super) fn decode(request: &Request) -> Option<Request> {
+    let encodings = request.header("Content-Encoding")?;
+    let mut body = request.body.bytes()?.into_owned();
+
+    // Encodings are listed in the order they were applied, so undo them from the last one.
+    for encoding in encodings.rsplit(',').map(str::trim) {
+        body = match encoding.to_ascii_lowercase().as_str() {
+            "identity" | "" => body,
+            "gzip" | "x-gzip" => read(flate2::read::MultiGzDecoder::new(body.as_slice()))?,
+            "deflate" => read(flate2::read::ZlibDecoder::new(body.as_slice()))
+                .or_else(|| read(flate2::read::DeflateDecoder::new(body.as_slice())))?,
+            "br" => read(brotli::Decompressor::new(body.as_slice(), 4096))?,
+            _ => return None,
+        };
+    }
+
+    let mut decoded = request.clone();
+    decoded.body = body.into();
+    Some(decoded)
+}
+
+fn read(mut reader: impl Read) -> Option<Vec<u8>> {
+    let mut decoded = Vec::new();
+    reader.read_to_end(&mut decoded).ok()?;
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matchers::Matcher;
+    use rstest::rstest;
+    use std::io::Write;
+
+    const PAYLOAD: &str = r#"{"event":"push","ref":"refs/heads/main"}"#;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn raw_deflate(data: &[u8]) -> Vec<u8> {
+        let mut encoder =
+            flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn brotli(data: &[u8]) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        {
+            let mut encoder = brotli::CompressorWriter::new(&mut encoded, 4096, 5, 22);
+            encoder.write_all(data).unwrap();
+        }
+        encoded
+    }
+
+    fn encoded(encoding: &str, body: Vec<u8>) -> Request {
+        Request::default()
+            .with_header("Content-Encoding", encoding)
+            .with_body(body)
+    }
+
+    #[rstest]
+    #[case::gzip(encoded("gzip", gzip(PAYLOAD.as_bytes())))]
+    #[case::x_gzip(encoded("x-gzip", gzip(PAYLOAD.as_bytes())))]
+    #[case::deflate(encoded("deflate", zlib(PAYLOAD.as_bytes())))]
+    #[case::raw_deflate(encoded("deflate", raw_deflate(PAYLOAD.as_bytes())))]
+    #[case::brotli(encoded("BR", brotli(PAYLOAD.as_bytes())))]
+    #[case::stacked(encoded("deflate, gzip", gzip(&zlib(PAYLOAD.as_bytes()))))]
+    #[case::identity(encoded("identity", PAYLOAD.into()))]
+    #[case::plain(Request::default().with_body(PAYLOAD))]
+    fn body_matchers_see_decoded_body(#[case] request: Request) {
+        for matcher in [
+            Matcher::BodyEq(PAYLOAD.into()),
+            Matcher::BodyContains("refs/heads/main".into()),
+            Matcher::Not(Box::new(Matcher::BodyContains("tag".into()))),
+        ] {
+            assert!(
+                matcher.observed(&request).is_none(),
+                "Matcher {matcher:?} should match request: {request}"
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::corrupt(encoded("gzip", b"not gzip".to_vec()), Matcher::BodyEq("not gzip".into()))]
+    #[case::unknown(encoded("zstd", b"opaque".to_vec()), Matcher::BodyEq("opaque".into()))]
+    #[case::wrong_value(encoded("gzip", gzip(b"other")), Matcher::BodyEq("other".into()))]
+    fn reports_decoded_or_raw_body(#[case] request: Request, #[case] report: Matcher) {
+        let matcher = Matcher::BodyEq(PAYLOAD.into());
+
+        assert_eq!(
+            matcher.observed(&request),
+            Some(report.clone()),
+            "Matcher {matcher:?} should report {report:?}"
+        );
+        assert!(report.observed(&request).is_none());
+    }
+}
@@ -0,0 +1,289 @@
+use super::{HashAlgorithm, Matcher, Matchers, ValueFormat, truncate};
+use std::time::SystemTime;
+
+impl Matcher {
+    /// Describes the expectation as a sentence, e.g. "query parameter `page` must equal `2`".
+    pub fn explain(&self) -> String {
+        match self {
+            Matcher::Method(method) => format!("method must be `{method}`"),
+            Matcher::MethodAny(methods) => format!("method must be one of {}", list(methods)),
+            Matcher::Scheme(scheme) => format!("scheme must be `{scheme}`"),
+            Matcher::SchemeMiss => "scheme must be absent".into(),
+            Matcher::Host(host) => format!("host must be `{host}`"),
+            Matcher::Port(port) => format!("port must be `{port}`"),
+            Matcher::PortMiss => "port must be absent".into(),
+            Matcher::Version(version) => format!("HTTP version must be `{version}`"),
+            Matcher::Path(path) => format!("path must equal `{path}`"),
+            Matcher::PathRegex(pattern) => format!("path must match regex `{pattern}`"),
+            Matcher::PathTemplate(template) => format!("path must match template `{template}`"),
+            Matcher::PathPrefix(prefix) => format!("path must start with `{prefix}`"),
+            Matcher::PathSuffix(suffix) => format!("path must end with `{suffix}`"),
+            Matcher::QueryExists(key) => format!("query parameter `{key}` must be present"),
+            Matcher::QueryMiss(key) => format!("query parameter `{key}` must be absent"),
+            Matcher::QueryEq(key, value) => {
+                format!("query parameter `{key}` must equal `{value}`")
+            }
+            Matcher::QueryContainsValue(key, value) => {
+                format!("query parameter `{key}` must include the value `{value}`")
+            }
+            Matcher::QueryEqAll(key, values) => {
+                format!(
+                    "query parameter `{key}` must have exactly the values {}",
+                    list(values)
+                )
+            }
+            Matcher::QueryFormat(key, format) => {
+                format!("query parameter `{key}` must be {}", format_name(format))
+            }
+            Matcher::FragmentEq(fragment) => format!("fragment must equal `{fragment}`"),
+            Matcher::FragmentMiss => "fragment must be absent".into(),
+            Matcher::HeaderExists(name) => format!("header `{name}` must be present"),
+            Matcher::HeaderMiss(name) => format!("header `{name}` must be absent"),
+            Matcher::HeaderEq(name, value) => format!("header `{name}` must equal `{value}`"),
+            Matcher::HeaderEqAll(name, values) => {
+                format!(
+                    "header `{name}` must have exactly the values {}",
+                    list(values)
+                )
+            }
+            Matcher::CookieExists(name) => format!("cookie `{name}` must be present"),
+            Matcher::CookieMiss(name) => format!("cookie `{name}` must be absent"),
+            Matcher::CookieEq(name, value) => format!("cookie `{name}` must equal `{value}`"),
+            Matcher::BearerToken(token) => format!("bearer token must be `{token}`"),
+            Matcher::BasicAuth(user, _) => {
+                format!("basic auth must be user `{user}` with the expected password")
+            }
+            Matcher::ContentType(media_type) => format!("content type must be `{media_type}`"),
+            Matcher::AcceptsLanguage(language) => format!("language `{language}` must be accepted"),
+            Matcher::PrefersLanguage(language) => {
+                format!("language `{language}` must be the most preferred")
+            }
+            Matcher::Range(start, end) => format!(
+                "range must be `bytes={}-{}`",
+                start.map(|s| s.to_string()).unwrap_or_default(),
+                end.map(|e| e.to_string()).unwrap_or_default()
+            ),
+            Matcher::IfNoneMatch(etag) => format!("`If-None-Match` must list ETag `{etag}`"),
+            Matcher::IfMatch(etag) => format!("`If-Match` must list ETag `{etag}`"),
+            Matcher::IfModifiedSince(time) => {
+                format!(
+                    "`If-Modified-Since` must not be before `{}`",
+                    http_date(*time)
+                )
+            }
+            Matcher::IfUnmodifiedSince(time) => {
+                format!(
+                    "`If-Unmodified-Since` must not be before `{}`",
+                    http_date(*time)
+                )
+            }
+            Matcher::CacheControl(directive, Some(value)) => {
+                format!("`Cache-Control` must contain `{directive}={value}`")
+            }
+            Matcher::CacheControl(directive, None) => {
+                format!("`Cache-Control` must contain `{directive}`")
+            }
+            Matcher::CacheControlMiss(directive) => {
+                format!("`Cache-Control` must not contain `{directive}`")
+            }
+            Matcher::BodyExists => "body must be present".into(),
+            Matcher::BodyMiss => "body must be absent".into(),
+            Matcher::BodyEq(body) => format!("body must equal `{}`", truncate(body)),
+            Matcher::BodyBytesEq(bytes) => {
+                format!("body must equal the given {} bytes", bytes.len())
+            }
+            Matcher::BodyBase64Eq(body) => format!("body must equal base64 `{}`", truncate(body)),
+            Matcher::BodyHash(algorithm, digest) => {
+                format!(
+                    "body {} digest must be `{digest}`",
+                    algorithm_name(algorithm)
+                )
+            }
+            Matcher::HmacSignature(header, algorithm, _) => format!(
+                "header `{header}` must carry the HMAC-{} signature of the body",
+                algorithm_name(algorithm)
+            ),
+            Matcher::BodyContains(needle) => format!("body must contain `{}`", truncate(needle)),
+            Matcher::BodyRegex(pattern) => format!("body must match regex `{pattern}`"),
+            Matcher::FormFieldExists(name) => format!("form field `{name}` must be present"),
+            Matcher::FormFieldMiss(name) => format!("form field `{name}` must be absent"),
+            Matcher::FormFieldEq(name, value) => {
+                format!("form field `{name}` must equal `{value}`")
+            }
+            Matcher::Not(inner) => format!("it must not hold that {}", inner.explain()),
+            Matcher::Named(label, inner) => format!("{label} ({})", inner.explain()),
+            Matcher::AnyOf(alternatives) => {
+                format!("at least one must hold: {}", join(alternatives, " or "))
+            }
+            Matcher::AllOf(matchers) => format!("all must hold: {}", join(matchers, " and ")),
+            #[cfg(feature = "json")]
+            Matcher::BodyJsonEq(json) => format!("JSON body must equal `{json}`"),
+            #[cfg(feature = "json")]
+            Matcher::BodyJsonIncludes(json) => format!("JSON body must include `{json}`"),
+            #[cfg(feature = "json")]
+            Matcher::BodyJsonPointer(pointer, Some(value)) => {
+                format!("JSON pointer `{pointer}` must equal `{value}`")
+            }
+            #[cfg(feature = "json")]
+            Matcher::BodyJsonPointer(pointer, None) => {
+                format!("JSON pointer `{pointer}` must be present")
+            }
+            #[cfg(feature = "json")]
+            Matcher::JsonPath(path, Some(value)) => {
+                format!("JSONPath `{path}` must select `{value}`")
+            }
+            #[cfg(feature = "json")]
+            Matcher::JsonPath(path, None) => format!("JSONPath `{path}` must select a value"),
+            #[cfg(feature = "json")]
+            Matcher::JsonPathMiss(path) => format!("JSONPath `{path}` must select nothing"),
+            #[cfg(feature = "json-schema")]
+            Matcher::BodyJsonSchema(_) => "JSON body must conform to the schema".into(),
+            #[cfg(feature = "graphql")]
+            Matcher::GraphQLOperation(name) => format!("GraphQL operation must be `{name}`"),
+            #[cfg(feature = "graphql")]
+            Matcher::GraphQLQuery(query) => format!("GraphQL query must equal `{query}`"),
+            #[cfg(feature = "graphql")]
+            Matcher::GraphQLVariablesEq(variables) => {
+                format!("GraphQL variables must equal `{variables}`")
+            }
+            #[cfg(feature = "graphql")]
+            Matcher::GraphQLVariablesIncludes(variables) => {
+                format!("GraphQL variables must include `{variables}`")
+            }
+            #[cfg(feature = "protobuf")]
+            Matcher::BodyProtobuf(message) => format!(
+                "body must decode to the expected `{}` message",
+                prost_reflect::ReflectMessage::descriptor(message).full_name()
+            ),
+            #[cfg(feature = "xml")]
+            Matcher::BodyXmlEq(xml) => format!("XML body must equal `{}`", truncate(xml)),
+            #[cfg(feature = "xml")]
+            Matcher::BodyXPath(path, Some(value)) => {
+                format!("XPath `{path}` must select `{value}`")
+            }
+            #[cfg(feature = "xml")]
+            Matcher::BodyXPath(path, None) => format!("XPath `{path}` must select a node"),
+            #[cfg(feature = "xml")]
+            Matcher::BodyXPathMiss(path) => format!("XPath `{path}` must select nothing"),
+        }
+    }
+}
+
+impl Matchers {
+    /// Describes every expectation in the set, one sentence each, in order.
+    pub fn explain(&self) -> Vec<String> {
+        self.iter().map(|matcher| matcher.explain()).collect()
+    }
+}
+
+fn list(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| format!("`{value}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn join(matchers: &[Matcher], separator: &str) -> String {
+    matchers
+        .iter()
+        .map(|matcher| format!("({})", matcher.explain()))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn format_name(format: &ValueFormat) -> &'static str {
+    match format {
+        ValueFormat::Uuid => "a UUID",
+        ValueFormat::Integer => "an integer",
+        ValueFormat::Date => "a `YYYY-MM-DD` date",
+    }
+}
+
+fn algorithm_name(algorithm: &HashAlgorithm) -> &'static str {
+    match algorithm {
+        HashAlgorithm::Sha256 => "SHA-256",
+        HashAlgorithm::Sha384 => "SHA-384",
+        HashAlgorithm::Sha512 => "SHA-512",
+    }
+}
+
+/// Formats `time` as an IMF-fixdate such as `Sun, 06 Nov 1994 08:49:37 GMT`.
+pub(super) fn http_date(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs()) as i64;
+    let (days, clock) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+    // Inverse of the days-from-civil conversion used when parsing HTTP dates.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let weekday = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"][days.rem_euclid(7) as usize];
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ][month as usize - 1];
+
+    format!(
+        "{weekday}, {day:02} {month} {year} {:02}:{:02}:{:02} GMT",
+        clock / 3_600,
+        clock % 3_600 / 60,
+        clock % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn s(value: &str) -> String {
+        value.into()
+    }
+
+    #[rstest::rstest]
+    #[case::query(
+        Matcher::QueryEq(s("page"), s("2")),
+        "query parameter `page` must equal `2`"
+    )]
+    #[case::method_any(Matcher::MethodAny(vec![s("PUT"), s("PATCH")]), "method must be one of `PUT`, `PATCH`")]
+    #[case::header_miss(Matcher::HeaderMiss(s("X-Debug")), "header `X-Debug` must be absent")]
+    #[case::format(
+        Matcher::QueryFormat(s("id"), ValueFormat::Uuid),
+        "query parameter `id` must be a UUID"
+    )]
+    #[case::range(Matcher::Range(Some(500), None), "range must be `bytes=500-`")]
+    #[case::not(
+        Matcher::Not(Box::new(Matcher::BodyMiss)),
+        "it must not hold that body must be absent"
+    )]
+    #[case::named(
+        Matcher::named("auth", Matcher::BearerToken(s("t"))),
+        "auth (bearer token must be `t`)"
+    )]
+    #[case::any_of(Matcher::AnyOf(vec![Matcher::Port(80), Matcher::Port(443)]), "at least one must hold: (port must be `80`) or (port must be `443`)")]
+    #[case::date(Matcher::IfModifiedSince(SystemTime::UNIX_EPOCH + Duration::from_secs(784_111_777)), "`If-Modified-Since` must not be before `Sun, 06 Nov 1994 08:49:37 GMT`")]
+    fn explain(#[case] matcher: Matcher, #[case] sentence: &str) {
+        assert_eq!(matcher.explain(), sentence);
+    }
+
+    #[test]
+    fn explain_matchers() {
+        let matchers = Matchers::new().method("GET").header_exists("Accept");
+
+        assert_eq!(
+            matchers.explain(),
+            vec![
+                s("method must be `GET`"),
+                s("header `Accept` must be present")
+            ]
+        );
+    }
+}
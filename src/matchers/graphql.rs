@@ -0,0 +1,169 @@
+use super::Matcher;
+use super::json::{first_difference, first_exclusion, parse_body};
+use crate::request::Request;
+use graphql_parser::query::{Definition, Document, OperationDefinition, parse_query};
+use serde_json::Value;
+
+/// The parts of a GraphQL-over-HTTP POST body the matchers look at.
+struct Operation {
+    name: Option<String>,
+    query: String,
+    variables: Value,
+}
+
+fn parse_operation(request: &Request) -> Result<Operation, Matcher> {
+    let body = parse_body(request)?;
+    let Some(query) = body.get("query").and_then(Value::as_str) else {
+        return Err(Matcher::JsonPathMiss("$.query".into()));
+    };
+
+    let name = body
+        .get("operationName")
+        .and_then(Value::as_str)
+        .map(String::from)
+        .or_else(|| single_operation_name(query));
+    let variables = match body.get("variables") {
+        Some(Value::Null) | None => Value::Object(Default::default()),
+        Some(variables) => variables.clone(),
+    };
+
+    Ok(Operation {
+        name,
+        query: query.to_string(),
+        variables,
+    })
+}
+
+/// Names the operation of a document that defines exactly one.
+fn single_operation_name(query: &str) -> Option<String> {
+    let document: Document<String> = parse_query(query).ok()?;
+    let mut names = document.definitions.into_iter().filter_map(|definition| {
+        let Definition::Operation(operation) = definition else {
+            return None;
+        };
+        Some(match operation {
+            OperationDefinition::Query(query) => query.name,
+            OperationDefinition::Mutation(mutation) => mutation.name,
+            OperationDefinition::Subscription(subscription) => subscription.name,
+            OperationDefinition::SelectionSet(_) => None,
+        })
+    });
+
+    match (names.next(), names.next()) {
+        (Some(name), None) => name,
+        _ => None,
+    }
+}
+
+/// Reformats a document so formatting, comments and insignificant commas don't matter.
+///
+/// Text that doesn't parse is only whitespace-collapsed, so it can still be compared.
+fn normalize(query: &str) -> String {
+    match parse_query::<String>(query) {
+        Ok(document) => document.to_string(),
+        Err(_) => query.split_whitespace().collect::<Vec<_>>().join(" "),
+    }
+}
+
+pub(super) fn validate_operation(expected: &str, request: &Request) -> Option<Matcher> {
+    let operation = match parse_operation(request) {
+        Ok(operation) => operation,
+        Err(report) => return Some(report),
+    };
+
+    match operation.name {
+        Some(actual) if actual == expected => None,
+        Some(actual) => Some(Matcher::GraphQLOperation(actual)),
+        None => Some(Matcher::GraphQLQuery(operation.query)),
+    }
+}
+
+pub(super) fn validate_query(expected: &str, request: &Request) -> Option<Matcher> {
+    let operation = match parse_operation(request) {
+        Ok(operation) => operation,
+        Err(report) => return Some(report),
+    };
+
+    if normalize(&operation.query) == normalize(expected) {
+        None
+    } else {
+        Some(Matcher::GraphQLQuery(operation.query))
+    }
+}
+
+pub(super) fn validate_variables(
+    expected: &Value,
+    exact: bool,
+    request: &Request,
+) -> Option<Matcher> {
+    let operation = match parse_operation(request) {
+        Ok(operation) => operation,
+        Err(report) => return Some(report),
+    };
+
+    let difference = if exact {
+        first_difference(expected, &operation.variables, String::new())
+    } else {
+        first_exclusion(expected, &operation.variables, String::new())
+    };
+    difference.map(|_| Matcher::GraphQLVariablesEq(operation.variables))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn graphql(body: Value) -> Request {
+        Request::default()
+            .with_method("POST")
+            .with_body(body.to_string())
+    }
+
+    fn user_query() -> Request {
+        graphql(json!({
+            "query": "query GetUser($id: ID!) {\n  user(id: $id) { id, name }  # trailing comment\n}",
+            "variables": {"id": "42", "withPosts": false}
+        }))
+    }
+
+    #[rstest]
+    #[case::operation(Matcher::GraphQLOperation("GetUser".into()))]
+    #[case::query(Matcher::GraphQLQuery("query GetUser($id: ID!) { user(id: $id) { id name } }".into()))]
+    #[case::variables_includes(Matcher::GraphQLVariablesIncludes(json!({"id": "42"})))]
+    #[case::variables_eq(Matcher::GraphQLVariablesEq(json!({"withPosts": false, "id": "42"})))]
+    fn graphql_matches(#[case] matcher: Matcher) {
+        let request = user_query();
+
+        assert!(
+            matcher.observed(&request).is_none(),
+            "Matcher {matcher:?} should match request: {request}"
+        );
+    }
+
+    #[rstest]
+    #[case::operation(Matcher::GraphQLOperation("ListUsers".into()), user_query(), Matcher::GraphQLOperation("GetUser".into()))]
+    #[case::operation_name_field(Matcher::GraphQLOperation("A".into()), graphql(json!({"query": "query A { a } query B { b }", "operationName": "B"})), Matcher::GraphQLOperation("B".into()))]
+    #[case::anonymous(Matcher::GraphQLOperation("A".into()), graphql(json!({"query": "{ a }"})), Matcher::GraphQLQuery("{ a }".into()))]
+    #[case::query(Matcher::GraphQLQuery("{ users { id } }".into()), graphql(json!({"query": "{ user { id } }"})), Matcher::GraphQLQuery("{ user { id } }".into()))]
+    #[case::variables_includes(Matcher::GraphQLVariablesIncludes(json!({"id": "7"})), user_query(), Matcher::GraphQLVariablesEq(json!({"id": "42", "withPosts": false})))]
+    #[case::variables_eq(Matcher::GraphQLVariablesEq(json!({"id": "42"})), user_query(), Matcher::GraphQLVariablesEq(json!({"id": "42", "withPosts": false})))]
+    #[case::no_variables(Matcher::GraphQLVariablesIncludes(json!({"id": "7"})), graphql(json!({"query": "{ a }", "variables": null})), Matcher::GraphQLVariablesEq(json!({})))]
+    #[case::not_graphql(Matcher::GraphQLQuery("{ a }".into()), graphql(json!({"data": 1})), Matcher::JsonPathMiss("$.query".into()))]
+    fn graphql_reports(
+        #[case] matcher: Matcher,
+        #[case] request: Request,
+        #[case] report: Matcher,
+    ) {
+        assert_eq!(
+            matcher.observed(&request),
+            Some(report.clone()),
+            "Matcher {matcher:?} should report {report:?} for request: {request}"
+        );
+        assert!(
+            report.observed(&request).is_none(),
+            "Report {report:?} should itself match request: {request}"
+        );
+    }
+}
@@ -0,0 +1,464 @@
+use super::{Matcher, body_preview};
+use crate::body::Body;
+use crate::request::Request;
+use serde_json::Value;
+use serde_json_path::JsonPath;
+
+/// Parses the request body as JSON, or returns the matcher describing why it couldn't be.
+pub(super) fn parse_body(request: &Request) -> Result<Value, Matcher> {
+    if let Body::Json(value) = &request.body {
+        return Ok(value.clone());
+    }
+    match request.body.bytes().as_deref() {
+        Some(body) => serde_json::from_slice(body).map_err(|_| body_preview(body)),
+        None => Err(Matcher::BodyMiss),
+    }
+}
+
+pub(super) fn validate_eq(expected: &Value, request: &Request) -> Option<Matcher> {
+    let actual = match parse_body(request) {
+        Ok(actual) => actual,
+        Err(report) => return Some(report),
+    };
+
+    first_difference(expected, &actual, String::new())
+        .map(|(pointer, actual)| Matcher::BodyJsonPointer(pointer, actual))
+}
+
+pub(super) fn validate_includes(expected: &Value, request: &Request) -> Option<Matcher> {
+    let actual = match parse_body(request) {
+        Ok(actual) => actual,
+        Err(report) => return Some(report),
+    };
+
+    first_exclusion(expected, &actual, String::new())
+        .map(|(pointer, actual)| Matcher::BodyJsonPointer(pointer, actual))
+}
+
+pub(super) fn validate_pointer(
+    pointer: &str,
+    expected: &Option<Value>,
+    request: &Request,
+) -> Option<Matcher> {
+    let actual = match parse_body(request) {
+        Ok(actual) => actual,
+        Err(report) => return Some(report),
+    };
+
+    let found = actual.pointer(pointer);
+    if found == expected.as_ref() {
+        None
+    } else {
+        Some(Matcher::BodyJsonPointer(
+            pointer.to_string(),
+            found.cloned(),
+        ))
+    }
+}
+
+/// Selects the nodes a JSONPath expression points to; invalid expressions select nothing.
+fn select(path: &str, body: &Value) -> Vec<Value> {
+    JsonPath::parse(path)
+        .map(|path| path.query(body).all().into_iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+pub(super) fn validate_path(
+    path: &str,
+    expected: &Option<Value>,
+    request: &Request,
+) -> Option<Matcher> {
+    let body = match parse_body(request) {
+        Ok(body) => body,
+        Err(report) => return Some(report),
+    };
+
+    let nodes = select(path, &body);
+    match (expected, nodes.first()) {
+        (_, None) => Some(Matcher::JsonPathMiss(path.to_string())),
+        (None, Some(_)) => None,
+        (Some(expected), Some(_)) if nodes.contains(expected) => None,
+        (Some(_), Some(actual)) => Some(Matcher::JsonPath(path.to_string(), Some(actual.clone()))),
+    }
+}
+
+pub(super) fn validate_path_miss(path: &str, request: &Request) -> Option<Matcher> {
+    let body = parse_body(request).ok()?;
+
+    select(path, &body)
+        .into_iter()
+        .next()
+        .map(|actual| Matcher::JsonPath(path.to_string(), Some(actual)))
+}
+
+#[cfg(feature = "json-schema")]
+struct SchemaViolation {
+    instance_path: String,
+    description: String,
+}
+
+/// Validates `body` against `schema`; a schema that fails to compile is reported as a violation
+/// at the document root.
+#[cfg(feature = "json-schema")]
+fn schema_violations(schema: &Value, body: &Value) -> Vec<SchemaViolation> {
+    match jsonschema::validator_for(schema) {
+        Ok(validator) => validator
+            .iter_errors(body)
+            .map(|error| SchemaViolation {
+                instance_path: error.instance_path().as_str().to_string(),
+                description: format!(
+                    "{}: {error} (schema {})",
+                    error.instance_path().as_str(),
+                    error.schema_path().as_str()
+                ),
+            })
+            .collect(),
+        Err(error) => vec![SchemaViolation {
+            instance_path: String::new(),
+            description: format!("invalid schema: {error}"),
+        }],
+    }
+}
+
+#[cfg(feature = "json-schema")]
+pub(super) fn validate_schema(schema: &Value, request: &Request) -> Option<Matcher> {
+    let body = match parse_body(request) {
+        Ok(body) => body,
+        Err(report) => return Some(report),
+    };
+
+    schema_violations(schema, &body)
+        .into_iter()
+        .next()
+        .map(|violation| {
+            let actual = body.pointer(&violation.instance_path).cloned();
+            Matcher::BodyJsonPointer(violation.instance_path, actual)
+        })
+}
+
+#[cfg(feature = "json-schema")]
+pub(super) fn schema_errors(schema: &Value, request: &Request) -> Vec<String> {
+    match parse_body(request) {
+        Ok(body) => schema_violations(schema, &body)
+            .into_iter()
+            .map(|violation| violation.description)
+            .collect(),
+        Err(_) => vec!["body is not a JSON document".to_string()],
+    }
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Finds the first location where `actual` diverges from `expected`.
+///
+/// Returns the JSON pointer of that location together with the actual value found there
+/// (`None` when the location is absent from `actual`).
+pub(super) fn first_difference(
+    expected: &Value,
+    actual: &Value,
+    pointer: String,
+) -> Option<(String, Option<Value>)> {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            for (key, expected_val) in expected {
+                let location = format!("{pointer}/{}", escape(key));
+                match actual.get(key) {
+                    Some(actual_val) => {
+                        if let Some(diff) = first_difference(expected_val, actual_val, location) {
+                            return Some(diff);
+                        }
+                    }
+                    None => return Some((location, None)),
+                }
+            }
+
+            actual
+                .iter()
+                .find(|(key, _)| !expected.contains_key(key.as_str()))
+                .map(|(key, val)| (format!("{pointer}/{}", escape(key)), Some(val.clone())))
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            for (idx, (expected_val, actual_val)) in expected.iter().zip(actual).enumerate() {
+                let location = format!("{pointer}/{idx}");
+                if let Some(diff) = first_difference(expected_val, actual_val, location) {
+                    return Some(diff);
+                }
+            }
+
+            let idx = expected.len().min(actual.len());
+            match expected.len().cmp(&actual.len()) {
+                std::cmp::Ordering::Equal => None,
+                _ => Some((format!("{pointer}/{idx}"), actual.get(idx).cloned())),
+            }
+        }
+        (expected, actual) if expected == actual => None,
+        (_, actual) => Some((pointer, Some(actual.clone()))),
+    }
+}
+
+/// Finds the first location where `expected` is not a subset of `actual`.
+///
+/// Objects may carry extra keys, and every expected array item must be included by some
+/// actual item regardless of position.
+pub(super) fn first_exclusion(
+    expected: &Value,
+    actual: &Value,
+    pointer: String,
+) -> Option<(String, Option<Value>)> {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            expected.iter().find_map(|(key, expected_val)| {
+                let location = format!("{pointer}/{}", escape(key));
+                match actual.get(key) {
+                    Some(actual_val) => first_exclusion(expected_val, actual_val, location),
+                    None => Some((location, None)),
+                }
+            })
+        }
+        (Value::Array(expected_items), Value::Array(actual_items)) => {
+            let included = expected_items.iter().all(|expected_val| {
+                actual_items.iter().any(|actual_val| {
+                    first_exclusion(expected_val, actual_val, String::new()).is_none()
+                })
+            });
+
+            if included {
+                None
+            } else {
+                Some((pointer, Some(actual.clone())))
+            }
+        }
+        (expected, actual) if expected == actual => None,
+        (_, actual) => Some((pointer, Some(actual.clone()))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    fn pointer(pointer: &str, value: Option<Value>) -> Matcher {
+        Matcher::BodyJsonPointer(pointer.into(), value)
+    }
+
+    fn with_body(body: &str) -> Request {
+        Request::default().with_body(body)
+    }
+
+    #[test]
+    fn pre_parsed_body() {
+        let request = Request::default().with_body(json!({"user": {"id": 42}}));
+
+        assert_eq!(
+            Matcher::BodyJsonPointer("/user/id".into(), Some(json!(42))).observed(&request),
+            None
+        );
+        assert_eq!(
+            Matcher::BodyEq(r#"{"user":{"id":42}}"#.into()).observed(&request),
+            None
+        );
+    }
+
+    #[rstest]
+    #[case::key_order(json!({"a": 1, "b": [1, 2]}), r#"{ "b": [1, 2],  "a": 1 }"#)]
+    #[case::nested(json!({"user": {"id": 42, "tags": []}}), r#"{"user":{"tags":[],"id":42}}"#)]
+    #[case::scalar(json!(null), " null ")]
+    fn json_eq_matches(#[case] expected: Value, #[case] body: &str) {
+        let request = with_body(body);
+        let matcher = Matcher::BodyJsonEq(expected);
+
+        assert!(
+            matcher.observed(&request).is_none(),
+            "Matcher {matcher:?} should match request: {request}"
+        );
+    }
+
+    #[rstest]
+    #[case::wrong_value(json!({"user": {"id": 42}}), r#"{"user": {"id": 7}}"#, pointer("/user/id", Some(json!(7))))]
+    #[case::missing_key(json!({"a": 1, "b": 2}), r#"{"a": 1}"#, pointer("/b", None))]
+    #[case::extra_key(json!({"a": 1}), r#"{"a": 1, "b": 2}"#, pointer("/b", Some(json!(2))))]
+    #[case::array_item(json!([1, 2, 3]), "[1, 5, 3]", pointer("/1", Some(json!(5))))]
+    #[case::array_shorter(json!([1, 2, 3]), "[1, 2]", pointer("/2", None))]
+    #[case::array_longer(json!([1]), "[1, 2]", pointer("/1", Some(json!(2))))]
+    #[case::escaped_key(json!({"a/b": 1}), r#"{"a/b": 2}"#, pointer("/a~1b", Some(json!(2))))]
+    #[case::root_type(json!({"a": 1}), "[]", pointer("", Some(json!([]))))]
+    #[case::invalid_json(json!({}), "not json", Matcher::BodyContains("not json".into()))]
+    fn json_eq_reports_difference(
+        #[case] expected: Value,
+        #[case] body: &str,
+        #[case] report: Matcher,
+    ) {
+        let request = with_body(body);
+        let matcher = Matcher::BodyJsonEq(expected);
+
+        assert_eq!(
+            matcher.observed(&request),
+            Some(report.clone()),
+            "Matcher {matcher:?} should report {report:?} for request: {request}"
+        );
+        assert!(
+            report.observed(&request).is_none(),
+            "Report {report:?} should itself match request: {request}"
+        );
+    }
+
+    #[rstest]
+    #[case::extra_keys(json!({"a": 1}), r#"{"a": 1, "b": 2}"#)]
+    #[case::nested(json!({"user": {"id": 42}}), r#"{"user": {"id": 42, "name": "Bob"}, "ok": true}"#)]
+    #[case::array_subset(json!({"tags": ["b"]}), r#"{"tags": ["a", "b", "c"]}"#)]
+    #[case::array_of_objects(json!([{"id": 2}]), r#"[{"id": 1, "x": 0}, {"id": 2, "x": 1}]"#)]
+    #[case::equal(json!({"a": [1, 2]}), r#"{"a": [2, 1]}"#)]
+    fn json_includes_matches(#[case] expected: Value, #[case] body: &str) {
+        let request = with_body(body);
+        let matcher = Matcher::BodyJsonIncludes(expected);
+
+        assert!(
+            matcher.observed(&request).is_none(),
+            "Matcher {matcher:?} should match request: {request}"
+        );
+    }
+
+    #[rstest]
+    #[case::missing_key(json!({"a": 1, "b": 2}), r#"{"a": 1}"#, pointer("/b", None))]
+    #[case::wrong_value(json!({"user": {"id": 42}}), r#"{"user": {"id": 7, "name": "Bob"}}"#, pointer("/user/id", Some(json!(7))))]
+    #[case::array_item(json!({"tags": ["d"]}), r#"{"tags": ["a", "b"]}"#, pointer("/tags", Some(json!(["a", "b"]))))]
+    #[case::root_type(json!({"a": 1}), "[1]", pointer("", Some(json!([1]))))]
+    fn json_includes_reports_difference(
+        #[case] expected: Value,
+        #[case] body: &str,
+        #[case] report: Matcher,
+    ) {
+        let request = with_body(body);
+        let matcher = Matcher::BodyJsonIncludes(expected);
+
+        assert_eq!(
+            matcher.observed(&request),
+            Some(report.clone()),
+            "Matcher {matcher:?} should report {report:?} for request: {request}"
+        );
+        assert!(
+            report.observed(&request).is_none(),
+            "Report {report:?} should itself match request: {request}"
+        );
+    }
+
+    fn json_path(path: &str, value: Option<Value>) -> Matcher {
+        Matcher::JsonPath(path.into(), value)
+    }
+
+    fn json_path_miss(path: &str) -> Matcher {
+        Matcher::JsonPathMiss(path.into())
+    }
+
+    #[rstest]
+    #[case::wrong_value(json_path("$.user.id", Some(json!(42))), json_path("$.user.id", Some(json!(7))), r#"{"user": {"id": 7}}"#)]
+    #[case::missing(json_path("$.user.id", Some(json!(42))), json_path_miss("$.user.id"), r#"{"user": {}}"#)]
+    #[case::exists(
+        json_path("$.user.email", None),
+        json_path_miss("$.user.email"),
+        r#"{"user": {"id": 7}}"#
+    )]
+    #[case::miss(json_path_miss("$.debug"), json_path("$.debug", Some(json!(true))), r#"{"debug": true}"#)]
+    #[case::wildcard(json_path("$.items[*].id", Some(json!(3))), json_path("$.items[*].id", Some(json!(1))), r#"{"items": [{"id": 1}, {"id": 2}]}"#)]
+    #[case::invalid_json(json_path("$.id", None), Matcher::BodyContains("<xml/>".into()), "<xml/>")]
+    fn json_path_validation(
+        #[case] invalid_matcher: Matcher,
+        #[case] valid_matcher: Matcher,
+        #[case] body: &str,
+    ) {
+        let request = with_body(body);
+
+        assert_eq!(
+            invalid_matcher.observed(&request),
+            Some(valid_matcher.clone()),
+            "Invalid matcher {invalid_matcher:?} should report {valid_matcher:?} for request: {request}"
+        );
+        assert!(
+            valid_matcher.observed(&request).is_none(),
+            "Valid matcher {valid_matcher:?} should pass validation for request: {request}"
+        );
+    }
+
+    #[rstest]
+    #[case::value(json_path("$.user.id", Some(json!(42))))]
+    #[case::exists(json_path("$.user.name", None))]
+    #[case::filter(json_path("$.items[?@.id == 2].name", Some(json!("two"))))]
+    #[case::any_node(json_path("$.items[*].id", Some(json!(2))))]
+    #[case::miss(json_path_miss("$.user.password"))]
+    fn json_path_matches(#[case] matcher: Matcher) {
+        let request = with_body(
+            r#"{"user": {"id": 42, "name": "Bob"}, "items": [{"id": 1, "name": "one"}, {"id": 2, "name": "two"}]}"#,
+        );
+
+        assert!(
+            matcher.observed(&request).is_none(),
+            "Matcher {matcher:?} should match request: {request}"
+        );
+    }
+
+    #[cfg(feature = "json-schema")]
+    fn user_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["id", "name"],
+            "properties": {
+                "id": {"type": "integer"},
+                "name": {"type": "string", "minLength": 1}
+            }
+        })
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[rstest]
+    #[case::valid(r#"{"id": 1, "name": "Bob"}"#)]
+    #[case::extra_fields(r#"{"id": 1, "name": "Bob", "admin": false}"#)]
+    fn json_schema_matches(#[case] body: &str) {
+        let request = with_body(body);
+        let matcher = Matcher::BodyJsonSchema(user_schema());
+
+        assert!(
+            matcher.observed(&request).is_none(),
+            "Matcher {matcher:?} should match request: {request}"
+        );
+        assert!(matcher.schema_errors(&request).is_empty());
+    }
+
+    #[cfg(feature = "json-schema")]
+    #[rstest]
+    #[case::wrong_type(r#"{"id": "1", "name": "Bob"}"#, pointer("/id", Some(json!("1"))), "/id: ")]
+    #[case::constraint(r#"{"id": 1, "name": ""}"#, pointer("/name", Some(json!(""))), "(schema /properties/name/minLength)")]
+    #[case::required(r#"{"id": 1}"#, pointer("", Some(json!({"id": 1}))), "(schema /required)")]
+    #[case::not_json("id=1", Matcher::BodyContains("id=1".into()), "not a JSON document")]
+    fn json_schema_reports_violation(
+        #[case] body: &str,
+        #[case] report: Matcher,
+        #[case] error_fragment: &str,
+    ) {
+        let request = with_body(body);
+        let matcher = Matcher::BodyJsonSchema(user_schema());
+
+        assert_eq!(
+            matcher.observed(&request),
+            Some(report.clone()),
+            "Matcher {matcher:?} should report {report:?} for request: {request}"
+        );
+
+        let errors = matcher.schema_errors(&request);
+        assert!(
+            errors.iter().any(|e| e.contains(error_fragment)),
+            "Schema errors {errors:?} should mention {error_fragment:?}"
+        );
+    }
+
+    #[test]
+    fn json_eq_without_body() {
+        assert_eq!(
+            Matcher::BodyJsonEq(json!({})).observed(&Request::default()),
+            Some(Matcher::BodyMiss)
+        );
+    }
+}
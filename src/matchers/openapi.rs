@@ -0,0 +1,324 @@
+use super::{Matcher, Matchers, ValueFormat};
+use serde_json::Value;
+
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Why an OpenAPI document couldn't be converted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpenApiError {
+    /// The `openapi` field is missing or not a 3.x version.
+    UnsupportedVersion(Option<String>),
+    MissingPaths,
+    /// A `$ref` that isn't a local `#/...` pointer into the document, or points nowhere.
+    UnresolvedRef(String),
+}
+
+impl std::fmt::Display for OpenApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OpenApiError::UnsupportedVersion(Some(version)) => {
+                write!(f, "unsupported OpenAPI version {version:?}, expected 3.x")
+            }
+            OpenApiError::UnsupportedVersion(None) => f.write_str("missing `openapi` version"),
+            OpenApiError::MissingPaths => f.write_str("document has no `paths` object"),
+            OpenApiError::UnresolvedRef(reference) => write!(f, "unresolved $ref {reference:?}"),
+        }
+    }
+}
+
+impl std::error::Error for OpenApiError {}
+
+impl Matchers {
+    /// Converts every operation of an OpenAPI 3.x document into a matcher set, keyed by its
+    /// `operationId` or, lacking one, by `"METHOD /path"`.
+    ///
+    /// Each set requires the method, the path as a template (typed by the path parameters'
+    /// schemas and prefixed with the first server's base path), every required query, header
+    /// and cookie parameter, and the content type of a required request body.
+    pub fn from_openapi(spec: &Value) -> Result<Vec<(String, Matchers)>, OpenApiError> {
+        let version = spec.get("openapi").and_then(Value::as_str);
+        if !version.is_some_and(|v| v.starts_with("3.")) {
+            return Err(OpenApiError::UnsupportedVersion(version.map(String::from)));
+        }
+        let paths = spec
+            .get("paths")
+            .and_then(Value::as_object)
+            .ok_or(OpenApiError::MissingPaths)?;
+        let base_path = spec
+            .pointer("/servers/0/url")
+            .and_then(Value::as_str)
+            .map(server_base_path)
+            .unwrap_or_default();
+
+        let mut operations = Vec::new();
+        for (path, item) in paths {
+            let item = resolve(spec, item)?;
+            let shared = item.get("parameters").and_then(Value::as_array);
+
+            for method in METHODS {
+                let Some(operation) = item.get(method) else {
+                    continue;
+                };
+                let mut parameters = Vec::new();
+                for parameter in shared
+                    .into_iter()
+                    .chain(operation.get("parameters").and_then(Value::as_array))
+                    .flatten()
+                {
+                    let parameter = resolve(spec, parameter)?;
+                    // Operation parameters override path-level ones with the same name and location.
+                    parameters.retain(|p: &&Value| {
+                        p.get("name") != parameter.get("name") || p.get("in") != parameter.get("in")
+                    });
+                    parameters.push(parameter);
+                }
+
+                let method = method.to_uppercase();
+                let id = operation
+                    .get("operationId")
+                    .and_then(Value::as_str)
+                    .map_or_else(|| format!("{method} {path}"), String::from);
+                let matchers =
+                    operation_matchers(spec, &method, &base_path, path, operation, &parameters)?;
+                operations.push((id, matchers));
+            }
+        }
+
+        Ok(operations)
+    }
+}
+
+fn operation_matchers(
+    spec: &Value,
+    method: &str,
+    base_path: &str,
+    path: &str,
+    operation: &Value,
+    parameters: &[&Value],
+) -> Result<Matchers, OpenApiError> {
+    let mut matchers = Matchers::new().method(method);
+
+    let mut template = format!("{base_path}{path}");
+    for parameter in parameters.iter().filter(|p| location(p) == Some("path")) {
+        if let (Some(name), Some(format)) = (name(parameter), parameter_format(spec, parameter)?) {
+            let typed = format!("{{{name}:{}}}", format_label(format));
+            template = template.replace(&format!("{{{name}}}"), &typed);
+        }
+    }
+    matchers.add(if template.contains('{') {
+        Matcher::PathTemplate(template)
+    } else {
+        Matcher::Path(template)
+    });
+
+    for parameter in parameters
+        .iter()
+        .filter(|p| p.get("required") == Some(&Value::Bool(true)))
+    {
+        let Some(name) = name(parameter).map(String::from) else {
+            continue;
+        };
+        match location(parameter) {
+            Some("query") => matchers.add(match parameter_format(spec, parameter)? {
+                Some(format) => Matcher::QueryFormat(name, format),
+                None => Matcher::QueryExists(name),
+            }),
+            Some("header") => matchers.add(Matcher::HeaderExists(name)),
+            Some("cookie") => matchers.add(Matcher::CookieExists(name)),
+            _ => {}
+        }
+    }
+
+    if let Some(body) = operation.get("requestBody") {
+        let body = resolve(spec, body)?;
+        let content_types: Vec<Matcher> = body
+            .get("content")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|content| content.keys())
+            .map(|media_type| Matcher::ContentType(media_type.clone()))
+            .collect();
+        if body.get("required") == Some(&Value::Bool(true)) {
+            match content_types.len() {
+                0 => {}
+                1 => matchers.extend(content_types),
+                _ => matchers.add(Matcher::AnyOf(content_types)),
+            }
+        }
+    }
+
+    Ok(matchers)
+}
+
+fn name(parameter: &Value) -> Option<&str> {
+    parameter.get("name").and_then(Value::as_str)
+}
+
+fn location(parameter: &Value) -> Option<&str> {
+    parameter.get("in").and_then(Value::as_str)
+}
+
+/// Maps a parameter schema onto the value formats matchers can check.
+fn parameter_format(spec: &Value, parameter: &Value) -> Result<Option<ValueFormat>, OpenApiError> {
+    let Some(schema) = parameter.get("schema") else {
+        return Ok(None);
+    };
+    let schema = resolve(spec, schema)?;
+    let kind = schema.get("type").and_then(Value::as_str);
+    let format = schema.get("format").and_then(Value::as_str);
+
+    Ok(match (kind, format) {
+        (Some("integer"), _) => Some(ValueFormat::Integer),
+        (Some("string"), Some("uuid")) => Some(ValueFormat::Uuid),
+        (Some("string"), Some("date")) => Some(ValueFormat::Date),
+        _ => None,
+    })
+}
+
+fn format_label(format: ValueFormat) -> &'static str {
+    match format {
+        ValueFormat::Uuid => "uuid",
+        ValueFormat::Integer => "int",
+        ValueFormat::Date => "date",
+    }
+}
+
+/// Follows local `$ref` pointers such as `#/components/parameters/Page`.
+fn resolve<'a>(spec: &'a Value, value: &'a Value) -> Result<&'a Value, OpenApiError> {
+    let mut value = value;
+    // Bounded so cyclic references can't loop forever.
+    for _ in 0..32 {
+        let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+            return Ok(value);
+        };
+        value = reference
+            .strip_prefix('#')
+            .and_then(|pointer| spec.pointer(pointer))
+            .ok_or_else(|| OpenApiError::UnresolvedRef(reference.into()))?;
+    }
+    Err(OpenApiError::UnresolvedRef(
+        value
+            .get("$ref")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .into(),
+    ))
+}
+
+/// Extracts the path of a server URL, e.g. `/v1` from `https://api.example.com/v1/`.
+fn server_base_path(url: &str) -> String {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("", |idx| &rest[idx..]),
+        None => url,
+    };
+    path.trim_end_matches('/').into()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::request::Request;
+    use serde_json::json;
+
+    fn spec() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "servers": [{"url": "https://api.example.com/v1/"}],
+            "paths": {
+                "/users/{id}": {
+                    "parameters": [
+                        {"name": "id", "in": "path", "required": true, "schema": {"type": "integer"}}
+                    ],
+                    "get": {
+                        "operationId": "getUser",
+                        "parameters": [
+                            {"$ref": "#/components/parameters/ApiKey"},
+                            {"name": "fields", "in": "query", "schema": {"type": "string"}}
+                        ]
+                    },
+                    "put": {
+                        "requestBody": {
+                            "required": true,
+                            "content": {"application/json": {}}
+                        }
+                    }
+                },
+                "/search": {
+                    "get": {
+                        "operationId": "search",
+                        "parameters": [
+                            {"name": "q", "in": "query", "required": true, "schema": {"type": "string"}},
+                            {"name": "since", "in": "query", "required": true, "schema": {"type": "string", "format": "date"}}
+                        ]
+                    }
+                }
+            },
+            "components": {
+                "parameters": {
+                    "ApiKey": {"name": "X-Api-Key", "in": "header", "required": true}
+                }
+            }
+        })
+    }
+
+    fn matchers(id: &str) -> Vec<Matcher> {
+        Matchers::from_openapi(&spec())
+            .unwrap()
+            .into_iter()
+            .find(|(operation, _)| operation == id)
+            .unwrap_or_else(|| panic!("operation {id:?} should be imported"))
+            .1
+            .iter()
+            .filter_map(|m| m.as_matcher().cloned())
+            .collect()
+    }
+
+    #[rstest::rstest]
+    #[case::operation_id("getUser", vec![
+        Matcher::Method("GET".into()),
+        Matcher::PathTemplate("/v1/users/{id:int}".into()),
+        Matcher::HeaderExists("X-Api-Key".into()),
+    ])]
+    #[case::generated_id("PUT /users/{id}", vec![
+        Matcher::Method("PUT".into()),
+        Matcher::PathTemplate("/v1/users/{id:int}".into()),
+        Matcher::ContentType("application/json".into()),
+    ])]
+    #[case::query("search", vec![
+        Matcher::Method("GET".into()),
+        Matcher::Path("/v1/search".into()),
+        Matcher::QueryExists("q".into()),
+        Matcher::QueryFormat("since".into(), ValueFormat::Date),
+    ])]
+    fn imports_operations(#[case] id: &str, #[case] expected: Vec<Matcher>) {
+        assert_eq!(matchers(id), expected);
+    }
+
+    #[test]
+    fn imported_matchers_match_requests() {
+        let operations = Matchers::from_openapi(&spec()).unwrap();
+        let (_, get_user) = operations.iter().find(|(id, _)| id == "getUser").unwrap();
+
+        assert!(get_user.is_matched(&Request::from("/v1/users/42").with_header("X-Api-Key", "k")));
+        assert!(
+            !get_user.is_matched(&Request::from("/v1/users/abc").with_header("X-Api-Key", "k"))
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::swagger(json!({"swagger": "2.0", "paths": {}}), OpenApiError::UnsupportedVersion(None))]
+    #[case::version(json!({"openapi": "2.0", "paths": {}}), OpenApiError::UnsupportedVersion(Some("2.0".into())))]
+    #[case::paths(json!({"openapi": "3.1.0"}), OpenApiError::MissingPaths)]
+    #[case::reference(
+        json!({"openapi": "3.1.0", "paths": {"/a": {"get": {"parameters": [{"$ref": "#/nowhere"}]}}}}),
+        OpenApiError::UnresolvedRef("#/nowhere".into())
+    )]
+    fn rejects_documents(#[case] spec: Value, #[case] error: OpenApiError) {
+        assert_eq!(
+            Matchers::from_openapi(&spec).map(|ops| ops.len()),
+            Err(error)
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use super::{Matcher, Matchers};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::Value;
+
+/// Why a Pact file couldn't be converted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PactError {
+    MissingInteractions,
+    /// The interaction at this index lacks a `request` with a `method` and `path`.
+    InvalidInteraction(usize),
+    /// A body marked as base64 encoded doesn't decode.
+    InvalidBody(usize),
+}
+
+impl std::fmt::Display for PactError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PactError::MissingInteractions => f.write_str("pact has no `interactions` array"),
+            PactError::InvalidInteraction(idx) => {
+                write!(f, "interaction {idx} has no request method and path")
+            }
+            PactError::InvalidBody(idx) => write!(f, "interaction {idx} has an undecodable body"),
+        }
+    }
+}
+
+impl std::error::Error for PactError {}
+
+/// One HTTP interaction of a Pact file: the request expectations and the agreed response.
+#[derive(Debug, Clone)]
+pub struct PactInteraction {
+    pub description: String,
+    pub provider_states: Vec<String>,
+    pub matchers: Matchers,
+    pub response: PactResponse,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PactResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+}
+
+impl Matchers {
+    /// Reads the HTTP interactions of a Pact V2, V3 or V4 file.
+    ///
+    /// Requests are matched exactly: Pact matching rules are not applied. Non-HTTP V4
+    /// interactions (messages) are skipped.
+    pub fn from_pact(pact: &Value) -> Result<Vec<PactInteraction>, PactError> {
+        let interactions = pact
+            .get("interactions")
+            .and_then(Value::as_array)
+            .ok_or(PactError::MissingInteractions)?;
+
+        let mut imported = Vec::new();
+        for (idx, interaction) in interactions.iter().enumerate() {
+            if interaction
+                .get("type")
+                .and_then(Value::as_str)
+                .is_some_and(|kind| kind != "Synchronous/HTTP")
+            {
+                continue;
+            }
+            let request = interaction
+                .get("request")
+                .ok_or(PactError::InvalidInteraction(idx))?;
+            let response = interaction.get("response").unwrap_or(&Value::Null);
+
+            imported.push(PactInteraction {
+                description: str_field(interaction, "description")
+                    .unwrap_or_default()
+                    .into(),
+                provider_states: provider_states(interaction),
+                matchers: request_matchers(idx, request)?,
+                response: PactResponse {
+                    status: response
+                        .get("status")
+                        .and_then(Value::as_u64)
+                        .and_then(|status| u16::try_from(status).ok())
+                        .unwrap_or(200),
+                    headers: headers(response),
+                    body: body(idx, response)?.map(PactBody::into_bytes),
+                },
+            });
+        }
+
+        Ok(imported)
+    }
+}
+
+fn request_matchers(idx: usize, request: &Value) -> Result<Matchers, PactError> {
+    let (Some(method), Some(path)) = (str_field(request, "method"), str_field(request, "path"))
+    else {
+        return Err(PactError::InvalidInteraction(idx));
+    };
+    let mut matchers = Matchers::new().method(method.to_uppercase()).path(path);
+
+    match request.get("query") {
+        // V2 serializes the query as a raw string.
+        Some(Value::String(query)) => {
+            let mut params: Vec<(String, Vec<String>)> = Vec::new();
+            for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+                match params.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, values)) => values.push(value.into_owned()),
+                    None => params.push((key.into_owned(), vec![value.into_owned()])),
+                }
+            }
+            matchers.extend(
+                params
+                    .into_iter()
+                    .map(|(key, values)| query_matcher(key, values)),
+            );
+        }
+        Some(Value::Object(query)) => {
+            matchers.extend(
+                query
+                    .iter()
+                    .map(|(key, values)| query_matcher(key.clone(), strings(values))),
+            );
+        }
+        _ => {}
+    }
+
+    for (name, value) in headers(request) {
+        matchers.add(Matcher::HeaderEq(name, value));
+    }
+
+    match body(idx, request)? {
+        Some(PactBody::Json(json)) => matchers.add(Matcher::BodyJsonEq(json)),
+        Some(PactBody::Text(text)) => matchers.add(Matcher::BodyEq(text)),
+        Some(PactBody::Bytes(bytes)) => matchers.add(Matcher::BodyBytesEq(bytes)),
+        None => {}
+    }
+
+    Ok(matchers)
+}
+
+fn query_matcher(key: String, mut values: Vec<String>) -> Matcher {
+    match values.len() {
+        1 => Matcher::QueryEq(key, values.remove(0)),
+        _ => Matcher::QueryEqAll(key, values),
+    }
+}
+
+enum PactBody {
+    Json(Value),
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+impl PactBody {
+    fn into_bytes(self) -> Vec<u8> {
+        match self {
+            PactBody::Json(json) => json.to_string().into_bytes(),
+            PactBody::Text(text) => text.into_bytes(),
+            PactBody::Bytes(bytes) => bytes,
+        }
+    }
+}
+
+/// Reads a V2/V3 body (any JSON value) or a V4 `{content, contentType, encoded}` body.
+fn body(idx: usize, part: &Value) -> Result<Option<PactBody>, PactError> {
+    let body = match part.get("body") {
+        None | Some(Value::Null) => return Ok(None),
+        Some(body) => body,
+    };
+
+    let (content, encoded) = match body.get("content") {
+        Some(content) if body.is_object() => (content, body.get("encoded")),
+        _ => (body, None),
+    };
+
+    Ok(Some(match (content, encoded) {
+        (Value::String(content), Some(Value::String(encoding)))
+            if encoding.eq_ignore_ascii_case("base64") =>
+        {
+            PactBody::Bytes(
+                BASE64
+                    .decode(content)
+                    .map_err(|_| PactError::InvalidBody(idx))?,
+            )
+        }
+        (Value::String(text), _) => PactBody::Text(text.clone()),
+        (json, _) => PactBody::Json(json.clone()),
+    }))
+}
+
+/// Reads headers whose values are strings (V2/V3) or string arrays (V4).
+fn headers(part: &Value) -> Vec<(String, String)> {
+    part.get("headers")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, value)| (name.clone(), strings(value).join(", ")))
+        .collect()
+}
+
+fn provider_states(interaction: &Value) -> Vec<String> {
+    match (
+        interaction.get("providerState"),
+        interaction.get("providerStates"),
+    ) {
+        (Some(Value::String(state)), _) => vec![state.clone()],
+        (_, Some(Value::Array(states))) => states
+            .iter()
+            .filter_map(|state| str_field(state, "name").map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    match value {
+        Value::Array(values) => values.iter().flat_map(strings).collect(),
+        Value::String(value) => vec![value.clone()],
+        Value::Null => Vec::new(),
+        value => vec![value.to_string()],
+    }
+}
+
+fn str_field<'a>(value: &'a Value, field: &str) -> Option<&'a str> {
+    value.get(field).and_then(Value::as_str)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::request::Request;
+    use serde_json::json;
+
+    fn matchers(interaction: &PactInteraction) -> Vec<Matcher> {
+        interaction
+            .matchers
+            .iter()
+            .filter_map(|m| m.as_matcher().cloned())
+            .collect()
+    }
+
+    #[test]
+    fn imports_v3_interaction() {
+        let pact = json!({
+            "metadata": {"pactSpecification": {"version": "3.0.0"}},
+            "interactions": [{
+                "description": "create a user",
+                "providerStates": [{"name": "no users exist"}],
+                "request": {
+                    "method": "post",
+                    "path": "/users",
+                    "query": {"notify": ["true"]},
+                    "headers": {"Content-Type": "application/json"},
+                    "body": {"name": "Bob"}
+                },
+                "response": {
+                    "status": 201,
+                    "headers": {"Location": "/users/1"},
+                    "body": {"id": 1}
+                }
+            }]
+        });
+        let interactions = Matchers::from_pact(&pact).unwrap();
+        let interaction = &interactions[0];
+
+        assert_eq!(interaction.description, "create a user");
+        assert_eq!(
+            interaction.provider_states,
+            vec!["no users exist".to_string()]
+        );
+        assert_eq!(
+            matchers(interaction),
+            vec![
+                Matcher::Method("POST".into()),
+                Matcher::Path("/users".into()),
+                Matcher::QueryEq("notify".into(), "true".into()),
+                Matcher::HeaderEq("Content-Type".into(), "application/json".into()),
+                Matcher::BodyJsonEq(json!({"name": "Bob"})),
+            ]
+        );
+        assert_eq!(
+            interaction.response,
+            PactResponse {
+                status: 201,
+                headers: vec![("Location".into(), "/users/1".into())],
+                body: Some(br#"{"id":1}"#.to_vec()),
+            }
+        );
+
+        let request = Request::from("/users?notify=true")
+            .with_method("POST")
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "name": "Bob" }"#);
+        assert!(interaction.matchers.is_matched(&request));
+    }
+
+    #[test]
+    fn imports_v4_interactions() {
+        let pact = json!({
+            "metadata": {"pactSpecification": {"version": "4.0"}},
+            "interactions": [
+                {"type": "Asynchronous/Messages", "description": "event"},
+                {
+                    "type": "Synchronous/HTTP",
+                    "description": "upload",
+                    "request": {
+                        "method": "PUT",
+                        "path": "/blob",
+                        "query": {"tag": ["a", "b"]},
+                        "headers": {"Accept": ["text/plain", "*/*"]},
+                        "body": {"content": "AQID", "contentType": "application/octet-stream", "encoded": "base64"}
+                    },
+                    "response": {"status": 204}
+                }
+            ]
+        });
+        let interactions = Matchers::from_pact(&pact).unwrap();
+
+        assert_eq!(interactions.len(), 1);
+        assert_eq!(
+            matchers(&interactions[0]),
+            vec![
+                Matcher::Method("PUT".into()),
+                Matcher::Path("/blob".into()),
+                Matcher::QueryEqAll("tag".into(), vec!["a".into(), "b".into()]),
+                Matcher::HeaderEq("Accept".into(), "text/plain, */*".into()),
+                Matcher::BodyBytesEq(vec![1, 2, 3]),
+            ]
+        );
+        assert_eq!(interactions[0].response.status, 204);
+        assert_eq!(interactions[0].response.body, None);
+    }
+
+    #[test]
+    fn imports_v2_query_string() {
+        let pact = json!({"interactions": [{
+            "description": "search",
+            "providerState": "indexed",
+            "request": {"method": "GET", "path": "/search", "query": "q=rust&tag=a&tag=b"},
+            "response": {"status": 200, "body": "ok"}
+        }]});
+        let interaction = &Matchers::from_pact(&pact).unwrap()[0];
+
+        assert_eq!(interaction.provider_states, vec!["indexed".to_string()]);
+        assert!(
+            interaction
+                .matchers
+                .is_matched(&Request::from("/search?q=rust&tag=a&tag=b"))
+        );
+        assert_eq!(interaction.response.body, Some(b"ok".to_vec()));
+    }
+
+    #[rstest::rstest]
+    #[case::interactions(json!({}), PactError::MissingInteractions)]
+    #[case::request(json!({"interactions": [{"description": "x"}]}), PactError::InvalidInteraction(0))]
+    #[case::body(
+        json!({"interactions": [{"request": {"method": "GET", "path": "/", "body": {"content": "!!", "encoded": "base64"}}}]}),
+        PactError::InvalidBody(0)
+    )]
+    fn rejects_pacts(#[case] pact: Value, #[case] error: PactError) {
+        assert_eq!(Matchers::from_pact(&pact).map(|i| i.len()), Err(error));
+    }
+}
@@ -0,0 +1,95 @@
+use super::{Matcher, body_preview};
+use crate::request::Request;
+use prost_reflect::{DynamicMessage, ReflectMessage};
+
+pub(super) fn validate(expected: &DynamicMessage, request: &Request) -> Option<Matcher> {
+    let Some(body) = request.body.bytes() else {
+        return Some(Matcher::BodyMiss);
+    };
+
+    match DynamicMessage::decode(expected.descriptor(), body.as_ref()) {
+        Ok(actual) if &actual == expected => None,
+        Ok(actual) => Some(Matcher::BodyProtobuf(actual)),
+        Err(_) => Some(body_preview(&body)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use prost_reflect::prost_types::field_descriptor_proto::{Label, Type};
+    use prost_reflect::prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    };
+    use prost_reflect::{DescriptorPool, MessageDescriptor, Value};
+    use rstest::rstest;
+    use std::sync::LazyLock;
+
+    fn field(name: &str, number: i32, kind: Type) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.into()),
+            number: Some(number),
+            label: Some(Label::Optional as i32),
+            r#type: Some(kind as i32),
+            ..Default::default()
+        }
+    }
+
+    /// `message User { int64 id = 1; string name = 2; }`
+    static USER: LazyLock<MessageDescriptor> = LazyLock::new(|| {
+        let file = FileDescriptorProto {
+            name: Some("user.proto".into()),
+            package: Some("test".into()),
+            syntax: Some("proto3".into()),
+            message_type: vec![DescriptorProto {
+                name: Some("User".into()),
+                field: vec![field("id", 1, Type::Int64), field("name", 2, Type::String)],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let pool = DescriptorPool::from_file_descriptor_set(FileDescriptorSet { file: vec![file] })
+            .unwrap();
+        pool.get_message_by_name("test.User").unwrap()
+    });
+
+    fn user(id: i64, name: &str) -> DynamicMessage {
+        let mut message = DynamicMessage::new(USER.clone());
+        message.set_field_by_name("id", Value::I64(id));
+        message.set_field_by_name("name", Value::String(name.into()));
+        message
+    }
+
+    /// `name = "Bob"` followed by `id = 42`, the reverse of declaration order.
+    const BOB_REORDERED: &[u8] = &[0x12, 0x03, b'B', b'o', b'b', 0x08, 0x2a];
+
+    #[test]
+    fn protobuf_ignores_field_order() {
+        let request = Request::default().with_body(BOB_REORDERED);
+        let matcher = Matcher::BodyProtobuf(user(42, "Bob"));
+
+        assert!(
+            matcher.observed(&request).is_none(),
+            "Matcher {matcher:?} should match request: {request}"
+        );
+    }
+
+    #[rstest]
+    #[case::wrong_field(user(42, "Alice"), Request::default().with_body(BOB_REORDERED), Matcher::BodyProtobuf(user(42, "Bob")))]
+    #[case::undecodable(user(42, "Bob"), Request::default().with_body([0xff, 0xff]), Matcher::BodyBytesEq(vec![0xff, 0xff]))]
+    #[case::missing(user(42, "Bob"), Request::default(), Matcher::BodyMiss)]
+    fn protobuf_reports(
+        #[case] expected: DynamicMessage,
+        #[case] request: Request,
+        #[case] report: Matcher,
+    ) {
+        let matcher = Matcher::BodyProtobuf(expected);
+
+        assert_eq!(
+            matcher.observed(&request),
+            Some(report.clone()),
+            "Matcher {matcher:?} should report {report:?}"
+        );
+        assert!(report.observed(&request).is_none());
+    }
+}
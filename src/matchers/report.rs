@@ -0,0 +1,505 @@
+use super::Matcher;
+use crate::redaction::{REDACTED, Redaction};
+
+/// Why an expectation failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The request lacks the part the expectation needs, e.g. a header.
+    Missing,
+    /// The part is present but holds a different value.
+    Wrong,
+    /// The part is present although the expectation forbids it.
+    Unexpected,
+}
+
+/// A single failed expectation: what was expected, why it failed and what the request had.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    expected: String,
+    reason: Reason,
+    actual: Option<String>,
+}
+
+impl Mismatch {
+    pub fn new<S: Into<String>>(expected: S, reason: Reason, actual: Option<String>) -> Self {
+        Self {
+            expected: expected.into(),
+            reason,
+            actual,
+        }
+    }
+
+    pub fn missing<S: Into<String>>(expected: S) -> Self {
+        Self::new(expected, Reason::Missing, None)
+    }
+
+    pub fn wrong<S: Into<String>, A: Into<String>>(expected: S, actual: A) -> Self {
+        Self::new(expected, Reason::Wrong, Some(actual.into()))
+    }
+
+    pub fn unexpected<S: Into<String>, A: Into<String>>(expected: S, actual: A) -> Self {
+        Self::new(expected, Reason::Unexpected, Some(actual.into()))
+    }
+
+    /// Describes a failed built-in `expected` matcher from the matcher `observed` in its place.
+    pub(super) fn between(expected: &Matcher, observed: &Matcher) -> Self {
+        if let Matcher::Named(label, inner) = expected {
+            let inner_observed = match observed {
+                Matcher::Named(_, observed) => observed,
+                observed => observed,
+            };
+            return Self {
+                expected: label.clone(),
+                ..Self::between(inner, inner_observed)
+            };
+        }
+
+        let reason = if forbids(expected) {
+            Reason::Unexpected
+        } else if is_absence(observed) {
+            Reason::Missing
+        } else {
+            Reason::Wrong
+        };
+
+        let expected = Redaction::current().mask(expected);
+        Self::new(expected.explain(), reason, value(observed))
+    }
+
+    pub fn expected(&self) -> &str {
+        &self.expected
+    }
+
+    pub fn reason(&self) -> Reason {
+        self.reason
+    }
+
+    pub fn actual(&self) -> Option<&str> {
+        self.actual.as_deref()
+    }
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, but ", self.expected)?;
+        match (self.reason, &self.actual) {
+            (Reason::Missing, _) => f.write_str("it is missing"),
+            (Reason::Wrong, Some(actual)) => write!(f, "got {actual:?}"),
+            (Reason::Wrong, None) => f.write_str("got a different value"),
+            (Reason::Unexpected, Some(actual)) => write!(f, "found {actual:?}"),
+            (Reason::Unexpected, None) => f.write_str("it is present"),
+        }
+    }
+}
+
+impl std::error::Error for Mismatch {}
+
+/// Every expectation of a [`Matchers`](super::Matchers) set that a request failed, in order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MismatchReport {
+    mismatches: Vec<Mismatch>,
+}
+
+impl MismatchReport {
+    pub(super) fn new(mismatches: Vec<Mismatch>) -> Self {
+        Self { mismatches }
+    }
+
+    pub fn mismatches(&self) -> &[Mismatch] {
+        &self.mismatches
+    }
+
+    pub fn len(&self) -> usize {
+        self.mismatches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Mismatch> {
+        self.mismatches.iter()
+    }
+}
+
+impl IntoIterator for MismatchReport {
+    type Item = Mismatch;
+    type IntoIter = std::vec::IntoIter<Mismatch>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.mismatches.into_iter()
+    }
+}
+
+impl std::fmt::Display for MismatchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "request did not match {} expectation(s)", self.len())?;
+        for mismatch in &self.mismatches {
+            write!(f, "\n  - {mismatch}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MismatchReport {}
+
+/// How close a request came to satisfying a [`Matchers`](super::Matchers) set.
+///
+/// Scores order by the fraction of satisfied matchers, then by how many were satisfied, so a
+/// request meeting 3 of 4 expectations ranks above one meeting 1 of 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchScore {
+    satisfied: usize,
+    total: usize,
+}
+
+impl MatchScore {
+    pub(super) fn new(satisfied: usize, total: usize) -> Self {
+        Self { satisfied, total }
+    }
+
+    pub fn satisfied(&self) -> usize {
+        self.satisfied
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn failed(&self) -> usize {
+        self.total - self.satisfied
+    }
+
+    /// The satisfied share in `0.0..=1.0`; an empty set counts as fully satisfied.
+    pub fn fraction(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.satisfied as f64 / self.total as f64
+        }
+    }
+
+    pub fn is_match(&self) -> bool {
+        self.satisfied == self.total
+    }
+}
+
+impl Ord for MatchScore {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // Compare the fractions exactly by cross-multiplying, treating empty sets as 1/1.
+        let ratio = |score: &Self| match score.total {
+            0 => (1, 1),
+            total => (score.satisfied, total),
+        };
+        let ((a, b), (c, d)) = (ratio(self), ratio(other));
+
+        (a * d)
+            .cmp(&(c * b))
+            .then(self.satisfied.cmp(&other.satisfied))
+    }
+}
+
+impl PartialOrd for MatchScore {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::fmt::Display for MatchScore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{} matchers satisfied", self.satisfied, self.total)
+    }
+}
+
+/// Whether `matcher` expects something to be absent.
+fn forbids(matcher: &Matcher) -> bool {
+    match matcher {
+        Matcher::SchemeMiss
+        | Matcher::PortMiss
+        | Matcher::QueryMiss(_)
+        | Matcher::FragmentMiss
+        | Matcher::HeaderMiss(_)
+        | Matcher::CookieMiss(_)
+        | Matcher::CacheControlMiss(_)
+        | Matcher::BodyMiss
+        | Matcher::FormFieldMiss(_)
+        | Matcher::Not(_) => true,
+        #[cfg(feature = "json")]
+        Matcher::JsonPathMiss(_) => true,
+        #[cfg(feature = "xml")]
+        Matcher::BodyXPathMiss(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether an observed matcher records that the request lacks something.
+fn is_absence(observed: &Matcher) -> bool {
+    match observed {
+        Matcher::Named(_, inner) => is_absence(inner),
+        #[cfg(feature = "json")]
+        Matcher::BodyJsonPointer(_, None) | Matcher::JsonPath(_, None) => true,
+        #[cfg(feature = "xml")]
+        Matcher::BodyXPath(_, None) => true,
+        matcher => forbids(matcher) && !matches!(matcher, Matcher::Not(_)),
+    }
+}
+
+/// The value an observed matcher recorded, masked when the current [`Redaction`] hides it.
+fn value(observed: &Matcher) -> Option<String> {
+    let value = recorded(observed)?;
+    let policy = Redaction::current();
+    let mut unnamed = observed;
+    while let Matcher::Named(_, inner) = unnamed {
+        unnamed = inner;
+    }
+    match unnamed {
+        _ if policy.hides(observed) => Some(REDACTED.into()),
+        Matcher::BodyEq(_) | Matcher::BodyContains(_) => policy.redact_form(&value).or(Some(value)),
+        _ => Some(value),
+    }
+}
+
+/// The value an observed matcher recorded, if it carries one. Variants that only ever
+/// appear as expectations, or record an absence, carry none.
+fn recorded(observed: &Matcher) -> Option<String> {
+    match observed {
+        Matcher::Method(value)
+        | Matcher::Scheme(value)
+        | Matcher::Host(value)
+        | Matcher::Path(value)
+        | Matcher::QueryEq(_, value)
+        | Matcher::FragmentEq(value)
+        | Matcher::HeaderEq(_, value)
+        | Matcher::CookieEq(_, value)
+        | Matcher::BearerToken(value)
+        | Matcher::ContentType(value)
+        | Matcher::PrefersLanguage(value)
+        | Matcher::IfNoneMatch(value)
+        | Matcher::IfMatch(value)
+        | Matcher::BodyEq(value)
+        | Matcher::BodyBase64Eq(value)
+        | Matcher::BodyHash(_, value)
+        | Matcher::BodyContains(value)
+        | Matcher::FormFieldEq(_, value) => Some(value.clone()),
+        Matcher::Port(port) => Some(port.to_string()),
+        Matcher::Version(version) => Some(version.to_string()),
+        Matcher::QueryEqAll(_, values) | Matcher::HeaderEqAll(_, values) => Some(values.join(", ")),
+        Matcher::BasicAuth(user, _) => Some(user.clone()),
+        Matcher::Range(start, end) => Some(format!(
+            "bytes={}-{}",
+            start.map(|start| start.to_string()).unwrap_or_default(),
+            end.map(|end| end.to_string()).unwrap_or_default()
+        )),
+        Matcher::IfModifiedSince(time) | Matcher::IfUnmodifiedSince(time) => {
+            Some(super::explain::http_date(*time))
+        }
+        Matcher::CacheControl(directive, Some(value)) => Some(format!("{directive}={value}")),
+        Matcher::CacheControl(directive, None) => Some(directive.clone()),
+        Matcher::BodyBytesEq(bytes) => Some(format!("{} bytes of binary body", bytes.len())),
+        Matcher::Named(_, inner) | Matcher::Not(inner) => recorded(inner),
+        // Several failures at once, each with its own value.
+        Matcher::AllOf(observed) => {
+            let values = observed.iter().filter_map(value).collect::<Vec<_>>();
+            (!values.is_empty()).then(|| values.join("; "))
+        }
+        Matcher::MethodAny(_)
+        | Matcher::SchemeMiss
+        | Matcher::PortMiss
+        | Matcher::PathRegex(_)
+        | Matcher::PathTemplate(_)
+        | Matcher::PathPrefix(_)
+        | Matcher::PathSuffix(_)
+        | Matcher::QueryExists(_)
+        | Matcher::QueryMiss(_)
+        | Matcher::QueryContainsValue(_, _)
+        | Matcher::QueryFormat(_, _)
+        | Matcher::FragmentMiss
+        | Matcher::HeaderExists(_)
+        | Matcher::HeaderMiss(_)
+        | Matcher::CookieExists(_)
+        | Matcher::CookieMiss(_)
+        | Matcher::AcceptsLanguage(_)
+        | Matcher::CacheControlMiss(_)
+        | Matcher::BodyExists
+        | Matcher::BodyMiss
+        | Matcher::HmacSignature(_, _, _)
+        | Matcher::BodyRegex(_)
+        | Matcher::FormFieldExists(_)
+        | Matcher::FormFieldMiss(_)
+        | Matcher::AnyOf(_) => None,
+        #[cfg(feature = "json")]
+        Matcher::BodyJsonEq(json) => Some(json.to_string()),
+        #[cfg(feature = "json")]
+        Matcher::BodyJsonPointer(_, json) | Matcher::JsonPath(_, json) => {
+            json.as_ref().map(|json| json.to_string())
+        }
+        #[cfg(feature = "json")]
+        Matcher::BodyJsonIncludes(_) | Matcher::JsonPathMiss(_) => None,
+        #[cfg(feature = "json-schema")]
+        Matcher::BodyJsonSchema(_) => None,
+        #[cfg(feature = "graphql")]
+        Matcher::GraphQLOperation(value) | Matcher::GraphQLQuery(value) => Some(value.clone()),
+        #[cfg(feature = "graphql")]
+        Matcher::GraphQLVariablesEq(json) => Some(json.to_string()),
+        #[cfg(feature = "graphql")]
+        Matcher::GraphQLVariablesIncludes(_) => None,
+        #[cfg(feature = "protobuf")]
+        Matcher::BodyProtobuf(message) => Some(format!(
+            "a `{}` message",
+            prost_reflect::ReflectMessage::descriptor(message).full_name()
+        )),
+        #[cfg(feature = "xml")]
+        Matcher::BodyXmlEq(value) => Some(value.clone()),
+        #[cfg(feature = "xml")]
+        Matcher::BodyXPath(_, value) => value.clone(),
+        #[cfg(feature = "xml")]
+        Matcher::BodyXPathMiss(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matchers::Matchers;
+    use crate::request::Request;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::wrong(Matcher::Method("POST".into()), Request::default(), Reason::Wrong, Some("GET"))]
+    #[case::missing(Matcher::HeaderEq("Accept".into(), "*/*".into()), Request::default(), Reason::Missing, None)]
+    #[case::unexpected(Matcher::QueryMiss("debug".into()), Request::from("/?debug=1"), Reason::Unexpected, Some("1"))]
+    #[case::negated(Matcher::Not(Box::new(Matcher::Path("/".into()))), Request::default(), Reason::Unexpected, Some("/"))]
+    #[case::port(Matcher::Port(8080), Request::default().with_port(80), Reason::Wrong, Some("80"))]
+    #[case::body_missing(Matcher::BodyEq("x".into()), Request::default(), Reason::Missing, None)]
+    #[case::scheme_missing(Matcher::Scheme("https".into()), Request::default(), Reason::Missing, None)]
+    #[case::port_missing(Matcher::Port(443), Request::default(), Reason::Missing, None)]
+    #[case::scheme_unexpected(Matcher::SchemeMiss, Request::default().with_scheme("http"), Reason::Unexpected, Some("http"))]
+    #[case::port_unexpected(Matcher::PortMiss, Request::default().with_port(8080), Reason::Unexpected, Some("8080"))]
+    fn mismatch_reason_and_actual(
+        #[case] matcher: Matcher,
+        #[case] request: Request,
+        #[case] reason: Reason,
+        #[case] actual: Option<&str>,
+    ) {
+        let mismatch = crate::matchers::Match::validate(&matcher, &request).unwrap();
+
+        assert_eq!(
+            (mismatch.expected(), mismatch.reason(), mismatch.actual()),
+            (matcher.explain().as_str(), reason, actual),
+            "Matcher {matcher:?} should fail for request {request}"
+        );
+    }
+
+    #[rstest]
+    #[case::all_of(
+        Matcher::AllOf(vec![Matcher::Method("POST".into()), Matcher::Path("/a".into())]),
+        Request::from("/b"),
+        Some("GET; /b")
+    )]
+    #[case::any_of(
+        Matcher::AnyOf(vec![Matcher::Path("/a".into()), Matcher::HeaderExists("X-Id".into())]),
+        Request::from("/b"),
+        Some("/b")
+    )]
+    #[case::not(Matcher::Not(Box::new(Matcher::BodyContains("a".into()))), Request::default().with_body("abc"), Some("a"))]
+    #[case::body_eq(Matcher::BodyEq("x".into()), Request::default().with_body("y"), Some("y"))]
+    #[case::body_regex(Matcher::BodyRegex("^x".into()), Request::default().with_body("yx"), Some("yx"))]
+    #[case::body_bytes(Matcher::BodyBytesEq(vec![1]), Request::default().with_body([0xff, 0]), Some("2 bytes of binary body"))]
+    #[case::body_exists(Matcher::BodyExists, Request::default(), None)]
+    #[case::range(Matcher::Range(Some(0), Some(9)), Request::default().with_header("Range", "bytes=5-"), Some("bytes=5-"))]
+    fn renders_observed_values(
+        #[case] matcher: Matcher,
+        #[case] request: Request,
+        #[case] actual: Option<&str>,
+    ) {
+        let mismatch = crate::matchers::Match::validate(&matcher, &request).unwrap();
+
+        assert_eq!(mismatch.actual(), actual);
+        assert!(!mismatch.to_string().contains("(\""), "{mismatch}");
+    }
+
+    #[cfg(feature = "json")]
+    #[rstest]
+    #[case::json_eq(Matcher::BodyJsonEq(serde_json::json!({"a": 1})), r#"{"a":2}"#)]
+    #[case::json_path(Matcher::JsonPath("$.a".into(), Some(serde_json::json!(1))), r#"{"a":2}"#)]
+    fn renders_observed_json(#[case] matcher: Matcher, #[case] body: &str) {
+        let request = Request::default().with_body(body);
+        let mismatch = crate::matchers::Match::validate(&matcher, &request).unwrap();
+
+        assert!(!mismatch.to_string().contains("Body"), "{mismatch}");
+        assert!(mismatch.actual().is_some_and(|actual| actual.contains('2')));
+    }
+
+    #[test]
+    fn absent_scheme_reads_as_missing() {
+        let report = Matchers::new()
+            .scheme("https")
+            .port(443)
+            .validate(&Request::default())
+            .unwrap_err();
+
+        assert_eq!(
+            report.to_string(),
+            [
+                "request did not match 2 expectation(s)",
+                "  - scheme must be `https`, but it is missing",
+                "  - port must be `443`, but it is missing",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn named_mismatch_uses_label() {
+        let matcher = Matcher::named("auth header", Matcher::HeaderExists("Authorization".into()));
+        let mismatch = crate::matchers::Match::validate(&matcher, &Request::default()).unwrap();
+
+        assert_eq!(mismatch, Mismatch::missing("auth header"));
+        assert_eq!(mismatch.to_string(), "auth header, but it is missing");
+    }
+
+    #[rstest]
+    #[case::closer(MatchScore::new(3, 4), MatchScore::new(1, 2))]
+    #[case::more_satisfied(MatchScore::new(2, 2), MatchScore::new(1, 1))]
+    #[case::empty_is_full(MatchScore::new(0, 0), MatchScore::new(4, 5))]
+    fn score_ordering(#[case] better: MatchScore, #[case] worse: MatchScore) {
+        assert!(better > worse, "{better} should rank above {worse}");
+    }
+
+    #[test]
+    fn score_counts_satisfied_matchers() {
+        let score = Matchers::new()
+            .method("GET")
+            .path("/users")
+            .header_exists("Accept")
+            .score(&Request::from("/posts"));
+
+        assert_eq!(
+            (score.satisfied(), score.failed(), score.total()),
+            (1, 2, 3)
+        );
+        assert!(!score.is_match());
+        assert!((score.fraction() - 1.0 / 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn report_display() {
+        let report = Matchers::new()
+            .method("POST")
+            .header_miss("X-Debug")
+            .query_exists("page")
+            .validate(&Request::default().with_header("X-Debug", "1"))
+            .unwrap_err();
+
+        assert_eq!(
+            report.to_string(),
+            [
+                "request did not match 3 expectation(s)",
+                r#"  - method must be `POST`, but got "GET""#,
+                r#"  - header `X-Debug` must be absent, but found "1""#,
+                r#"  - query parameter `page` must be present, but it is missing"#,
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use super::{Matcher, Matchers};
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Error, Serialize, SerializeSeq, Serializer};
+
+/// Serializes as a plain list of matchers; custom [`Match`](super::Match) implementations
+/// have no representation and fail serialization.
+impl Serialize for Matchers {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.len()))?;
+        for matcher in self {
+            let matcher = matcher.as_matcher().ok_or_else(|| {
+                S::Error::custom(format!("custom matcher {matcher:?} can't be serialized"))
+            })?;
+            seq.serialize_element(matcher)?;
+        }
+        seq.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Matchers {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<Matcher>::deserialize(deserializer).map(Matchers::from)
+    }
+}
+
+/// Reads and writes a [`std::time::SystemTime`] as an IMF-fixdate string.
+pub(super) mod http_date {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::SystemTime;
+
+    pub fn serialize<S: Serializer>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::super::explain::http_date(*time))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        super::super::parse_http_date(&value)
+            .ok_or_else(|| D::Error::custom(format!("invalid HTTP date {value:?}")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matchers::{HashAlgorithm, Match, Mismatch};
+    use crate::request::{HttpVersion, Request};
+    use serde_json::json;
+    use std::time::{Duration, SystemTime};
+
+    #[rstest::rstest]
+    #[case::unit(Matcher::BodyMiss, json!("body_miss"))]
+    #[case::single(Matcher::Method("GET".into()), json!({"method": "GET"}))]
+    #[case::pair(Matcher::QueryEq("page".into(), "2".into()), json!({"query_eq": ["page", "2"]}))]
+    #[case::version(Matcher::Version(HttpVersion::Http2), json!({"version": "HTTP/2"}))]
+    #[case::hash(Matcher::BodyHash(HashAlgorithm::Sha256, "00".into()), json!({"body_hash": ["sha256", "00"]}))]
+    #[case::date(Matcher::IfModifiedSince(SystemTime::UNIX_EPOCH + Duration::from_secs(784_111_777)), json!({"if_modified_since": "Sun, 06 Nov 1994 08:49:37 GMT"}))]
+    #[case::nested(Matcher::Not(Box::new(Matcher::HeaderExists("X-Debug".into()))), json!({"not": {"header_exists": "X-Debug"}}))]
+    fn matcher_round_trip(#[case] matcher: Matcher, #[case] expected: serde_json::Value) {
+        let serialized = serde_json::to_value(&matcher).unwrap();
+
+        assert_eq!(
+            serialized, expected,
+            "Matcher {matcher:?} should serialize readably"
+        );
+        assert_eq!(
+            serde_json::from_value::<Matcher>(serialized).unwrap(),
+            matcher
+        );
+    }
+
+    #[test]
+    fn matchers_round_trip() {
+        let matchers = Matchers::new().method("POST").path("/users");
+        let serialized = serde_json::to_value(&matchers).unwrap();
+
+        assert_eq!(serialized, json!([{"method": "POST"}, {"path": "/users"}]));
+
+        let restored: Matchers = serde_json::from_value(serialized).unwrap();
+        assert_eq!(restored.explain(), matchers.explain());
+    }
+
+    #[derive(Debug)]
+    struct Anything;
+
+    impl Match for Anything {
+        fn validate(&self, _: &Request) -> Option<Mismatch> {
+            None
+        }
+    }
+
+    #[test]
+    fn custom_matchers_are_not_serializable() {
+        assert!(serde_json::to_value(Matchers::new().with(Anything)).is_err());
+    }
+}
@@ -0,0 +1,200 @@
+use super::{Matcher, body_preview};
+use crate::request::Request;
+use sxd_document::dom::{ChildOfElement, ChildOfRoot, Element};
+use sxd_document::{Package, parser};
+use sxd_xpath::{Value, evaluate_xpath};
+
+fn parse_body(request: &Request) -> Result<Package, Matcher> {
+    match request.body.bytes().as_deref() {
+        Some(body) => std::str::from_utf8(body)
+            .ok()
+            .and_then(|text| parser::parse(text).ok())
+            .ok_or_else(|| body_preview(body)),
+        None => Err(Matcher::BodyMiss),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Renders an element so that equivalent documents produce identical strings: attributes are
+/// sorted, comments and processing instructions are dropped, and text is trimmed.
+fn write_canonical(element: Element, parent_ns: Option<&str>, out: &mut String) {
+    let name = element.name();
+    out.push('<');
+    out.push_str(name.local_part());
+
+    if name.namespace_uri() != parent_ns {
+        out.push_str(&format!(
+            " xmlns=\"{}\"",
+            escape(name.namespace_uri().unwrap_or_default())
+        ));
+    }
+
+    let mut attributes: Vec<(&str, &str)> = element
+        .attributes()
+        .iter()
+        .map(|attr| (attr.name().local_part(), attr.value()))
+        .collect();
+    attributes.sort_unstable();
+    for (key, value) in attributes {
+        out.push_str(&format!(" {key}=\"{}\"", escape(value)));
+    }
+    out.push('>');
+
+    for child in element.children() {
+        match child {
+            ChildOfElement::Element(child) => write_canonical(child, name.namespace_uri(), out),
+            ChildOfElement::Text(text) => out.push_str(&escape(text.text().trim())),
+            _ => {}
+        }
+    }
+
+    out.push_str(&format!("</{}>", name.local_part()));
+}
+
+fn canonical(package: &Package) -> String {
+    let mut out = String::new();
+    for child in package.as_document().root().children() {
+        if let ChildOfRoot::Element(element) = child {
+            write_canonical(element, None, &mut out);
+        }
+    }
+    out
+}
+
+pub(super) fn validate_eq(expected: &str, request: &Request) -> Option<Matcher> {
+    let actual = match parse_body(request) {
+        Ok(actual) => canonical(&actual),
+        Err(report) => return Some(report),
+    };
+
+    match parser::parse(expected) {
+        Ok(expected) if canonical(&expected) == actual => None,
+        _ => Some(Matcher::BodyXmlEq(actual)),
+    }
+}
+
+/// Evaluates `expr` and returns the string value of its result, or `None` when it selects
+/// nothing (an empty node set, `false`, or an invalid expression).
+fn select(expr: &str, package: &Package) -> Option<String> {
+    let document = package.as_document();
+    match evaluate_xpath(&document, expr).ok()? {
+        Value::Nodeset(nodes) if nodes.size() == 0 => None,
+        Value::Boolean(false) => None,
+        value => Some(value.string()),
+    }
+}
+
+pub(super) fn validate_xpath(
+    expr: &str,
+    expected: &Option<String>,
+    request: &Request,
+) -> Option<Matcher> {
+    let package = match parse_body(request) {
+        Ok(package) => package,
+        Err(report) => return Some(report),
+    };
+
+    match (expected, select(expr, &package)) {
+        (_, None) => Some(Matcher::BodyXPathMiss(expr.to_string())),
+        (None, Some(_)) => None,
+        (Some(expected), Some(actual)) if expected == &actual => None,
+        (Some(_), Some(actual)) => Some(Matcher::BodyXPath(expr.to_string(), Some(actual))),
+    }
+}
+
+pub(super) fn validate_xpath_miss(expr: &str, request: &Request) -> Option<Matcher> {
+    let package = parse_body(request).ok()?;
+
+    select(expr, &package).map(|actual| Matcher::BodyXPath(expr.to_string(), Some(actual)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn with_body(body: &str) -> Request {
+        Request::default().with_body(body)
+    }
+
+    fn xpath(expr: &str, value: Option<&str>) -> Matcher {
+        Matcher::BodyXPath(expr.into(), value.map(String::from))
+    }
+
+    fn xpath_miss(expr: &str) -> Matcher {
+        Matcher::BodyXPathMiss(expr.into())
+    }
+
+    #[rstest]
+    #[case::whitespace("<a><b>1</b></a>", "<a>\n  <b> 1 </b>\n</a>")]
+    #[case::attribute_order(r#"<a x="1" y="2"/>"#, r#"<a y="2"  x="1"></a>"#)]
+    #[case::comments(
+        "<a><b/></a>",
+        "<?xml version=\"1.0\"?><!-- note --><a><!-- inner --><b/></a>"
+    )]
+    #[case::namespaces(
+        r#"<soap:Envelope xmlns:soap="urn:s"><soap:Body/></soap:Envelope>"#,
+        r#"<e:Envelope xmlns:e="urn:s"><e:Body/></e:Envelope>"#
+    )]
+    fn xml_eq_matches(#[case] expected: &str, #[case] body: &str) {
+        let request = with_body(body);
+        let matcher = Matcher::BodyXmlEq(expected.into());
+
+        assert!(
+            matcher.observed(&request).is_none(),
+            "Matcher {matcher:?} should match request: {request}"
+        );
+    }
+
+    #[rstest]
+    #[case::text("<a><b>1</b></a>", "<a><b>2</b></a>", Matcher::BodyXmlEq("<a><b>2</b></a>".into()))]
+    #[case::attribute(r#"<a x="1"/>"#, r#"<a  x="2" />"#, Matcher::BodyXmlEq(r#"<a x="2"></a>"#.into()))]
+    #[case::not_xml("<a/>", "{}", Matcher::BodyContains("{}".into()))]
+    fn xml_eq_reports_canonical_body(
+        #[case] expected: &str,
+        #[case] body: &str,
+        #[case] report: Matcher,
+    ) {
+        let request = with_body(body);
+        let matcher = Matcher::BodyXmlEq(expected.into());
+
+        assert_eq!(
+            matcher.observed(&request),
+            Some(report.clone()),
+            "Matcher {matcher:?} should report {report:?} for request: {request}"
+        );
+        assert!(
+            report.observed(&request).is_none(),
+            "Report {report:?} should itself match request: {request}"
+        );
+    }
+
+    #[rstest]
+    #[case::wrong_value(xpath("/order/id", Some("7")), xpath("/order/id", Some("42")))]
+    #[case::missing(xpath("/order/customer", None), xpath_miss("/order/customer"))]
+    #[case::attribute(
+        xpath("/order/@status", Some("new")),
+        xpath("/order/@status", Some("paid"))
+    )]
+    #[case::miss(xpath_miss("//item"), xpath("//item", Some("book")))]
+    #[case::boolean(xpath("count(//item) > 1", None), xpath_miss("count(//item) > 1"))]
+    fn xpath_validation(#[case] invalid_matcher: Matcher, #[case] valid_matcher: Matcher) {
+        let request = with_body(r#"<order status="paid"><id>42</id><item>book</item></order>"#);
+
+        assert_eq!(
+            invalid_matcher.observed(&request),
+            Some(valid_matcher.clone()),
+            "Invalid matcher {invalid_matcher:?} should report {valid_matcher:?} for request: {request}"
+        );
+        assert!(
+            valid_matcher.observed(&request).is_none(),
+            "Valid matcher {valid_matcher:?} should pass validation for request: {request}"
+        );
+    }
+}
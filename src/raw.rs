@@ -0,0 +1,528 @@
+use crate::headers::Headers;
+use crate::request::{HttpVersion, Request, split_url};
+use crate::response::Response;
+use std::borrow::Cow;
+
+/// Why raw HTTP/1.x bytes couldn't be read as a request or response.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RawRequestError {
+    /// The input ends before the blank line closing the headers.
+    Incomplete,
+    /// The request line or headers aren't valid UTF-8.
+    NotUtf8,
+    /// The request line isn't `METHOD target VERSION`.
+    InvalidRequestLine(String),
+    /// The status line isn't `VERSION code [reason]`.
+    InvalidStatusLine(String),
+    UnsupportedVersion(String),
+    /// A header line lacks the `:` separator.
+    InvalidHeader(String),
+    InvalidContentLength(String),
+    /// The body is shorter than the `Content-Length` header announces.
+    TruncatedBody {
+        expected: usize,
+        actual: usize,
+    },
+    /// A `Transfer-Encoding: chunked` body is malformed or ends early.
+    InvalidChunk,
+    /// More header lines than [`ParseLimits::max_headers`].
+    TooManyHeaders(usize),
+    /// The request line or a header line is longer than [`ParseLimits::max_header_size`].
+    HeaderTooLarge(usize),
+    /// The body is longer than [`ParseLimits::max_body_size`].
+    BodyTooLarge(usize),
+    /// More query parameters than [`ParseLimits::max_query_params`].
+    TooManyQueryParams(usize),
+}
+
+/// Upper bounds applied while parsing raw requests, so untrusted input can't exhaust
+/// memory. The defaults suit ordinary API traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseLimits {
+    pub max_headers: usize,
+    /// In bytes, for the request line and for each header line.
+    pub max_header_size: usize,
+    /// In bytes.
+    pub max_body_size: usize,
+    pub max_query_params: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_headers: 100,
+            max_header_size: 8 * 1024,
+            max_body_size: 16 * 1024 * 1024,
+            max_query_params: 1000,
+        }
+    }
+}
+
+impl ParseLimits {
+    /// No limits at all, for trusted input such as `.http` files.
+    pub fn unlimited() -> Self {
+        Self {
+            max_headers: usize::MAX,
+            max_header_size: usize::MAX,
+            max_body_size: usize::MAX,
+            max_query_params: usize::MAX,
+        }
+    }
+}
+
+impl std::fmt::Display for RawRequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RawRequestError::Incomplete => f.write_str("request headers are not terminated"),
+            RawRequestError::NotUtf8 => f.write_str("request head is not valid UTF-8"),
+            RawRequestError::InvalidRequestLine(line) => {
+                write!(f, "invalid request line {line:?}")
+            }
+            RawRequestError::InvalidStatusLine(line) => write!(f, "invalid status line {line:?}"),
+            RawRequestError::UnsupportedVersion(version) => {
+                write!(f, "unsupported HTTP version {version:?}")
+            }
+            RawRequestError::InvalidHeader(line) => write!(f, "invalid header line {line:?}"),
+            RawRequestError::InvalidContentLength(value) => {
+                write!(f, "invalid Content-Length {value:?}")
+            }
+            RawRequestError::TruncatedBody { expected, actual } => {
+                write!(f, "expected {expected} body bytes, got {actual}")
+            }
+            RawRequestError::InvalidChunk => f.write_str("malformed chunked body"),
+            RawRequestError::TooManyHeaders(max) => write!(f, "more than {max} headers"),
+            RawRequestError::HeaderTooLarge(max) => write!(f, "header line exceeds {max} bytes"),
+            RawRequestError::BodyTooLarge(max) => write!(f, "body exceeds {max} bytes"),
+            RawRequestError::TooManyQueryParams(max) => {
+                write!(f, "more than {max} query parameters")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RawRequestError {}
+
+/// Whether `target` is in a form `method` allows: `/path` or an absolute URL, `*` for
+/// `OPTIONS`, and `host:port` for `CONNECT`.
+fn is_request_target(method: &str, target: &str) -> bool {
+    if method.eq_ignore_ascii_case("CONNECT") {
+        return !target.is_empty() && !target.contains(['/', '?', '#']);
+    }
+    let absolute = target.split_once("://").is_some_and(|(scheme, _)| {
+        scheme.starts_with(|ch: char| ch.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || "+-.".contains(ch))
+    });
+    match target {
+        "*" => method.eq_ignore_ascii_case("OPTIONS"),
+        target => target.starts_with('/') || absolute,
+    }
+}
+
+/// Reads a request line, headers and body. Lines may end in `\r\n` or a bare `\n`, as in
+/// `.http` files. Without `Content-Length` or chunked framing everything after the headers
+/// is the body.
+pub(crate) fn parse(bytes: &[u8], limits: &ParseLimits) -> Result<Request, RawRequestError> {
+    let (request_line, headers, rest) = parse_head(bytes, limits)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target), version, None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(RawRequestError::InvalidRequestLine(request_line.into()));
+    };
+
+    if !is_request_target(method, target) {
+        return Err(RawRequestError::InvalidRequestLine(request_line.into()));
+    }
+    let (authority, target) = match target {
+        "*" => (None, "*"),
+        target => split_url(target),
+    };
+    let mut request = Request::from(target);
+    if target == "*" {
+        request.path = "*".into();
+    }
+    request.method = method.into();
+    // HTTP/0.9 style lines without a version are read as HTTP/1.1.
+    if let Some(version) = version {
+        request.http_version = parse_version(version)?;
+    }
+    if let Some((scheme, host, port)) = authority {
+        request.scheme = scheme.map(String::from);
+        request.host = Some(host.into());
+        request.port = port;
+    }
+    if request.query.values().map(Vec::len).sum::<usize>() > limits.max_query_params {
+        return Err(RawRequestError::TooManyQueryParams(limits.max_query_params));
+    }
+
+    let body = parse_body(&headers, rest, limits)?;
+    request.headers = headers;
+    if !body.is_empty() {
+        request.set_body(body.into_owned());
+    }
+    Ok(request)
+}
+
+/// Reads a status line, headers and body, framed like [`parse`] does for requests.
+/// Responses to `HEAD` can't be told apart, so pass only their head.
+pub(crate) fn parse_response(
+    bytes: &[u8],
+    limits: &ParseLimits,
+) -> Result<Response, RawRequestError> {
+    let (status_line, headers, rest) = parse_head(bytes, limits)?;
+    let invalid = || RawRequestError::InvalidStatusLine(status_line.into());
+    let mut parts = status_line.splitn(3, ' ');
+    let (Some(version), Some(status)) = (parts.next(), parts.next()) else {
+        return Err(invalid());
+    };
+    let status = status
+        .parse()
+        .ok()
+        .filter(|status| (100..1000).contains(status))
+        .ok_or_else(invalid)?;
+
+    let mut response = Response::new(status).with_http_version(parse_version(version)?);
+    response.reason = parts
+        .next()
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty())
+        .map(String::from);
+    let body = match response.allows_body() {
+        true => parse_body(&headers, rest, limits)?,
+        false => Cow::Borrowed(&[][..]),
+    };
+    response.headers = headers;
+    if !body.is_empty() {
+        response.set_body(body.into_owned());
+    }
+    Ok(response)
+}
+
+/// Splits off the start line and reads the headers, returning the bytes after them.
+fn parse_head<'a>(
+    bytes: &'a [u8],
+    limits: &ParseLimits,
+) -> Result<(&'a str, Headers, &'a [u8]), RawRequestError> {
+    let (head, rest) = split_head(bytes).ok_or(RawRequestError::Incomplete)?;
+    let head = std::str::from_utf8(head).map_err(|_| RawRequestError::NotUtf8)?;
+    let mut lines = head.lines().skip_while(|line| line.is_empty());
+    let within_size = |line: &str| match line.len() > limits.max_header_size {
+        true => Err(RawRequestError::HeaderTooLarge(limits.max_header_size)),
+        false => Ok(()),
+    };
+
+    let start_line = lines.next().ok_or(RawRequestError::Incomplete)?;
+    within_size(start_line)?;
+
+    let mut headers = Headers::new();
+    for (count, line) in lines.enumerate() {
+        if count == limits.max_headers {
+            return Err(RawRequestError::TooManyHeaders(limits.max_headers));
+        }
+        within_size(line)?;
+        let (name, value) = line
+            .split_once(':')
+            .filter(|(name, _)| !name.trim().is_empty())
+            .ok_or_else(|| RawRequestError::InvalidHeader(line.into()))?;
+        headers.append(name.trim(), value.trim());
+    }
+    Ok((start_line, headers, rest))
+}
+
+/// The body framed by `Transfer-Encoding: chunked` or `Content-Length`, or else `rest`.
+fn parse_body<'a>(
+    headers: &Headers,
+    rest: &'a [u8],
+    limits: &ParseLimits,
+) -> Result<Cow<'a, [u8]>, RawRequestError> {
+    if is_chunked(headers) {
+        return decode_chunked(rest, limits).map(Cow::Owned);
+    }
+
+    match headers.get("Content-Length") {
+        Some(length) => {
+            let expected: usize = length
+                .parse()
+                .map_err(|_| RawRequestError::InvalidContentLength(length.clone()))?;
+            if expected > limits.max_body_size {
+                return Err(RawRequestError::BodyTooLarge(limits.max_body_size));
+            }
+            if rest.len() < expected {
+                return Err(RawRequestError::TruncatedBody {
+                    expected,
+                    actual: rest.len(),
+                });
+            }
+            Ok(Cow::Borrowed(&rest[..expected]))
+        }
+        None if rest.len() > limits.max_body_size => {
+            Err(RawRequestError::BodyTooLarge(limits.max_body_size))
+        }
+        None => Ok(Cow::Borrowed(rest)),
+    }
+}
+
+/// Whether the last transfer coding is `chunked`.
+fn is_chunked(headers: &Headers) -> bool {
+    headers.get_all("Transfer-Encoding").iter().any(|value| {
+        value
+            .rsplit(',')
+            .next()
+            .is_some_and(|last| last.trim().eq_ignore_ascii_case("chunked"))
+    })
+}
+
+/// Joins the chunks up to the terminating zero-size one; trailers are dropped.
+fn decode_chunked(mut rest: &[u8], limits: &ParseLimits) -> Result<Vec<u8>, RawRequestError> {
+    let mut body = Vec::new();
+    loop {
+        let line_end = rest
+            .iter()
+            .position(|byte| *byte == b'\n')
+            .ok_or(RawRequestError::InvalidChunk)?;
+        let size_line =
+            std::str::from_utf8(&rest[..line_end]).map_err(|_| RawRequestError::InvalidChunk)?;
+        let size = size_line.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| RawRequestError::InvalidChunk)?;
+        rest = &rest[line_end + 1..];
+        if size == 0 {
+            return Ok(body);
+        }
+        if body.len().saturating_add(size) > limits.max_body_size {
+            return Err(RawRequestError::BodyTooLarge(limits.max_body_size));
+        }
+        let chunk = rest.get(..size).ok_or(RawRequestError::InvalidChunk)?;
+        body.extend_from_slice(chunk);
+        rest = &rest[size..];
+        rest = rest
+            .strip_prefix(b"\r\n")
+            .or_else(|| rest.strip_prefix(b"\n"))
+            .ok_or(RawRequestError::InvalidChunk)?;
+    }
+}
+
+/// Writes `request` as HTTP/1.1 (or HTTP/1.0) in origin-form. A `Host` header is added
+/// from the host (falling back to `localhost`), and `Content-Length` for a body unless the
+/// request already frames it; under `Transfer-Encoding: chunked` the body goes out as a
+/// single chunk.
+pub(crate) fn render(request: &Request) -> Vec<u8> {
+    let version = match request.http_version {
+        HttpVersion::Http10 => "HTTP/1.0",
+        _ => "HTTP/1.1",
+    };
+    let mut head = format!("{} {} {version}\r\n", request.method, request.target());
+
+    if !request.headers.contains("Host") {
+        let host = request.host.as_deref().unwrap_or("localhost");
+        match request.port {
+            Some(port) => head.push_str(&format!("Host: {host}:{port}\r\n")),
+            None => head.push_str(&format!("Host: {host}\r\n")),
+        }
+    }
+    for (name, value) in request.headers.iter() {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    let body = request.body.bytes();
+    let framed =
+        request.headers.contains("Content-Length") || request.headers.contains("Transfer-Encoding");
+    if let Some(body) = &body
+        && !framed
+    {
+        head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    head.push_str("\r\n");
+
+    let mut bytes = head.into_bytes();
+    let body = body.as_deref().unwrap_or_default();
+    if is_chunked(&request.headers) {
+        if !body.is_empty() {
+            bytes.extend_from_slice(format!("{:x}\r\n", body.len()).as_bytes());
+            bytes.extend_from_slice(body);
+            bytes.extend_from_slice(b"\r\n");
+        }
+        bytes.extend_from_slice(b"0\r\n\r\n");
+    } else {
+        bytes.extend_from_slice(body);
+    }
+    bytes
+}
+
+/// Writes `response` as HTTP/1.1 (or HTTP/1.0), adding `Content-Length` unless the body
+/// is already framed or the status forbids one.
+pub(crate) fn render_response(response: &Response) -> Vec<u8> {
+    let version = match response.http_version {
+        HttpVersion::Http10 => "HTTP/1.0",
+        _ => "HTTP/1.1",
+    };
+    let mut head = format!("{version} {}", response.status);
+    if let Some(reason) = response.reason() {
+        head.push_str(&format!(" {reason}"));
+    }
+    head.push_str("\r\n");
+    for (name, value) in response.headers.iter() {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    let body = response.body.bytes();
+    let framed = response.headers.contains("Content-Length")
+        || response.headers.contains("Transfer-Encoding");
+    if !framed && response.allows_body() {
+        let length = body.as_deref().map_or(0, <[u8]>::len);
+        head.push_str(&format!("Content-Length: {length}\r\n"));
+    }
+    head.push_str("\r\n");
+
+    let mut bytes = head.into_bytes();
+    if response.allows_body() {
+        bytes.extend_from_slice(body.as_deref().unwrap_or_default());
+    }
+    bytes
+}
+
+/// Splits at the first empty line, returning the head and the bytes after it.
+fn split_head(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let mut start = 0;
+    for (idx, _) in bytes.iter().enumerate().filter(|(_, byte)| **byte == b'\n') {
+        let line = &bytes[start..idx];
+        if line.is_empty() || line == b"\r" {
+            // A leading empty line isn't the end of an empty head.
+            if bytes[..start].iter().any(|b| !b.is_ascii_whitespace()) {
+                return Some((&bytes[..start], &bytes[idx + 1..]));
+            }
+        }
+        start = idx + 1;
+    }
+    None
+}
+
+fn parse_version(version: &str) -> Result<HttpVersion, RawRequestError> {
+    match version {
+        "HTTP/1.0" => Ok(HttpVersion::Http10),
+        "HTTP/1.1" => Ok(HttpVersion::Http11),
+        "HTTP/2" | "HTTP/2.0" => Ok(HttpVersion::Http2),
+        "HTTP/3" | "HTTP/3.0" => Ok(HttpVersion::Http3),
+        version => Err(RawRequestError::UnsupportedVersion(version.into())),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn parses_full_request() {
+        let request = Request::parse_http(
+            b"POST /users?notify=true HTTP/1.1\r\nHost: api.example.com\r\nContent-Type: application/json\r\nContent-Length: 14\r\n\r\n{\"name\":\"Bob\"}trailing",
+        )
+        .unwrap();
+
+        assert_eq!(
+            request,
+            Request::from("/users?notify=true")
+                .with_method("POST")
+                .with_header("Host", "api.example.com")
+                .with_header("Content-Type", "application/json")
+                .with_header("Content-Length", "14")
+                .with_body("{\"name\":\"Bob\"}")
+        );
+    }
+
+    #[rstest]
+    #[case::bare_newlines(b"GET /health HTTP/1.0\nAccept: */*\n\n", Request::from("/health").with_http_version(HttpVersion::Http10).with_header("Accept", "*/*"))]
+    #[case::absolute_form(b"GET http://example.com:8080/a HTTP/1.1\r\n\r\n", Request::from("/a").with_scheme("http").with_host("example.com").with_port(8080))]
+    #[case::asterisk(b"OPTIONS * HTTP/1.1\r\n\r\n", Request::from("/").with_path("*").with_method("OPTIONS"))]
+    #[case::authority_form(b"CONNECT example.com:443 HTTP/1.1\r\n\r\n", Request::default().with_method("CONNECT").with_host("example.com").with_port(443))]
+    #[case::repeated_header(b"GET / HTTP/1.1\r\nAccept: a\r\naccept: b\r\n\r\n", Request::default().with_header("Accept", "a").with_appended_header("accept", "b"))]
+    #[case::body_without_length(b"\nPUT /note HTTP/1.1\n\nhello", Request::from("/note").with_method("PUT").with_body("hello"))]
+    fn parses(#[case] raw: &[u8], #[case] expected: Request) {
+        assert_eq!(
+            parse(raw, &ParseLimits::default()),
+            Ok(expected),
+            "Raw request {:?} should be parsed",
+            String::from_utf8_lossy(raw)
+        );
+    }
+
+    #[rstest]
+    #[case::get(Request::from("/health?full").with_host("a.test").with_port(8080), "GET /health?full HTTP/1.1\r\nHost: a.test:8080\r\n\r\n")]
+    #[case::no_host(Request::default().with_http_version(HttpVersion::Http10), "GET / HTTP/1.0\r\nHost: localhost\r\n\r\n")]
+    #[case::body(Request::from("/n").with_method("PUT").with_header("Host", "a.test").with_body("hi"), "PUT /n HTTP/1.1\r\nHost: a.test\r\nContent-Length: 2\r\n\r\nhi")]
+    fn renders(#[case] request: Request, #[case] raw: &str) {
+        assert_eq!(request.to_http_string(), raw);
+    }
+
+    #[rstest]
+    #[case::length(
+        Request::from("/users?tag=a&tag=b")
+            .with_method("POST")
+            .with_header("Host", "api.example.com")
+            .with_header("Via", "1.1 a")
+            .with_appended_header("Via", "1.1 b")
+            .with_header("Content-Length", "4")
+            .with_body([0, 1, 2, 3])
+    )]
+    #[case::chunked(
+        Request::parse_http(
+            b"POST /upload HTTP/1.1\r\nHost: a\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nhi\r\n3\r\n!!!\r\n0\r\n\r\n"
+        )
+        .unwrap()
+    )]
+    #[case::chunked_empty(
+        Request::from("/")
+            .with_method("POST")
+            .with_header("Host", "a")
+            .with_header("Transfer-Encoding", "gzip, chunked")
+    )]
+    fn render_round_trips(#[case] request: Request) {
+        assert_eq!(Request::parse_http(&request.to_http_bytes()), Ok(request));
+    }
+
+    #[rstest]
+    #[case::incomplete(b"GET / HTTP/1.1\r\nHost: a", RawRequestError::Incomplete)]
+    #[case::request_line(b"GET\r\n\r\n", RawRequestError::InvalidRequestLine("GET".into()))]
+    #[case::relative_target(b"GET users HTTP/1.1\r\n\r\n", RawRequestError::InvalidRequestLine("GET users HTTP/1.1".into()))]
+    #[case::asterisk_get(b"GET * HTTP/1.1\r\n\r\n", RawRequestError::InvalidRequestLine("GET * HTTP/1.1".into()))]
+    #[case::connect_path(b"CONNECT /a HTTP/1.1\r\n\r\n", RawRequestError::InvalidRequestLine("CONNECT /a HTTP/1.1".into()))]
+    #[case::version(b"GET / HTTP/9\r\n\r\n", RawRequestError::UnsupportedVersion("HTTP/9".into()))]
+    #[case::header(b"GET / HTTP/1.1\r\nbroken\r\n\r\n", RawRequestError::InvalidHeader("broken".into()))]
+    #[case::content_length(b"GET / HTTP/1.1\r\nContent-Length: x\r\n\r\n", RawRequestError::InvalidContentLength("x".into()))]
+    #[case::truncated(b"POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nab", RawRequestError::TruncatedBody { expected: 5, actual: 2 })]
+    fn rejects(#[case] raw: &[u8], #[case] error: RawRequestError) {
+        assert_eq!(parse(raw, &ParseLimits::default()), Err(error));
+    }
+
+    #[rstest]
+    #[case::headers(
+        b"GET / HTTP/1.1\r\nA: 1\r\nB: 2\r\nC: 3\r\n\r\n",
+        RawRequestError::TooManyHeaders(2)
+    )]
+    #[case::header_size(
+        b"GET / HTTP/1.1\r\nAuthorization: Bearer 0123456789abcdef\r\n\r\n",
+        RawRequestError::HeaderTooLarge(32)
+    )]
+    #[case::request_line(
+        b"GET /0123456789abcdef0123456789abcdef HTTP/1.1\r\n\r\n",
+        RawRequestError::HeaderTooLarge(32)
+    )]
+    #[case::content_length(
+        b"POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\n",
+        RawRequestError::BodyTooLarge(8)
+    )]
+    #[case::body(b"POST / HTTP/1.1\r\n\r\n0123456789", RawRequestError::BodyTooLarge(8))]
+    #[case::query(
+        b"GET /?a&b&c&a HTTP/1.1\r\n\r\n",
+        RawRequestError::TooManyQueryParams(3)
+    )]
+    fn enforces_limits(#[case] raw: &[u8], #[case] error: RawRequestError) {
+        let limits = ParseLimits {
+            max_headers: 2,
+            max_header_size: 32,
+            max_body_size: 8,
+            max_query_params: 3,
+        };
+
+        assert_eq!(parse(raw, &limits), Err(error));
+    }
+}
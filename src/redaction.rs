@@ -0,0 +1,342 @@
+use crate::matchers::Matcher;
+use crate::request::Request;
+use crate::response::Response;
+use std::sync::{Arc, LazyLock, PoisonError, RwLock};
+
+/// Shown in place of a redacted value.
+pub const REDACTED: &str = "[REDACTED]";
+
+static POLICY: LazyLock<RwLock<Arc<Redaction>>> = LazyLock::new(Default::default);
+
+/// Which header and parameter values are masked when a request is displayed or reported,
+/// so secrets don't end up in logs. Names compare case-insensitively; parameters cover the
+/// query, cookies and form-encoded body fields. Reports mask the values expected of masked
+/// parts too.
+///
+/// The default masks `Authorization`, `Proxy-Authorization`, `Cookie` and `Set-Cookie`
+/// headers, and parameters such as `token`, `api_key` and `password`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redaction {
+    headers: Vec<String>,
+    params: Vec<String>,
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        let headers = [
+            "Authorization",
+            "Proxy-Authorization",
+            "Cookie",
+            "Set-Cookie",
+        ];
+        let params = [
+            "token",
+            "access_token",
+            "refresh_token",
+            "id_token",
+            "api_key",
+            "apikey",
+            "password",
+            "secret",
+            "client_secret",
+        ];
+        Self {
+            headers: headers.map(String::from).into(),
+            params: params.map(String::from).into(),
+        }
+    }
+}
+
+impl Redaction {
+    /// A policy that masks nothing.
+    pub fn none() -> Self {
+        Self {
+            headers: Vec::new(),
+            params: Vec::new(),
+        }
+    }
+
+    pub fn header<S: Into<String>>(mut self, name: S) -> Self {
+        self.headers.push(name.into());
+        self
+    }
+
+    pub fn param<S: Into<String>>(mut self, name: S) -> Self {
+        self.params.push(name.into());
+        self
+    }
+
+    /// The policy `Display` and mismatch reports use.
+    pub fn current() -> Arc<Redaction> {
+        POLICY
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Makes this the policy for the whole process.
+    pub fn install(self) {
+        *POLICY.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(self);
+    }
+
+    pub fn hides_header(&self, name: &str) -> bool {
+        self.headers.iter().any(|h| h.eq_ignore_ascii_case(name))
+    }
+
+    pub fn hides_param(&self, name: &str) -> bool {
+        self.params.iter().any(|p| p.eq_ignore_ascii_case(name))
+    }
+
+    /// A copy of `request` with masked values replaced by [`REDACTED`].
+    pub fn apply(&self, request: &Request) -> Request {
+        let mut redacted = request.clone();
+        for (key, values) in redacted.query.iter_mut() {
+            if self.hides_param(key) {
+                values
+                    .iter_mut()
+                    .flatten()
+                    .for_each(|v| *v = REDACTED.into());
+            }
+        }
+        redacted.headers = request
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.hides_header(name) {
+                    REDACTED.into()
+                } else if name.eq_ignore_ascii_case("Cookie") {
+                    self.redact_cookies(value)
+                } else {
+                    value.clone()
+                };
+                (name.clone(), value)
+            })
+            .collect();
+        if let Some(body) = request.body.text().and_then(|body| self.redact_form(&body)) {
+            redacted.body = body.into();
+        }
+        redacted
+    }
+
+    /// A copy of `response` with masked headers, e.g. `Set-Cookie`, replaced by [`REDACTED`].
+    pub(crate) fn apply_response(&self, response: &Response) -> Response {
+        let mut redacted = response.clone();
+        redacted.headers = response
+            .headers
+            .iter()
+            .map(|(name, value)| match self.hides_header(name) {
+                true => (name.clone(), REDACTED.into()),
+                false => (name.clone(), value.clone()),
+            })
+            .collect();
+        redacted
+    }
+
+    /// `body` with masked `application/x-www-form-urlencoded` fields replaced, or `None` when
+    /// it has none.
+    pub(crate) fn redact_form(&self, body: &str) -> Option<String> {
+        let mut masked = false;
+        let pairs = body
+            .split('&')
+            .map(|pair| {
+                let hidden = pair.contains('=')
+                    && form_urlencoded::parse(pair.as_bytes())
+                        .next()
+                        .is_some_and(|(name, _)| self.hides_param(&name));
+                match (hidden, pair.split_once('=')) {
+                    (true, Some((name, _))) => {
+                        masked = true;
+                        format!("{name}={REDACTED}")
+                    }
+                    _ => pair.to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+        masked.then(|| pairs.join("&"))
+    }
+
+    fn redact_cookies(&self, header: &str) -> String {
+        header
+            .split(';')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if self.hides_param(name.trim()) => format!("{name}={REDACTED}"),
+                _ => pair.into(),
+            })
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+
+    /// Whether the value an observed matcher carries comes from a masked part.
+    pub(crate) fn hides(&self, observed: &Matcher) -> bool {
+        match observed {
+            Matcher::HeaderEq(name, _) | Matcher::HeaderEqAll(name, _) => self.hides_header(name),
+            Matcher::QueryEq(name, _)
+            | Matcher::QueryEqAll(name, _)
+            | Matcher::QueryContainsValue(name, _)
+            | Matcher::FormFieldEq(name, _) => self.hides_param(name),
+            Matcher::CookieEq(name, _) => self.hides_header("Cookie") || self.hides_param(name),
+            Matcher::BearerToken(_) | Matcher::BasicAuth(..) => self.hides_header("Authorization"),
+            Matcher::Named(_, inner) => self.hides(inner),
+            _ => false,
+        }
+    }
+
+    /// `matcher` with the values of masked parts replaced by [`REDACTED`], for explaining
+    /// expectations without revealing the secrets they expect.
+    pub(crate) fn mask(&self, matcher: &Matcher) -> Matcher {
+        let masked = || REDACTED.to_string();
+        match matcher {
+            Matcher::HeaderEq(name, _) if self.hides_header(name) => {
+                Matcher::HeaderEq(name.clone(), masked())
+            }
+            Matcher::HeaderEqAll(name, values) if self.hides_header(name) => {
+                Matcher::HeaderEqAll(name.clone(), values.iter().map(|_| masked()).collect())
+            }
+            Matcher::QueryEq(name, _) if self.hides_param(name) => {
+                Matcher::QueryEq(name.clone(), masked())
+            }
+            Matcher::QueryEqAll(name, values) if self.hides_param(name) => {
+                Matcher::QueryEqAll(name.clone(), values.iter().map(|_| masked()).collect())
+            }
+            Matcher::QueryContainsValue(name, _) if self.hides_param(name) => {
+                Matcher::QueryContainsValue(name.clone(), masked())
+            }
+            Matcher::FormFieldEq(name, _) if self.hides_param(name) => {
+                Matcher::FormFieldEq(name.clone(), masked())
+            }
+            matcher @ Matcher::CookieEq(name, _) if self.hides(matcher) => {
+                Matcher::CookieEq(name.clone(), masked())
+            }
+            Matcher::BearerToken(_) if self.hides(matcher) => Matcher::BearerToken(masked()),
+            Matcher::BasicAuth(..) if self.hides(matcher) => Matcher::BasicAuth(masked(), masked()),
+            Matcher::BodyEq(body) => {
+                Matcher::BodyEq(self.redact_form(body).unwrap_or(body.clone()))
+            }
+            Matcher::BodyContains(body) => {
+                Matcher::BodyContains(self.redact_form(body).unwrap_or(body.clone()))
+            }
+            Matcher::Not(inner) => Matcher::Not(Box::new(self.mask(inner))),
+            Matcher::Named(label, inner) => {
+                Matcher::Named(label.clone(), Box::new(self.mask(inner)))
+            }
+            Matcher::AnyOf(matchers) => {
+                Matcher::AnyOf(matchers.iter().map(|matcher| self.mask(matcher)).collect())
+            }
+            Matcher::AllOf(matchers) => {
+                Matcher::AllOf(matchers.iter().map(|matcher| self.mask(matcher)).collect())
+            }
+            matcher => matcher.clone(),
+        }
+    }
+}
+
+impl Request {
+    /// A copy with the values the current [`Redaction`] masks replaced by [`REDACTED`].
+    pub fn redacted(&self) -> Request {
+        Redaction::current().apply(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::authorization(Request::default().with_header("authorization", "Bearer abc"), Request::default().with_header("authorization", REDACTED))]
+    #[case::query(Request::from("/?token=abc&page=2"), Request::from(format!("/?token={REDACTED}&page=2").as_str()))]
+    #[case::flag(Request::from("/?token"), Request::from("/?token"))]
+    #[case::cookie(Request::default().with_cookie("sid", "1"), Request::default().with_header("Cookie", REDACTED))]
+    #[case::plain(Request::default().with_header("Accept", "*/*"), Request::default().with_header("Accept", "*/*"))]
+    fn default_policy(#[case] request: Request, #[case] expected: Request) {
+        assert_eq!(Redaction::default().apply(&request), expected);
+    }
+
+    #[test]
+    fn custom_policy() {
+        let policy = Redaction::none().header("X-Api-Key").param("sid");
+        let request = Request::default()
+            .with_header("x-api-key", "k")
+            .with_header("Authorization", "Basic x")
+            .with_cookie("sid", "1")
+            .with_cookie("theme", "dark");
+
+        assert_eq!(
+            policy.apply(&request),
+            Request::default()
+                .with_header("x-api-key", REDACTED)
+                .with_header("Authorization", "Basic x")
+                .with_cookie("sid", REDACTED)
+                .with_cookie("theme", "dark")
+        );
+    }
+
+    #[test]
+    fn display_masks_secrets() {
+        let request = Request::from("/login?password=hunter2").with_header("Cookie", "sid=1");
+
+        assert_eq!(
+            request.to_string(),
+            format!(
+                r#"[GET /login?password={REDACTED} | with headers {{"Cookie" = "{REDACTED}"}}]"#
+            )
+        );
+    }
+
+    #[rstest]
+    #[case::form("user=ann&password=hunter2", format!("user=ann&password={REDACTED}"))]
+    #[case::encoded_name("api%5Fkey=k", format!("api%5Fkey={REDACTED}"))]
+    #[case::plain("user=ann&note", "user=ann&note".to_string())]
+    fn masks_form_fields(#[case] body: &str, #[case] expected: String) {
+        let request = Request::default().with_body(body);
+
+        assert_eq!(Redaction::default().apply(&request).body, expected.into());
+    }
+
+    #[test]
+    fn display_masks_form_body() {
+        let request = Request::from("/login").with_body("user=ann&password=hunter2");
+
+        assert!(
+            request
+                .to_string()
+                .contains(&format!("password={REDACTED}")),
+            "{request}"
+        );
+    }
+
+    #[test]
+    fn reports_mask_expected_secrets() {
+        let report = crate::Matchers::new()
+            .header_eq("Authorization", "Bearer s3cret")
+            .validate(&Request::default())
+            .unwrap_err();
+
+        assert!(!report.to_string().contains("s3cret"), "{report}");
+        assert!(report.to_string().contains(REDACTED), "{report}");
+    }
+
+    #[test]
+    fn reports_mask_body_secrets() {
+        let report = crate::Matchers::new()
+            .body_eq("user=bob&password=expected")
+            .validate(&Request::default().with_body("user=ann&password=hunter2"))
+            .unwrap_err();
+
+        let text = report.to_string();
+        assert!(
+            !text.contains("hunter2") && !text.contains("expected"),
+            "{text}"
+        );
+    }
+
+    #[test]
+    fn reports_mask_secrets() {
+        let report = crate::Matchers::new()
+            .header_eq("Authorization", "Bearer expected")
+            .validate(&Request::default().with_header("Authorization", "Bearer actual"))
+            .unwrap_err();
+
+        assert_eq!(report.mismatches()[0].actual(), Some(REDACTED));
+    }
+}
@@ -1,16 +1,253 @@
-use std::collections::HashMap;
+use crate::body::Body;
+use crate::curl::{self, CurlError};
+use crate::headers::Headers;
+use crate::raw::{self, ParseLimits, RawRequestError};
+use indexmap::IndexMap;
 
+mod builder;
+mod connection;
+mod content;
+mod diff;
+mod pseudo;
+
+pub use builder::{BuildError, RequestBuilder};
+pub use connection::{Connection, TlsInfo};
+pub use content::BodyError;
+pub use diff::{Difference, Part, RequestDiff};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum HttpVersion {
+    #[cfg_attr(feature = "serde", serde(rename = "HTTP/1.0"))]
+    Http10,
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "HTTP/1.1"))]
+    Http11,
+    #[cfg_attr(feature = "serde", serde(rename = "HTTP/2"))]
+    Http2,
+    #[cfg_attr(feature = "serde", serde(rename = "HTTP/3"))]
+    Http3,
+}
+
+impl HttpVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpVersion::Http10 => "HTTP/1.0",
+            HttpVersion::Http11 => "HTTP/1.1",
+            HttpVersion::Http2 => "HTTP/2",
+            HttpVersion::Http3 => "HTTP/3",
+        }
+    }
+}
+
+impl std::fmt::Display for HttpVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What made a path, query and fragment string unacceptable to [`Request::parse`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// A `?` with nothing after it.
+    EmptyQuery,
+    /// A `#` with nothing after it.
+    EmptyFragment,
+    /// A query pair without a name, e.g. `=value` or the gap in `a&&b`.
+    EmptyQueryKey { position: usize },
+    /// Whitespace, a control character or a second `#` at the given byte offset.
+    InvalidCharacter { character: char, position: usize },
+    /// A `%` not followed by two hex digits.
+    InvalidPercentEncoding { position: usize },
+    /// An absolute URL without a host, e.g. `http:///path`.
+    EmptyHost,
+    /// An absolute URL whose port isn't a number up to 65535.
+    InvalidPort(String),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::EmptyQuery => f.write_str("empty query after `?`"),
+            ParseError::EmptyFragment => f.write_str("empty fragment after `#`"),
+            ParseError::EmptyQueryKey { position } => {
+                write!(f, "query parameter without a name at {position}")
+            }
+            ParseError::InvalidCharacter {
+                character,
+                position,
+            } => write!(f, "invalid character {character:?} at {position}"),
+            ParseError::InvalidPercentEncoding { position } => {
+                write!(f, "invalid percent-encoding at {position}")
+            }
+            ParseError::EmptyHost => f.write_str("URL has an empty host"),
+            ParseError::InvalidPort(port) => write!(f, "invalid port {port:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// With the `serde` feature, absent parts are left out of the serialized form, e.g.
+/// `{"method": "GET", "path": "/users", "query": {"page": ["2"]}, "version": "HTTP/1.1"}`.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct Request {
     pub method: String,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub scheme: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub host: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub port: Option<u16>,
     pub path: String,
-    pub query: HashMap<String, Option<String>>,
+    /// Values per parameter, with parameters in the order they first appeared.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "IndexMap::is_empty"))]
+    pub query: IndexMap<String, Vec<Option<String>>>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub fragment: Option<String>,
-    pub headers: HashMap<String, String>,
-    pub body: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Headers::is_empty"))]
+    pub headers: Headers,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Body::is_empty"))]
+    pub body: Body,
+    #[cfg_attr(feature = "serde", serde(rename = "version"))]
+    pub http_version: HttpVersion,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Connection::is_empty"))]
+    pub connection: Connection,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub(crate) cache: content::BodyCache,
 }
 
 impl Request {
+    /// Parses a raw HTTP/1.x request — request line, headers and a body honouring
+    /// `Content-Length` — as captured from the wire or written in an `.http` file.
+    /// Applies the default [`ParseLimits`].
+    pub fn parse_http(bytes: &[u8]) -> Result<Request, RawRequestError> {
+        raw::parse(bytes, &ParseLimits::default())
+    }
+
+    /// [`Request::parse_http`] with custom limits, e.g. stricter ones for untrusted input.
+    pub fn parse_http_with(bytes: &[u8], limits: &ParseLimits) -> Result<Request, RawRequestError> {
+        raw::parse(bytes, limits)
+    }
+
+    /// Strict counterpart of `From<&str>`: rejects stray delimiters, empty parts and
+    /// characters that can't appear in a request target.
+    pub fn parse(input: &str) -> Result<Request, ParseError> {
+        let input = input.trim();
+        let target = if is_absolute(input) {
+            let (_, rest) = input.split_once("://").unwrap_or_default();
+            let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+            validate_authority(&rest[..end])?;
+            &rest[end..]
+        } else {
+            input
+        };
+        validate_target(target)?;
+        Ok(Request::from(input))
+    }
+
+    /// Builds the request a `curl` command line would send, e.g. one pasted from a bug
+    /// report. Understands `-X`, `-H`, the `--data` family, `--json`, `-u`, `-b` and `-G`;
+    /// options that don't shape the request are skipped.
+    pub fn from_curl(command: &str) -> Result<Request, CurlError> {
+        curl::parse(command)
+    }
+
+    /// Renders a copy-pastable `curl` command that sends this request. Without a host the
+    /// URL falls back to the `Host` header, then to `localhost`.
+    pub fn to_curl(&self) -> String {
+        curl::render(self)
+    }
+
+    /// Writes the request as raw HTTP/1.1, ready to replay with other tools; see
+    /// [`Request::to_http_bytes`]. Binary bodies are converted lossily.
+    pub fn to_http_string(&self) -> String {
+        String::from_utf8_lossy(&self.to_http_bytes()).into_owned()
+    }
+
+    /// Writes the request line, headers (adding `Host` and `Content-Length` when missing)
+    /// and body. HTTP/2 and HTTP/3 requests are written as HTTP/1.1.
+    pub fn to_http_bytes(&self) -> Vec<u8> {
+        raw::render(self)
+    }
+
+    /// Like [`Request::uri`] but always absolute: without a host the authority comes from
+    /// the `Host` header, then defaults to `localhost`.
+    pub(crate) fn absolute_uri(&self) -> String {
+        match (&self.host, self.header("Host")) {
+            (Some(_), _) => self.uri(),
+            (None, authority) => {
+                let scheme = self.scheme.as_deref().unwrap_or("http");
+                let authority = authority.map_or("localhost", String::as_str);
+                format!("{scheme}://{authority}{}", self.uri())
+            }
+        }
+    }
+
+    /// The target as sent on the wire: absolute-form when the host is known, origin-form
+    /// otherwise. The fragment is never sent.
+    pub(crate) fn uri(&self) -> String {
+        let mut uri = String::new();
+        if let Some(host) = &self.host {
+            let scheme = self.scheme.as_deref().unwrap_or("http");
+            uri.push_str(&format!("{scheme}://{host}"));
+            if let Some(port) = self.port {
+                uri.push_str(&format!(":{port}"));
+            }
+        }
+        uri.push_str(&self.target());
+        uri
+    }
+
+    /// The path and query in origin-form, without the fragment.
+    pub(crate) fn target(&self) -> String {
+        let mut uri = self.path.clone();
+
+        let query: Vec<String> = self
+            .query
+            .iter()
+            .flat_map(|(key, values)| values.iter().map(move |value| (key, value)))
+            .map(|(key, value)| match value {
+                Some(value) => format!("{key}={value}"),
+                None => key.clone(),
+            })
+            .collect();
+        if !query.is_empty() {
+            uri.push('?');
+            uri.push_str(&query.join("&"));
+        }
+        uri
+    }
+
+    /// Collapses repeated slashes, resolves `.` and `..` segments and drops a trailing
+    /// slash, so `/a//b/../c/` becomes `/a/c`. `..` never climbs above the root.
+    pub fn normalize_path(&mut self) {
+        if self.path == "*" {
+            return;
+        }
+        let mut segments: Vec<&str> = Vec::new();
+        for segment in self.path.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment),
+            }
+        }
+        self.path = format!("/{}", segments.join("/"));
+    }
+
+    pub fn with_normalized_path(mut self) -> Self {
+        self.normalize_path();
+        self
+    }
+
     pub fn set_path<S: Into<String>>(&mut self, path: S) {
         self.path = path.into();
     }
@@ -19,20 +256,123 @@ impl Request {
         self.method = method.into();
     }
 
+    pub fn set_scheme<S: Into<String>>(&mut self, scheme: S) {
+        self.scheme = Some(scheme.into());
+    }
+
+    pub fn set_host<S: Into<String>>(&mut self, host: S) {
+        self.host = Some(host.into());
+    }
+
+    pub fn set_port(&mut self, port: u16) {
+        self.port = Some(port);
+    }
+
+    pub fn set_http_version(&mut self, version: HttpVersion) {
+        self.http_version = version;
+    }
+
     pub fn set_fragment<S: Into<String>>(&mut self, fragment: S) {
         self.fragment = Some(fragment.into());
     }
 
-    pub fn set_body<S: Into<String>>(&mut self, body: S) {
-        self.body = Some(body.into());
+    pub fn set_body<B: Into<Body>>(&mut self, body: B) {
+        self.body = body.into();
     }
 
     pub fn set_query<K: Into<String>, V: Into<String>>(&mut self, key: K, value: Option<V>) {
-        self.query.insert(key.into(), value.map(|s| s.into()));
+        self.query.insert(key.into(), vec![value.map(|s| s.into())]);
+    }
+
+    /// Adds another value for `key`, keeping the ones already present.
+    pub fn append_query<K: Into<String>, V: Into<String>>(&mut self, key: K, value: Option<V>) {
+        self.query
+            .entry(key.into())
+            .or_default()
+            .push(value.map(|s| s.into()));
     }
 
+    /// Replaces every value of the header, whatever the case of its name.
+    /// HTTP/2 pseudo-headers such as `:authority` set the matching fields instead.
     pub fn set_header<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
-        self.headers.insert(key.into(), value.into());
+        let (key, value) = (key.into(), value.into());
+        if !self.set_pseudo_header(&key, &value) {
+            self.headers.insert(key, value);
+        }
+    }
+
+    /// Adds another value for the header, keeping the ones already present. HTTP/2
+    /// pseudo-headers such as `:authority` set the matching fields instead.
+    pub fn append_header<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        let (key, value) = (key.into(), value.into());
+        if !self.set_pseudo_header(&key, &value) {
+            self.headers.append(key, value);
+        }
+    }
+
+    /// Parses every `Cookie` header into name/value pairs, in the order they were sent.
+    pub fn cookies(&self) -> Vec<(&str, &str)> {
+        self.header_values("Cookie")
+            .iter()
+            .flat_map(|header| header.split(';'))
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, value)| (name.trim(), value.trim()))
+            .collect()
+    }
+
+    /// The value of the first cookie called `name`.
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies()
+            .into_iter()
+            .find(|(k, _)| *k == name)
+            .map(|(_, v)| v)
+    }
+
+    /// Sets a cookie, replacing one of the same name, and rewrites the `Cookie` header.
+    pub fn set_cookie<K: Into<String>, V: Into<String>>(&mut self, name: K, value: V) {
+        let (name, value) = (name.into(), value.into());
+        let mut cookies: Vec<(&str, &str)> = self.cookies();
+        match cookies.iter_mut().find(|(k, _)| *k == name) {
+            Some(cookie) => cookie.1 = &value,
+            None => cookies.push((&name, &value)),
+        }
+        let header = cookies
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+        self.set_header("Cookie", header);
+    }
+
+    /// Returns the target host, falling back to the `Host` header for origin-form requests.
+    pub fn effective_host(&self) -> Option<&str> {
+        self.host
+            .as_deref()
+            .or_else(|| Some(split_host_port(self.header("Host")?).0))
+    }
+
+    /// Returns the target port: the explicit one, then the `Host` header's, then the scheme default.
+    pub fn effective_port(&self) -> Option<u16> {
+        self.port
+            .or_else(|| split_host_port(self.header("Host")?).1)
+            .or_else(
+                || match self.scheme.as_deref()?.to_ascii_lowercase().as_str() {
+                    "http" | "ws" => Some(80),
+                    "https" | "wss" => Some(443),
+                    _ => None,
+                },
+            )
+    }
+
+    /// Looks up the first value of a header by name, ignoring ASCII case as required by
+    /// RFC 7230.
+    pub fn header(&self, name: &str) -> Option<&String> {
+        self.headers.get(name)
+    }
+
+    /// Every value of a header, in the order they were added; empty when it is absent.
+    pub fn header_values(&self, name: &str) -> &[String] {
+        self.headers.get_all(name)
     }
 
     pub fn with_path<S: Into<String>>(mut self, path: S) -> Self {
@@ -45,16 +385,63 @@ impl Request {
         self
     }
 
+    pub fn with_scheme<S: Into<String>>(mut self, scheme: S) -> Self {
+        self.set_scheme(scheme);
+        self
+    }
+
+    pub fn with_host<S: Into<String>>(mut self, host: S) -> Self {
+        self.set_host(host);
+        self
+    }
+
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.set_port(port);
+        self
+    }
+
+    pub fn with_http_version(mut self, version: HttpVersion) -> Self {
+        self.set_http_version(version);
+        self
+    }
+
     pub fn with_fragment<S: Into<String>>(mut self, fragment: S) -> Self {
         self.set_fragment(fragment);
         self
     }
 
-    pub fn with_body<S: Into<String>>(mut self, body: S) -> Self {
+    pub fn with_body<B: Into<Body>>(mut self, body: B) -> Self {
         self.set_body(body);
         self
     }
 
+    /// Sets `value` as a JSON body along with `Content-Type: application/json`.
+    ///
+    /// # Panics
+    ///
+    /// When `value` can't be represented as JSON, e.g. a map with non-string keys.
+    #[cfg(feature = "json")]
+    pub fn with_json<T: serde::Serialize + ?Sized>(self, value: &T) -> Self {
+        let json = serde_json::to_value(value).expect("value should serialize to JSON");
+        self.with_header("Content-Type", "application/json")
+            .with_body(json)
+    }
+
+    /// Sets `fields` as a form-encoded body along with
+    /// `Content-Type: application/x-www-form-urlencoded`.
+    pub fn with_form<K: AsRef<str>, V: AsRef<str>>(self, fields: &[(K, V)]) -> Self {
+        let body = form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(fields)
+            .finish();
+        self.with_header("Content-Type", "application/x-www-form-urlencoded")
+            .with_body(body)
+    }
+
+    pub fn with_connection(mut self, connection: Connection) -> Self {
+        self.connection = connection;
+        self
+    }
+
     pub fn with_query<K: Into<String>, V: Into<String>>(
         mut self,
         key: K,
@@ -64,33 +451,199 @@ impl Request {
         self
     }
 
+    pub fn with_appended_query<K: Into<String>, V: Into<String>>(
+        mut self,
+        key: K,
+        value: Option<V>,
+    ) -> Self {
+        self.append_query(key, value);
+        self
+    }
+
     pub fn with_header<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
         self.set_header(key, value);
         self
     }
+
+    pub fn with_cookie<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.set_cookie(name, value);
+        self
+    }
+
+    pub fn with_appended_header<K: Into<String>, V: Into<String>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Self {
+        self.append_header(key, value);
+        self
+    }
 }
 
 impl Default for Request {
     fn default() -> Self {
         Self {
             method: String::from("GET"),
+            scheme: Default::default(),
+            host: Default::default(),
+            port: Default::default(),
             path: String::from("/"),
             query: Default::default(),
             fragment: Default::default(),
             headers: Default::default(),
             body: Default::default(),
+            http_version: Default::default(),
+            connection: Default::default(),
+            cache: Default::default(),
         }
     }
 }
 
+/// Splits an authority such as `example.com:8080` or `[::1]:3000` into host and port.
+fn split_host_port(authority: &str) -> (&str, Option<u16>) {
+    let authority = authority.trim();
+    let port_start = match authority.rfind(']') {
+        Some(bracket) => authority[bracket..].find(':').map(|idx| bracket + idx),
+        None => authority.rfind(':'),
+    };
+
+    match port_start.map(|idx| (&authority[..idx], authority[idx + 1..].parse().ok())) {
+        Some((host, Some(port))) => (host, Some(port)),
+        _ => (authority, None),
+    }
+}
+
+type Authority<'a> = (Option<&'a str>, &'a str, Option<u16>);
+
+/// Splits a URL into its authority (scheme, host, port) and the path onwards.
+pub(crate) fn split_url(url: &str) -> (Option<Authority<'_>>, &str) {
+    if url.starts_with('/') {
+        return (None, url);
+    }
+
+    let (scheme, rest) = match url.split_once("://") {
+        Some((scheme, rest)) => (Some(scheme), rest),
+        None => (None, url),
+    };
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    let (authority, target) = rest.split_at(end);
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host, port.parse().ok()),
+        _ => (authority, None),
+    };
+    (Some((scheme, host, port)), target)
+}
+
 fn split_str_by<'a>(input: &'a str, delimiter: &str) -> (&'a str, Option<&'a str>) {
     input
         .split_once(delimiter)
         .map(|(p, f)| (p, if f.is_empty() { None } else { Some(f) }))
         .unwrap_or((input, None))
 }
+fn validate_authority(authority: &str) -> Result<(), ParseError> {
+    let host_port = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let (host, port) = match host_port.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host, Some(port)),
+        _ => (host_port, None),
+    };
+    if host.is_empty() {
+        return Err(ParseError::EmptyHost);
+    }
+    match port {
+        Some(port) if port.parse::<u16>().is_err() => Err(ParseError::InvalidPort(port.into())),
+        _ => Ok(()),
+    }
+}
+
+fn validate_target(target: &str) -> Result<(), ParseError> {
+    let bytes = target.as_bytes();
+    let mut query_start = None;
+    let mut fragment_start = None;
+
+    for (position, character) in target.char_indices() {
+        match character {
+            '?' if query_start.is_none() && fragment_start.is_none() => {
+                query_start = Some(position + 1)
+            }
+            '#' if fragment_start.is_none() => fragment_start = Some(position + 1),
+            '%' => {
+                let hex = bytes.get(position + 1..position + 3);
+                if !hex.is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) {
+                    return Err(ParseError::InvalidPercentEncoding { position });
+                }
+            }
+            character
+                if character == '#' || character.is_whitespace() || character.is_control() =>
+            {
+                return Err(ParseError::InvalidCharacter {
+                    character,
+                    position,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if fragment_start == Some(target.len()) {
+        return Err(ParseError::EmptyFragment);
+    }
+    if let Some(start) = query_start {
+        let end = fragment_start.map_or(target.len(), |idx| idx - 1);
+        if start == end {
+            return Err(ParseError::EmptyQuery);
+        }
+        let mut position = start;
+        for pair in target[start..end].split('&') {
+            if pair.is_empty() || pair.starts_with('=') {
+                return Err(ParseError::EmptyQueryKey { position });
+            }
+            position += pair.len() + 1;
+        }
+    }
+
+    Ok(())
+}
+
+impl std::str::FromStr for Request {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Request::parse(s)
+    }
+}
+
+/// Whether `url` starts with an RFC 3986 scheme followed by `://`.
+fn is_absolute(url: &str) -> bool {
+    url.split_once("://").is_some_and(|(scheme, _)| {
+        scheme.starts_with(|ch: char| ch.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || "+-.".contains(ch))
+    })
+}
+
+/// Accepts an origin-form target such as `/users?page=2#top`, or an absolute URL such as
+/// `https://api.example.com:8443/v1/x?y=1` which also fills scheme, host and port.
 impl From<&str> for Request {
     fn from(value: &str) -> Self {
+        let value = value.trim();
+        if is_absolute(value) {
+            let (authority, target) = split_url(value);
+            let mut request = Self::from(target);
+            if let Some((scheme, host, port)) = authority {
+                request.scheme = scheme.map(String::from);
+                request.host = Some(host.into());
+                request.port = port;
+            }
+            return request;
+        }
+
         let (path, fragment) = split_str_by(value.trim().trim_start_matches("/"), "#");
         let (path, query) = split_str_by(path, "?");
         let mut request = Self {
@@ -100,26 +653,35 @@ impl From<&str> for Request {
         };
 
         if let Some(query) = query {
-            request.query = query
-                .split("&")
-                .map(|s| split_str_by(s, "="))
-                .map(|(k, v)| (k.to_string(), v.map(String::from)))
-                .collect();
+            for (key, value) in query.split("&").map(|s| split_str_by(s, "=")) {
+                request.append_query(key, value);
+            }
         }
 
         request
     }
 }
 
+/// Values masked by the current [`Redaction`](crate::Redaction) are shown as `[REDACTED]`.
 impl std::fmt::Display for Request {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(&format!("[{} {}", self.method.to_uppercase(), self.path));
+        let request = self.redacted();
+        f.write_str(&format!("[{} ", request.method.to_uppercase()));
+        if let Some(host) = &request.host {
+            let scheme = request.scheme.as_deref().unwrap_or("http");
+            f.write_str(&format!("{scheme}://{host}"));
+            if let Some(port) = request.port {
+                f.write_str(&format!(":{port}"));
+            }
+        }
+        f.write_str(&request.path);
 
-        if !self.query.is_empty() {
+        if !request.query.is_empty() {
             f.write_str("?");
-            let query = self
+            let query = request
                 .query
                 .iter()
+                .flat_map(|(k, values)| values.iter().map(move |v| (k, v)))
                 .map(|(k, v)| {
                     if let Some(v) = v {
                         format!("{}={}", k, v)
@@ -132,14 +694,14 @@ impl std::fmt::Display for Request {
             f.write_str(&query);
         }
 
-        if let Some(fragment) = &self.fragment {
+        if let Some(fragment) = &request.fragment {
             f.write_str(&format!("#{fragment}"));
         }
 
-        if !self.headers.is_empty() {
+        if !request.headers.is_empty() {
             f.write_str(" | with headers {");
 
-            let headers = self
+            let headers = request
                 .headers
                 .iter()
                 .map(|(k, v)| format!("{k:?} = {v:?}"))
@@ -150,8 +712,11 @@ impl std::fmt::Display for Request {
             f.write_str("}");
         }
 
-        if let Some(body) = &self.body {
-            f.write_str(&format!(" | with body {body:?}"));
+        if let Some(body) = request.body.bytes() {
+            match std::str::from_utf8(&body) {
+                Ok(text) => f.write_str(&format!(" | with body {text:?}")),
+                Err(_) => f.write_str(&format!(" | with {} bytes of binary body", body.len())),
+            };
         }
 
         f.write_str("]");
@@ -168,9 +733,13 @@ mod test {
     #[case("", Request::default())]
     #[case("/", Request::default())]
     #[case("/some/path", Request { path: "/some/path".into(), ..Default::default() })]
-    #[case("/path?key=value", Request { path: "/path".into(), query: [("key".into(), Some("value".into()))].into(), ..Default::default() })]
-    #[case("/path?key=value#some-hash", Request { path: "/path".into(), query: [("key".into(), Some("value".into()))].into(), fragment: Some("some-hash".into()), ..Default::default() })]
-    #[case("?key=value&empty_key", Request { query: [("key".into(), Some("value".into())), ("empty_key".into(), None)].into(), ..Default::default() })]
+    #[case("/path?key=value", Request { path: "/path".into(), query: [("key".into(), vec![Some("value".into())])].into(), ..Default::default() })]
+    #[case("/path?key=value#some-hash", Request { path: "/path".into(), query: [("key".into(), vec![Some("value".into())])].into(), fragment: Some("some-hash".into()), ..Default::default() })]
+    #[case("?key=value&empty_key", Request { query: [("key".into(), vec![Some("value".into())]), ("empty_key".into(), vec![None])].into(), ..Default::default() })]
+    #[case("?tag=a&tag=b&tag", Request { query: [("tag".into(), vec![Some("a".into()), Some("b".into()), None])].into(), ..Default::default() })]
+    #[case("https://api.example.com:8443/v1/x?y=1", Request { scheme: Some("https".into()), host: Some("api.example.com".into()), port: Some(8443), path: "/v1/x".into(), query: [("y".into(), vec![Some("1".into())])].into(), ..Default::default() })]
+    #[case("http://[::1]:3000", Request { scheme: Some("http".into()), host: Some("[::1]".into()), port: Some(3000), ..Default::default() })]
+    #[case("ws://chat.test#room", Request { scheme: Some("ws".into()), host: Some("chat.test".into()), fragment: Some("room".into()), ..Default::default() })]
     fn from_str(#[case] uri: &str, #[case] request: Request) {
         assert_eq!(
             Request::from(uri),
@@ -178,4 +747,231 @@ mod test {
             "The request with {uri:?} should be parsed into {request:?}"
         );
     }
+
+    #[rstest::rstest]
+    #[case::full(
+        "/users/1?tab=posts&page=2#top",
+        Request::from("/users/1?tab=posts&page=2#top")
+    )]
+    #[case::flag_and_encoding("/search?q=a%20b&raw", Request::from("/search?q=a%20b&raw"))]
+    #[case::question_mark_in_query("/a?next=/b?c", Request::from("/a?next=/b?c"))]
+    #[case::empty("", Request::default())]
+    #[case::absolute("https://api.example.com:8443/v1/x?y=1", Request::from("/v1/x?y=1").with_scheme("https").with_host("api.example.com").with_port(8443))]
+    fn parse_valid(#[case] input: &str, #[case] request: Request) {
+        assert_eq!(Request::parse(input), Ok(request));
+        assert!(input.parse::<Request>().is_ok());
+    }
+
+    #[rstest::rstest]
+    #[case::empty_query("/path?", ParseError::EmptyQuery)]
+    #[case::empty_query_before_fragment("/path?#top", ParseError::EmptyQuery)]
+    #[case::empty_fragment("/path#", ParseError::EmptyFragment)]
+    #[case::stray_ampersand("/path?a=1&&b=2", ParseError::EmptyQueryKey { position: 10 })]
+    #[case::missing_name("/path?=1", ParseError::EmptyQueryKey { position: 6 })]
+    #[case::second_hash("/path#a#b", ParseError::InvalidCharacter { character: '#', position: 7 })]
+    #[case::space("/a b", ParseError::InvalidCharacter { character: ' ', position: 2 })]
+    #[case::percent("/a%2", ParseError::InvalidPercentEncoding { position: 2 })]
+    #[case::empty_host("http:///a", ParseError::EmptyHost)]
+    #[case::port("http://a.test:99999/", ParseError::InvalidPort("99999".into()))]
+    fn parse_invalid(#[case] input: &str, #[case] error: ParseError) {
+        assert_eq!(
+            Request::parse(input),
+            Err(error.clone()),
+            "Parsing {input:?} should fail with {error:?}"
+        );
+    }
+
+    #[test]
+    fn query_keeps_order_and_repeats() {
+        let request = Request::from("/search?z=1&a=2&z=3&m");
+
+        assert_eq!(
+            request.query.keys().collect::<Vec<_>>(),
+            vec!["z", "a", "m"]
+        );
+        assert_eq!(request.to_string(), "[GET /search?z=1&z=3&a=2&m]");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_round_trip() {
+        let request = Request::from("/users?tag=a&tag=b&flag")
+            .with_method("POST")
+            .with_host("api.example.com")
+            .with_header("Content-Type", "application/json")
+            .with_header("Via", "1.1 a")
+            .with_appended_header("Via", "1.1 b")
+            .with_body(r#"{"name":"Bob"}"#);
+        let serialized = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(
+            serialized,
+            serde_json::json!({
+                "method": "POST",
+                "host": "api.example.com",
+                "path": "/users",
+                "query": {"tag": ["a", "b"], "flag": [null]},
+                "headers": {"Content-Type": "application/json", "Via": ["1.1 a", "1.1 b"]},
+                "body": r#"{"name":"Bob"}"#,
+                "version": "HTTP/1.1",
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<Request>(serialized).unwrap(),
+            request
+        );
+        assert_eq!(
+            serde_json::from_value::<Request>(serde_json::json!({"path": "/health"})).unwrap(),
+            Request::from("/health")
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_connection() {
+        let request = Request::from("/").with_connection(Connection {
+            remote_addr: Some("10.0.0.7:51000".parse().unwrap()),
+            tls: Some(TlsInfo {
+                server_name: Some("api.example.com".into()),
+                alpn_protocol: Some("h2".into()),
+            }),
+            ..Default::default()
+        });
+        let serialized = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(
+            serialized["connection"],
+            serde_json::json!({
+                "remote_addr": "10.0.0.7:51000",
+                "tls": {"server_name": "api.example.com", "alpn_protocol": "h2"},
+            })
+        );
+        assert_eq!(
+            serde_json::from_value::<Request>(serialized).unwrap(),
+            request
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::dot_segments("/a/b/../c", "/a/c")]
+    #[case::current("/a/./b", "/a/b")]
+    #[case::duplicate_slashes("//a///b", "/a/b")]
+    #[case::trailing_slash("/a/b/", "/a/b")]
+    #[case::above_root("/../../a", "/a")]
+    #[case::root("/", "/")]
+    #[case::asterisk("*", "*")]
+    fn normalize_path(#[case] path: &str, #[case] expected: &str) {
+        assert_eq!(
+            Request::default()
+                .with_path(path)
+                .with_normalized_path()
+                .path,
+            expected
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::same_case("Content-Type", "Content-Type")]
+    #[case::lower_case("Content-Type", "content-type")]
+    #[case::upper_case("x-request-id", "X-REQUEST-ID")]
+    fn header_case_insensitive(#[case] stored: &str, #[case] lookup: &str) {
+        let request = Request::default().with_header(stored, "value");
+
+        assert_eq!(
+            request.header(lookup),
+            Some(&"value".to_string()),
+            "Header {stored:?} should be found when looked up as {lookup:?}"
+        );
+    }
+
+    #[test]
+    fn repeated_header_keeps_every_value() {
+        let request = Request::default()
+            .with_header("Via", "1.1 a")
+            .with_appended_header("via", "1.1 b");
+
+        assert_eq!(request.header("VIA"), Some(&"1.1 a".to_string()));
+        assert_eq!(request.header_values("Via"), ["1.1 a", "1.1 b"]);
+        assert!(request.header_values("Set-Cookie").is_empty());
+    }
+
+    #[test]
+    fn cookies() {
+        let request = Request::default()
+            .with_header("Cookie", "theme=dark; session=abc")
+            .with_appended_header("cookie", "lang=en");
+
+        assert_eq!(
+            request.cookies(),
+            vec![("theme", "dark"), ("session", "abc"), ("lang", "en")]
+        );
+        assert_eq!(request.cookie("lang"), Some("en"));
+        assert_eq!(request.cookie("missing"), None);
+    }
+
+    #[test]
+    fn set_cookie_maintains_header() {
+        let request = Request::default()
+            .with_cookie("theme", "dark")
+            .with_cookie("session", "abc")
+            .with_cookie("theme", "light");
+
+        assert_eq!(
+            request.header_values("Cookie"),
+            ["theme=light; session=abc"]
+        );
+    }
+
+    #[rstest::rstest]
+    #[case::explicit(Request::default().with_scheme("https").with_host("api.example.com").with_port(8443), Some("api.example.com"), Some(8443))]
+    #[case::scheme_default(Request::default().with_scheme("https").with_host("api.example.com"), Some("api.example.com"), Some(443))]
+    #[case::host_header(Request::default().with_header("Host", "example.com:8080"), Some("example.com"), Some(8080))]
+    #[case::host_header_without_port(Request::default().with_header("host", "example.com"), Some("example.com"), None)]
+    #[case::ipv6_host_header(Request::default().with_header("Host", "[::1]:3000"), Some("[::1]"), Some(3000))]
+    #[case::field_over_header(Request::default().with_host("a.test").with_header("Host", "b.test"), Some("a.test"), None)]
+    #[case::unknown(Request::default(), None, None)]
+    fn effective_authority(
+        #[case] request: Request,
+        #[case] host: Option<&str>,
+        #[case] port: Option<u16>,
+    ) {
+        assert_eq!(
+            (request.effective_host(), request.effective_port()),
+            (host, port),
+            "Request {request} should resolve host {host:?} and port {port:?}"
+        );
+    }
+
+    #[test]
+    fn set_header_replaces_differently_cased_name() {
+        let request = Request::default()
+            .with_header("content-type", "text/plain")
+            .with_header("Content-Type", "application/json");
+
+        assert_eq!(
+            request.headers,
+            Headers::from([("Content-Type", "application/json")]),
+            "Setting a header should replace an existing one regardless of name case"
+        );
+    }
+
+    #[test]
+    fn with_form_encodes_fields() {
+        let request = Request::default().with_form(&[("name", "Ann Lee"), ("tag", "a&b")]);
+
+        assert_eq!(
+            request.headers.content_type(),
+            Some("application/x-www-form-urlencoded")
+        );
+        assert_eq!(request.body, Body::from("name=Ann+Lee&tag=a%26b"));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn with_json_sets_body_and_content_type() {
+        let request = Request::default().with_json(&[("id", 1)]);
+
+        assert_eq!(request.headers.content_type(), Some("application/json"));
+        assert_eq!(request.body, Body::from(serde_json::json!([["id", 1]])));
+    }
 }
@@ -0,0 +1,176 @@
+use super::{HttpVersion, Request};
+use crate::body::Body;
+
+/// Why [`RequestBuilder::build`] rejected its input.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// The method isn't an RFC 9110 token, e.g. it is empty or contains a space.
+    InvalidMethod(String),
+    /// The path doesn't start with `/` (only `*` is accepted otherwise) or contains
+    /// whitespace.
+    InvalidPath(String),
+    /// The header name isn't an RFC 9110 token.
+    InvalidHeaderName(String),
+    /// The value of this header contains a line break or another control character.
+    InvalidHeaderValue(String),
+}
+
+impl std::fmt::Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuildError::InvalidMethod(method) => write!(f, "invalid method {method:?}"),
+            BuildError::InvalidPath(path) => write!(f, "invalid path {path:?}"),
+            BuildError::InvalidHeaderName(name) => write!(f, "invalid header name {name:?}"),
+            BuildError::InvalidHeaderValue(name) => {
+                write!(f, "invalid value for header {name:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for BuildError {}
+
+/// Builds a [`Request`] like the `with_*` setters do, but checks the method, path and
+/// headers in [`RequestBuilder::build`].
+#[derive(Debug, Clone, Default)]
+pub struct RequestBuilder {
+    request: Request,
+}
+
+impl Request {
+    pub fn builder() -> RequestBuilder {
+        RequestBuilder::default()
+    }
+}
+
+impl RequestBuilder {
+    pub fn method<S: Into<String>>(mut self, method: S) -> Self {
+        self.request.set_method(method);
+        self
+    }
+
+    pub fn scheme<S: Into<String>>(mut self, scheme: S) -> Self {
+        self.request.set_scheme(scheme);
+        self
+    }
+
+    pub fn host<S: Into<String>>(mut self, host: S) -> Self {
+        self.request.set_host(host);
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.request.set_port(port);
+        self
+    }
+
+    pub fn version(mut self, version: HttpVersion) -> Self {
+        self.request.set_http_version(version);
+        self
+    }
+
+    pub fn path<S: Into<String>>(mut self, path: S) -> Self {
+        self.request.set_path(path);
+        self
+    }
+
+    /// Adds a query value, keeping earlier values of the same key.
+    pub fn query<K: Into<String>, V: Into<String>>(mut self, key: K, value: Option<V>) -> Self {
+        self.request.append_query(key, value);
+        self
+    }
+
+    pub fn fragment<S: Into<String>>(mut self, fragment: S) -> Self {
+        self.request.set_fragment(fragment);
+        self
+    }
+
+    /// Adds a header value, keeping earlier values of the same name.
+    pub fn header<K: Into<String>, V: Into<String>>(mut self, name: K, value: V) -> Self {
+        self.request.append_header(name, value);
+        self
+    }
+
+    pub fn body<B: Into<Body>>(mut self, body: B) -> Self {
+        self.request.set_body(body);
+        self
+    }
+
+    pub fn build(self) -> Result<Request, BuildError> {
+        let request = self.request;
+        if !is_token(&request.method) {
+            return Err(BuildError::InvalidMethod(request.method));
+        }
+        let path_ok = request.path == "*"
+            || (request.path.starts_with('/') && !request.path.contains(char::is_whitespace));
+        if !path_ok {
+            return Err(BuildError::InvalidPath(request.path));
+        }
+        for (name, value) in request.headers.iter() {
+            if !is_token(name) {
+                return Err(BuildError::InvalidHeaderName(name.clone()));
+            }
+            if value.chars().any(|ch| ch.is_control() && ch != '\t') {
+                return Err(BuildError::InvalidHeaderValue(name.clone()));
+            }
+        }
+        Ok(request)
+    }
+}
+
+/// An RFC 9110 token: one or more visible ASCII characters other than delimiters.
+fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn builds_valid_request() {
+        let request = Request::builder()
+            .method("POST")
+            .scheme("https")
+            .host("api.example.com")
+            .path("/users")
+            .query("tag", Some("a"))
+            .query("tag", Some("b"))
+            .header("Content-Type", "application/json")
+            .body("{}")
+            .build();
+
+        assert_eq!(
+            request,
+            Ok(Request::from("/users?tag=a&tag=b")
+                .with_method("POST")
+                .with_scheme("https")
+                .with_host("api.example.com")
+                .with_header("Content-Type", "application/json")
+                .with_body("{}"))
+        );
+    }
+
+    #[rstest]
+    #[case::empty_method(Request::builder().method(""), BuildError::InvalidMethod("".into()))]
+    #[case::method_space(Request::builder().method("GET X"), BuildError::InvalidMethod("GET X".into()))]
+    #[case::relative_path(Request::builder().path("users"), BuildError::InvalidPath("users".into()))]
+    #[case::path_space(Request::builder().path("/a b"), BuildError::InvalidPath("/a b".into()))]
+    #[case::header_name(Request::builder().header("X Key", "v"), BuildError::InvalidHeaderName("X Key".into()))]
+    #[case::header_colon(Request::builder().header("X:Key", "v"), BuildError::InvalidHeaderName("X:Key".into()))]
+    #[case::header_value(Request::builder().header("X-Key", "a\r\nInjected: 1"), BuildError::InvalidHeaderValue("X-Key".into()))]
+    fn rejects(#[case] builder: RequestBuilder, #[case] error: BuildError) {
+        assert_eq!(builder.build(), Err(error));
+    }
+
+    #[test]
+    fn accepts_asterisk_form() {
+        let request = Request::builder().method("OPTIONS").path("*").build();
+
+        assert_eq!(request.map(|r| r.path), Ok("*".to_string()));
+    }
+}
@@ -0,0 +1,46 @@
+use std::net::SocketAddr;
+use std::time::SystemTime;
+
+/// Where and when a request arrived. Left empty for requests built by hand or parsed from
+/// text; filled by integrations that see the connection.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Connection {
+    /// The peer that sent the request.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub remote_addr: Option<SocketAddr>,
+    /// The local address the request was received on.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub local_addr: Option<SocketAddr>,
+    /// Present when the request came over TLS.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub tls: Option<TlsInfo>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub received_at: Option<SystemTime>,
+}
+
+impl Connection {
+    pub fn is_empty(&self) -> bool {
+        self == &Connection::default()
+    }
+}
+
+/// What was negotiated during the TLS handshake.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct TlsInfo {
+    /// The SNI host name the client asked for.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub server_name: Option<String>,
+    /// The ALPN protocol, such as `h2` or `http/1.1`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub alpn_protocol: Option<String>,
+}
@@ -0,0 +1,234 @@
+use super::Request;
+use std::borrow::Cow;
+use std::sync::{Mutex, PoisonError};
+
+/// Why a body couldn't be read as the requested type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BodyError {
+    /// `Content-Type` names a media type other than the one asked for.
+    ContentType {
+        expected: &'static str,
+        actual: String,
+    },
+    /// `Content-Type` names a charset other than UTF-8.
+    Charset(String),
+    /// The body isn't valid UTF-8.
+    NotUtf8,
+    /// The body isn't valid JSON, or doesn't fit the target type.
+    #[cfg(feature = "json")]
+    Json(String),
+}
+
+impl std::fmt::Display for BodyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BodyError::ContentType { expected, actual } => {
+                write!(f, "expected content type `{expected}`, got `{actual}`")
+            }
+            BodyError::Charset(charset) => write!(f, "unsupported charset `{charset}`"),
+            BodyError::NotUtf8 => f.write_str("body is not valid UTF-8"),
+            #[cfg(feature = "json")]
+            BodyError::Json(error) => write!(f, "invalid JSON body: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for BodyError {}
+
+/// Parsed forms of the body, kept alongside the bytes they were parsed from so that a
+/// replaced body is parsed afresh.
+#[derive(Default)]
+pub(crate) struct BodyCache(Mutex<Option<Parsed>>);
+
+#[derive(Clone, Default)]
+struct Parsed {
+    source: Vec<u8>,
+    form: Option<Vec<(String, String)>>,
+    #[cfg(feature = "json")]
+    json: Option<Result<serde_json::Value, String>>,
+}
+
+impl BodyCache {
+    fn with<R>(&self, body: &[u8], read: impl FnOnce(&mut Parsed) -> R) -> R {
+        let mut slot = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let parsed = match slot.take() {
+            Some(parsed) if parsed.source == body => slot.insert(parsed),
+            _ => slot.insert(Parsed {
+                source: body.to_vec(),
+                ..Default::default()
+            }),
+        };
+        read(parsed)
+    }
+}
+
+impl Clone for BodyCache {
+    fn clone(&self) -> Self {
+        let slot = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        Self(Mutex::new(slot.clone()))
+    }
+}
+
+/// A cache never makes two requests differ.
+impl PartialEq for BodyCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl std::fmt::Debug for BodyCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BodyCache")
+    }
+}
+
+impl Request {
+    /// The body as text. Fails when `Content-Type` names a charset other than UTF-8 or the
+    /// body isn't valid UTF-8; an empty body is an empty string.
+    pub fn text(&self) -> Result<Cow<'_, str>, BodyError> {
+        let charset = self
+            .header("Content-Type")
+            .into_iter()
+            .flat_map(|value| value.split(';').skip(1))
+            .find_map(|param| {
+                let (name, value) = param.split_once('=')?;
+                name.trim()
+                    .eq_ignore_ascii_case("charset")
+                    .then(|| value.trim().trim_matches('"'))
+            });
+        if let Some(charset) = charset
+            .filter(|c| !c.eq_ignore_ascii_case("utf-8") && !c.eq_ignore_ascii_case("us-ascii"))
+        {
+            return Err(BodyError::Charset(charset.into()));
+        }
+        if self.body.is_empty() {
+            return Ok(Cow::Borrowed(""));
+        }
+        self.body.text().ok_or(BodyError::NotUtf8)
+    }
+
+    /// The body as `application/x-www-form-urlencoded` fields, in order. Parsed once per
+    /// body and cached.
+    pub fn form(&self) -> Result<Vec<(String, String)>, BodyError> {
+        self.expect_content_type("application/x-www-form-urlencoded", |media| {
+            media.eq_ignore_ascii_case("application/x-www-form-urlencoded")
+        })?;
+        let body = self.body.bytes().unwrap_or_default();
+        Ok(self.cache.with(&body, |parsed| {
+            parsed
+                .form
+                .get_or_insert_with(|| form_urlencoded::parse(&body).into_owned().collect())
+                .clone()
+        }))
+    }
+
+    /// The body deserialized from JSON. The parsed document is cached per body, so reading
+    /// it again, even as another type, skips parsing.
+    #[cfg(feature = "json")]
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, BodyError> {
+        use crate::body::Body;
+
+        self.expect_content_type("application/json", |media| {
+            let media = media.to_ascii_lowercase();
+            media == "application/json" || media.ends_with("+json")
+        })?;
+        let deserialize = |value: &serde_json::Value| {
+            T::deserialize(value).map_err(|error| BodyError::Json(error.to_string()))
+        };
+        if let Body::Json(value) = &self.body {
+            return deserialize(value);
+        }
+        let body = self.body.bytes().unwrap_or_default();
+        self.cache.with(&body, |parsed| {
+            let json = parsed
+                .json
+                .get_or_insert_with(|| serde_json::from_slice(&body).map_err(|e| e.to_string()));
+            match json {
+                Ok(value) => deserialize(value),
+                Err(error) => Err(BodyError::Json(error.clone())),
+            }
+        })
+    }
+
+    /// Accepts a request without `Content-Type`, or one whose media type passes `accepts`.
+    fn expect_content_type(
+        &self,
+        expected: &'static str,
+        accepts: impl FnOnce(&str) -> bool,
+    ) -> Result<(), BodyError> {
+        match self.headers.content_type() {
+            Some(media) if !accepts(media) => Err(BodyError::ContentType {
+                expected,
+                actual: media.into(),
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::plain(Request::default().with_body("hi"), Ok("hi"))]
+    #[case::empty(Request::default(), Ok(""))]
+    #[case::utf8(Request::default().with_header("Content-Type", "text/plain; charset=UTF-8").with_body("hi"), Ok("hi"))]
+    #[case::charset(Request::default().with_header("Content-Type", "text/plain; charset=latin1").with_body("hi"), Err(BodyError::Charset("latin1".into())))]
+    #[case::binary(Request::default().with_body([0xff]), Err(BodyError::NotUtf8))]
+    fn text(#[case] request: Request, #[case] expected: Result<&str, BodyError>) {
+        assert_eq!(request.text().as_deref().map_err(Clone::clone), expected);
+    }
+
+    #[rstest]
+    #[case::untyped(Request::default().with_body("a=1&b=x+y"), Ok(vec![("a".into(), "1".into()), ("b".into(), "x y".into())]))]
+    #[case::typed(Request::default().with_form(&[("a", "1")]), Ok(vec![("a".into(), "1".into())]))]
+    #[case::empty(Request::default(), Ok(vec![]))]
+    #[case::json(Request::default().with_header("Content-Type", "application/json").with_body("{}"), Err(BodyError::ContentType { expected: "application/x-www-form-urlencoded", actual: "application/json".into() }))]
+    fn form(#[case] request: Request, #[case] expected: Result<Vec<(String, String)>, BodyError>) {
+        assert_eq!(request.form(), expected);
+    }
+
+    #[test]
+    fn form_is_parsed_again_after_the_body_changes() {
+        let mut request = Request::default().with_body("a=1");
+        assert_eq!(request.form().unwrap(), vec![("a".into(), "1".into())]);
+
+        request.body = "a=2".into();
+
+        assert_eq!(
+            request.form().unwrap(),
+            vec![("a".into(), "2".into())],
+            "A replaced body should not be served from the cache"
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[rstest]
+    #[case::text(Request::default().with_body(r#"{"id": 1}"#), Ok(serde_json::json!({"id": 1})))]
+    #[case::value(Request::default().with_json(&serde_json::json!([1])), Ok(serde_json::json!([1])))]
+    #[case::suffix(Request::default().with_header("Content-Type", "application/problem+json").with_body("null"), Ok(serde_json::Value::Null))]
+    #[case::invalid(Request::default().with_body("{"), Err(BodyError::Json("EOF while parsing an object at line 1 column 1".into())))]
+    #[case::form(Request::default().with_form(&[("a", "1")]), Err(BodyError::ContentType { expected: "application/json", actual: "application/x-www-form-urlencoded".into() }))]
+    fn json(#[case] request: Request, #[case] expected: Result<serde_json::Value, BodyError>) {
+        assert_eq!(request.json::<serde_json::Value>(), expected);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_reads_cached_document_as_several_types() {
+        let request = Request::default().with_body(r#"{"ids": [1, 2]}"#);
+
+        assert_eq!(
+            request.json::<std::collections::HashMap<String, Vec<u8>>>(),
+            Ok([("ids".into(), vec![1, 2])].into())
+        );
+        assert_eq!(
+            request.json::<Vec<u8>>(),
+            Err(BodyError::Json(
+                "invalid type: map, expected a sequence".into()
+            ))
+        );
+    }
+}
@@ -0,0 +1,256 @@
+use super::Request;
+use crate::redaction::{REDACTED, Redaction};
+
+/// The part of a request a [`Difference`] is about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Part {
+    Method,
+    Scheme,
+    Host,
+    Port,
+    Version,
+    Path,
+    /// A query parameter, by name.
+    Query(String),
+    Fragment,
+    /// A header, by name as first spelled.
+    Header(String),
+    Body,
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::Method => f.write_str("method"),
+            Part::Scheme => f.write_str("scheme"),
+            Part::Host => f.write_str("host"),
+            Part::Port => f.write_str("port"),
+            Part::Version => f.write_str("version"),
+            Part::Path => f.write_str("path"),
+            Part::Query(key) => write!(f, "query `{key}`"),
+            Part::Fragment => f.write_str("fragment"),
+            Part::Header(name) => write!(f, "header `{name}`"),
+            Part::Body => f.write_str("body"),
+        }
+    }
+}
+
+/// One part that differs, with its value on either side; `None` where it is absent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    pub part: Part,
+    pub left: Option<String>,
+    pub right: Option<String>,
+}
+
+/// Values hidden by the current [`Redaction`] show as `[REDACTED]`.
+impl std::fmt::Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let policy = Redaction::current();
+        let hidden = match &self.part {
+            Part::Query(key) => policy.hides_param(key),
+            Part::Header(name) => policy.hides_header(name),
+            _ => false,
+        };
+        let show = |value: &Option<String>| match value {
+            None => "absent".to_string(),
+            Some(_) if hidden => REDACTED.to_string(),
+            Some(value) => format!("{value:?}"),
+        };
+        write!(
+            f,
+            "{}: {} != {}",
+            self.part,
+            show(&self.left),
+            show(&self.right)
+        )
+    }
+}
+
+/// Every part in which two requests differ, in request order: see [`Request::diff`].
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RequestDiff {
+    differences: Vec<Difference>,
+}
+
+impl RequestDiff {
+    pub fn differences(&self) -> &[Difference] {
+        &self.differences
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.differences.len()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Difference> {
+        self.differences.iter()
+    }
+
+    fn compare(&mut self, part: Part, left: Option<String>, right: Option<String>) {
+        if left != right {
+            self.differences.push(Difference { part, left, right });
+        }
+    }
+}
+
+/// One difference per line.
+impl std::fmt::Display for RequestDiff {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return f.write_str("requests are equal");
+        }
+        let lines: Vec<String> = self.iter().map(ToString::to_string).collect();
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+impl<'a> IntoIterator for &'a RequestDiff {
+    type Item = &'a Difference;
+    type IntoIter = std::slice::Iter<'a, Difference>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Request {
+    /// Compares this request (the left side) with `other`, part by part. Query parameters
+    /// and headers are compared by name, so their order doesn't matter. A parameter shows as
+    /// its `key=value` pairs joined with `&`, a bare `key` for a flag; repeated header values
+    /// are joined with `, `.
+    pub fn diff(&self, other: &Request) -> RequestDiff {
+        let mut diff = RequestDiff::default();
+        diff.compare(
+            Part::Method,
+            Some(self.method.to_uppercase()),
+            Some(other.method.to_uppercase()),
+        );
+        diff.compare(Part::Scheme, self.scheme.clone(), other.scheme.clone());
+        diff.compare(Part::Host, self.host.clone(), other.host.clone());
+        diff.compare(
+            Part::Port,
+            self.port.map(|p| p.to_string()),
+            other.port.map(|p| p.to_string()),
+        );
+        diff.compare(
+            Part::Version,
+            Some(self.http_version.to_string()),
+            Some(other.http_version.to_string()),
+        );
+        diff.compare(
+            Part::Path,
+            Some(self.path.clone()),
+            Some(other.path.clone()),
+        );
+
+        let mut keys: Vec<&String> = self.query.keys().collect();
+        keys.extend(
+            other
+                .query
+                .keys()
+                .filter(|key| !self.query.contains_key(*key)),
+        );
+        for key in keys {
+            let values = |request: &Request| {
+                request.query.get(key).map(|values| {
+                    let pairs: Vec<String> = values
+                        .iter()
+                        .map(|value| match value {
+                            Some(value) => format!("{key}={value}"),
+                            None => key.clone(),
+                        })
+                        .collect();
+                    pairs.join("&")
+                })
+            };
+            diff.compare(Part::Query(key.clone()), values(self), values(other));
+        }
+
+        diff.compare(
+            Part::Fragment,
+            self.fragment.clone(),
+            other.fragment.clone(),
+        );
+
+        let mut names: Vec<&String> = self.headers.names().collect();
+        names.extend(
+            other
+                .headers
+                .names()
+                .filter(|name| !self.headers.contains(name)),
+        );
+        for name in names {
+            let values = |request: &Request| match request.header_values(name) {
+                [] => None,
+                values => Some(values.join(", ")),
+            };
+            diff.compare(Part::Header(name.clone()), values(self), values(other));
+        }
+
+        let body = |request: &Request| {
+            let bytes = request.body.bytes()?;
+            Some(match std::str::from_utf8(&bytes) {
+                Ok(text) => text.to_string(),
+                Err(_) => format!("{} bytes of binary body", bytes.len()),
+            })
+        };
+        if self.body != other.body {
+            diff.compare(Part::Body, body(self), body(other));
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn difference(part: Part, left: Option<&str>, right: Option<&str>) -> Difference {
+        Difference {
+            part,
+            left: left.map(String::from),
+            right: right.map(String::from),
+        }
+    }
+
+    #[rstest]
+    #[case::equal(Request::from("/a?x=1").with_header("A", "1"), Request::from("/a?x=1").with_header("a", "1"), vec![])]
+    #[case::method(Request::default(), Request::default().with_method("post"), vec![difference(Part::Method, Some("GET"), Some("POST"))])]
+    #[case::path(Request::from("/a"), Request::from("/b"), vec![difference(Part::Path, Some("/a"), Some("/b"))])]
+    #[case::query(Request::from("/?x=1&y=2"), Request::from("/?y=2&x=3&z"), vec![difference(Part::Query("x".into()), Some("x=1"), Some("x=3")), difference(Part::Query("z".into()), None, Some("z"))])]
+    #[case::empty_value(Request::from("/?x"), Request::default().with_query("x", Some("")), vec![difference(Part::Query("x".into()), Some("x"), Some("x="))])]
+    #[case::repeated_flag(Request::from("/?tag=a&tag"), Request::from("/?tag=a"), vec![difference(Part::Query("tag".into()), Some("tag=a&tag"), Some("tag=a"))])]
+    #[case::header(Request::default().with_header("Accept", "a"), Request::default().with_header("Via", "v"), vec![difference(Part::Header("Accept".into()), Some("a"), None), difference(Part::Header("Via".into()), None, Some("v"))])]
+    #[case::body(Request::default().with_body("a"), Request::default(), vec![difference(Part::Body, Some("a"), None)])]
+    #[case::authority(Request::from("http://a.test/"), Request::from("http://a.test:81/"), vec![difference(Part::Port, None, Some("81"))])]
+    fn reports_differences(
+        #[case] left: Request,
+        #[case] right: Request,
+        #[case] expected: Vec<Difference>,
+    ) {
+        assert_eq!(
+            left.diff(&right).differences(),
+            expected,
+            "Comparing {left} with {right}"
+        );
+    }
+
+    #[test]
+    fn display() {
+        let left = Request::from("/users?page=1").with_header("Authorization", "Bearer a");
+        let right = Request::from("/users?page=2").with_header("Authorization", "Bearer b");
+
+        assert_eq!(
+            left.diff(&right).to_string(),
+            format!(
+                "query `page`: \"page=1\" != \"page=2\"\nheader `Authorization`: {REDACTED} != {REDACTED}"
+            )
+        );
+        assert_eq!(left.diff(&left).to_string(), "requests are equal");
+    }
+}
@@ -0,0 +1,95 @@
+use super::{Request, split_host_port};
+
+/// HTTP/2 pseudo-headers in the order they precede regular headers.
+const PSEUDO_HEADERS: [&str; 4] = [":method", ":scheme", ":authority", ":path"];
+
+impl Request {
+    /// Reads an HTTP/2 pseudo-header from the fields it maps to: `:method`, `:scheme`,
+    /// `:authority` (the host and port, or the `Host` header) and `:path` (path and query).
+    pub fn pseudo_header(&self, name: &str) -> Option<String> {
+        match name {
+            ":method" => Some(self.method.clone()),
+            ":scheme" => self.scheme.clone(),
+            ":authority" => match (&self.host, self.port) {
+                (Some(host), Some(port)) => Some(format!("{host}:{port}")),
+                (Some(host), None) => Some(host.clone()),
+                (None, _) => self.header("Host").cloned(),
+            },
+            ":path" => Some(self.target()),
+            _ => None,
+        }
+    }
+
+    /// Every pseudo-header the request can fill, as sent ahead of the headers over HTTP/2.
+    pub fn pseudo_headers(&self) -> Vec<(&'static str, String)> {
+        PSEUDO_HEADERS
+            .into_iter()
+            .filter_map(|name| Some((name, self.pseudo_header(name)?)))
+            .collect()
+    }
+
+    /// Stores a known pseudo-header in its fields; `false` for any other name.
+    pub(crate) fn set_pseudo_header(&mut self, name: &str, value: &str) -> bool {
+        match name {
+            ":method" => self.method = value.into(),
+            ":scheme" => self.scheme = Some(value.into()),
+            ":authority" => {
+                let (host, port) = split_host_port(value);
+                self.host = Some(host.into());
+                self.port = port;
+            }
+            ":path" => {
+                let target = Request::from(value);
+                self.path = target.path;
+                self.query = target.query;
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::method(":method", "POST", Request::default().with_method("POST"))]
+    #[case::scheme(":scheme", "https", Request::default().with_scheme("https"))]
+    #[case::authority(":authority", "api.example.com:8443", Request::default().with_host("api.example.com").with_port(8443))]
+    #[case::path(":path", "/users?page=2", Request::from("/users?page=2"))]
+    #[case::regular("x-trace", "1", Request::default().with_header("X-Trace", "1"))]
+    fn set_maps_to_fields(#[case] name: &str, #[case] value: &str, #[case] expected: Request) {
+        assert_eq!(
+            Request::default().with_header(name, value),
+            expected,
+            "Setting {name} should update the matching field"
+        );
+    }
+
+    #[test]
+    fn reads_fields_in_wire_order() {
+        let request = Request::from("https://api.example.com/v1/x?y=1#top").with_method("PUT");
+
+        assert_eq!(
+            request.pseudo_headers(),
+            vec![
+                (":method", "PUT".into()),
+                (":scheme", "https".into()),
+                (":authority", "api.example.com".into()),
+                (":path", "/v1/x?y=1".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn authority_falls_back_to_host_header() {
+        let request = Request::default().with_header("Host", "example.com:8080");
+
+        assert_eq!(
+            request.pseudo_header(":authority").as_deref(),
+            Some("example.com:8080")
+        );
+    }
+}
@@ -0,0 +1,283 @@
+use crate::body::Body;
+use crate::headers::Headers;
+use crate::raw::{self, ParseLimits, RawRequestError};
+use crate::redaction::{REDACTED, Redaction};
+use crate::request::HttpVersion;
+
+/// An HTTP response, built with the same `with_*` shorthands as [`Request`](crate::Request).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Response {
+    pub status: u16,
+    /// The reason phrase; [`Response::reason`] falls back to the standard one.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub reason: Option<String>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Headers::is_empty"))]
+    pub headers: Headers,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Body::is_empty"))]
+    pub body: Body,
+    #[cfg_attr(feature = "serde", serde(rename = "version"))]
+    pub http_version: HttpVersion,
+}
+
+impl Default for Response {
+    fn default() -> Self {
+        Self::new(200)
+    }
+}
+
+impl Response {
+    pub fn new(status: u16) -> Self {
+        Self {
+            status,
+            reason: None,
+            headers: Headers::new(),
+            body: Body::Empty,
+            http_version: HttpVersion::default(),
+        }
+    }
+
+    /// Parses a raw HTTP/1.x response — status line, headers and a body framed by
+    /// `Content-Length` or chunked encoding — applying the default [`ParseLimits`].
+    pub fn parse_http(bytes: &[u8]) -> Result<Response, RawRequestError> {
+        raw::parse_response(bytes, &ParseLimits::default())
+    }
+
+    /// [`Response::parse_http`] with custom limits.
+    pub fn parse_http_with(
+        bytes: &[u8],
+        limits: &ParseLimits,
+    ) -> Result<Response, RawRequestError> {
+        raw::parse_response(bytes, limits)
+    }
+
+    /// Writes the response as raw HTTP/1.1, adding `Content-Length` when missing.
+    pub fn to_http_bytes(&self) -> Vec<u8> {
+        raw::render_response(self)
+    }
+
+    pub fn to_http_string(&self) -> String {
+        String::from_utf8_lossy(&self.to_http_bytes()).into_owned()
+    }
+
+    /// The explicit reason phrase, or the standard one for the status.
+    pub fn reason(&self) -> Option<&str> {
+        self.reason
+            .as_deref()
+            .or_else(|| canonical_reason(self.status))
+    }
+
+    /// Whether the status permits a body: not for `1xx`, `204 No Content` and
+    /// `304 Not Modified`.
+    pub fn allows_body(&self) -> bool {
+        !matches!(self.status, 100..=199 | 204 | 304)
+    }
+
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The first value of a header, looked up case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&String> {
+        self.headers.get(name)
+    }
+
+    pub fn header_values(&self, name: &str) -> &[String] {
+        self.headers.get_all(name)
+    }
+
+    pub fn set_status(&mut self, status: u16) {
+        self.status = status;
+    }
+
+    pub fn set_reason<S: Into<String>>(&mut self, reason: S) {
+        self.reason = Some(reason.into());
+    }
+
+    pub fn set_http_version(&mut self, version: HttpVersion) {
+        self.http_version = version;
+    }
+
+    pub fn set_header<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        self.headers.insert(key, value);
+    }
+
+    /// Adds another value for the header, keeping the ones already present.
+    pub fn append_header<K: Into<String>, V: Into<String>>(&mut self, key: K, value: V) {
+        self.headers.append(key, value);
+    }
+
+    pub fn set_body<B: Into<Body>>(&mut self, body: B) {
+        self.body = body.into();
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.set_status(status);
+        self
+    }
+
+    pub fn with_reason<S: Into<String>>(mut self, reason: S) -> Self {
+        self.set_reason(reason);
+        self
+    }
+
+    pub fn with_http_version(mut self, version: HttpVersion) -> Self {
+        self.set_http_version(version);
+        self
+    }
+
+    pub fn with_header<K: Into<String>, V: Into<String>>(mut self, key: K, value: V) -> Self {
+        self.set_header(key, value);
+        self
+    }
+
+    pub fn with_appended_header<K: Into<String>, V: Into<String>>(
+        mut self,
+        key: K,
+        value: V,
+    ) -> Self {
+        self.append_header(key, value);
+        self
+    }
+
+    pub fn with_body<B: Into<Body>>(mut self, body: B) -> Self {
+        self.set_body(body);
+        self
+    }
+
+    /// Sets `value` as a JSON body along with `Content-Type: application/json`.
+    ///
+    /// # Panics
+    ///
+    /// When `value` can't be represented as JSON, e.g. a map with non-string keys.
+    #[cfg(feature = "json")]
+    pub fn with_json<T: serde::Serialize + ?Sized>(self, value: &T) -> Self {
+        let json = serde_json::to_value(value).expect("value should serialize to JSON");
+        self.with_header("Content-Type", "application/json")
+            .with_body(json)
+    }
+}
+
+/// The standard reason phrase for common status codes.
+pub(crate) fn canonical_reason(status: u16) -> Option<&'static str> {
+    Some(match status {
+        100 => "Continue",
+        101 => "Switching Protocols",
+        200 => "OK",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "No Content",
+        206 => "Partial Content",
+        301 => "Moved Permanently",
+        302 => "Found",
+        303 => "See Other",
+        304 => "Not Modified",
+        307 => "Temporary Redirect",
+        308 => "Permanent Redirect",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        406 => "Not Acceptable",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        410 => "Gone",
+        412 => "Precondition Failed",
+        413 => "Content Too Large",
+        415 => "Unsupported Media Type",
+        416 => "Range Not Satisfiable",
+        422 => "Unprocessable Content",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        501 => "Not Implemented",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        _ => return None,
+    })
+}
+
+/// Headers masked by the current [`Redaction`] are shown as `[REDACTED]`.
+impl std::fmt::Display for Response {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}", self.status)?;
+        if let Some(reason) = self.reason() {
+            write!(f, " {reason}")?;
+        }
+
+        if !self.headers.is_empty() {
+            let policy = Redaction::current();
+            let headers = self
+                .headers
+                .iter()
+                .map(|(k, v)| match policy.hides_header(k) {
+                    true => format!("{k:?} = {REDACTED:?}"),
+                    false => format!("{k:?} = {v:?}"),
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            write!(f, " | with headers {{{headers}}}")?;
+        }
+
+        if let Some(body) = self.body.bytes() {
+            match std::str::from_utf8(&body) {
+                Ok(text) => write!(f, " | with body {text:?}")?,
+                Err(_) => write!(f, " | with {} bytes of binary body", body.len())?,
+            };
+        }
+
+        f.write_str("]")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::plain(Response::new(404), "[404 Not Found]")]
+    #[case::custom_reason(Response::new(299).with_reason("Fine"), "[299 Fine]")]
+    #[case::unknown(Response::new(599), "[599]")]
+    #[case::full(Response::default().with_header("Set-Cookie", "sid=1").with_body("hi"), r#"[200 OK | with headers {"Set-Cookie" = "[REDACTED]"} | with body "hi"]"#)]
+    fn display(#[case] response: Response, #[case] expected: &str) {
+        assert_eq!(response.to_string(), expected);
+    }
+
+    #[rstest]
+    #[case::length(b"HTTP/1.1 201 Created\r\nContent-Length: 2\r\n\r\nhi", Response::new(201).with_reason("Created").with_header("Content-Length", "2").with_body("hi"))]
+    #[case::no_reason(b"HTTP/1.0 204\r\n\r\n", Response::new(204).with_http_version(HttpVersion::Http10))]
+    #[case::chunked(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nhe\r\n3;x=1\r\nllo\r\n0\r\n\r\n", Response::new(200).with_reason("OK").with_header("Transfer-Encoding", "chunked").with_body("hello"))]
+    #[case::until_close(b"HTTP/1.1 500 Oops\n\nboom", Response::new(500).with_reason("Oops").with_body("boom"))]
+    fn parses(#[case] raw: &[u8], #[case] expected: Response) {
+        assert_eq!(Response::parse_http(raw), Ok(expected));
+    }
+
+    #[rstest]
+    #[case::status_line(b"HTTP/1.1 abc\r\n\r\n", RawRequestError::InvalidStatusLine("HTTP/1.1 abc".into()))]
+    #[case::chunk(
+        b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nab",
+        RawRequestError::InvalidChunk
+    )]
+    fn rejects(#[case] raw: &[u8], #[case] error: RawRequestError) {
+        assert_eq!(Response::parse_http(raw), Err(error));
+    }
+
+    #[rstest]
+    #[case::empty(
+        Response::new(404),
+        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n"
+    )]
+    #[case::no_content(Response::new(204), "HTTP/1.1 204 No Content\r\n\r\n")]
+    #[case::no_content_body(Response::new(204).with_body("ignored"), "HTTP/1.1 204 No Content\r\n\r\n")]
+    #[case::not_modified_body(Response::new(304).with_body("ignored"), "HTTP/1.1 304 Not Modified\r\n\r\n")]
+    #[case::body(Response::default().with_header("Content-Type", "text/plain").with_body("hi"), "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nContent-Length: 2\r\n\r\nhi")]
+    fn renders(#[case] response: Response, #[case] raw: &str) {
+        assert_eq!(response.to_http_string(), raw);
+    }
+}
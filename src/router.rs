@@ -0,0 +1,165 @@
+use crate::matchers::{MatchScore, Matchers, MismatchReport};
+use crate::request::Request;
+
+/// A set of matcher sets, each identified by an `Id`, that picks the one a request matches.
+#[derive(Debug, Clone)]
+pub struct Router<Id> {
+    routes: Vec<Route<Id>>,
+}
+
+#[derive(Debug, Clone)]
+struct Route<Id> {
+    id: Id,
+    matchers: Matchers,
+}
+
+/// A matcher set that rejected the request, with the reasons.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate<'a, Id> {
+    pub id: &'a Id,
+    pub score: MatchScore,
+    pub report: MismatchReport,
+}
+
+impl<Id> Router<Id> {
+    pub fn new() -> Self {
+        Self { routes: Vec::new() }
+    }
+
+    /// Registers `matchers` under `id`. When several sets match, the highest
+    /// [`Matchers::priority`] wins, then the highest [`Matchers::specificity`], then the set
+    /// added first.
+    pub fn add(&mut self, id: Id, matchers: Matchers) {
+        self.routes.push(Route { id, matchers });
+    }
+
+    pub fn with(mut self, id: Id, matchers: Matchers) -> Self {
+        self.add(id, matchers);
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    pub fn get(&self, id: &Id) -> Option<&Matchers>
+    where
+        Id: PartialEq,
+    {
+        self.routes
+            .iter()
+            .find(|route| &route.id == id)
+            .map(|route| &route.matchers)
+    }
+
+    /// Returns the id of the best matching set, or every candidate ordered from the nearest
+    /// miss (highest [`MatchScore`]) to the farthest.
+    pub fn route(&self, request: &Request) -> Result<&Id, Vec<Candidate<'_, Id>>> {
+        let mut best: Option<&Route<Id>> = None;
+        let mut candidates = Vec::new();
+
+        for route in &self.routes {
+            match route.matchers.validate(request) {
+                Ok(()) if best.is_none_or(|b| rank(&route.matchers) > rank(&b.matchers)) => {
+                    best = Some(route)
+                }
+                Ok(()) => {}
+                Err(report) if best.is_none() => candidates.push(Candidate {
+                    id: &route.id,
+                    score: route.matchers.score(request),
+                    report,
+                }),
+                Err(_) => {}
+            }
+        }
+
+        match best {
+            Some(route) => Ok(&route.id),
+            None => {
+                candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.score));
+                Err(candidates)
+            }
+        }
+    }
+}
+
+fn rank(matchers: &Matchers) -> (i32, u32) {
+    (matchers.priority(), matchers.specificity())
+}
+
+impl<Id> Default for Router<Id> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn router() -> Router<&'static str> {
+        Router::new()
+            .with("any-get", Matchers::new().method("GET"))
+            .with("users", Matchers::new().method("GET").path("/users"))
+            .with("create", Matchers::new().method("POST").path("/users"))
+            .with("users-any", Matchers::new().path("/users"))
+            .with(
+                "health",
+                Matchers::new().path_prefix("/health").with_priority(10),
+            )
+            .with(
+                "health-get",
+                Matchers::new().path("/health").with_priority(10),
+            )
+    }
+
+    #[rstest]
+    #[case::most_specific(Request::from("/users"), "users")]
+    #[case::method(Request::from("/users").with_method("POST"), "create")]
+    #[case::first_added(Request::from("/users").with_method("PUT"), "users-any")]
+    #[case::fallthrough(Request::from("/posts"), "any-get")]
+    #[case::priority(Request::from("/health/live"), "health")]
+    #[case::priority_then_specificity(Request::from("/health"), "health-get")]
+    fn routes_to_best_match(#[case] request: Request, #[case] expected: &str) {
+        assert_eq!(
+            router().route(&request),
+            Ok(&expected),
+            "Request {request} should be routed to {expected:?}"
+        );
+    }
+
+    #[test]
+    fn unmatched_request_lists_nearest_candidates() {
+        let router = Router::new()
+            .with("health", Matchers::new().path("/health"))
+            .with(
+                "create",
+                Matchers::new()
+                    .method("POST")
+                    .path("/users")
+                    .header_exists("X-Key"),
+            )
+            .with("users", Matchers::new().method("GET").path("/users"));
+        let candidates = router
+            .route(&Request::from("/users").with_method("DELETE"))
+            .unwrap_err();
+        let ranked: Vec<(&str, String)> = candidates
+            .iter()
+            .map(|candidate| (*candidate.id, candidate.score.to_string()))
+            .collect();
+
+        assert_eq!(
+            ranked,
+            vec![
+                ("users", "1/2 matchers satisfied".into()),
+                ("create", "1/3 matchers satisfied".into()),
+                ("health", "0/1 matchers satisfied".into()),
+            ]
+        );
+    }
+}
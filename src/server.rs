@@ -0,0 +1,1679 @@
+//! A mock HTTP/1.1 server answering requests from stubs, each a [`Matchers`] set paired
+//! with a [`Response`].
+//!
+//! Responses of [templated](Stub::templated) stubs may contain placeholders filled from the
+//! matched request:
+//!
+//! - `{{method}}`, `{{path}}` and `{{url}}` (path and query);
+//! - `{{path.<name>}}` for a path template capture;
+//! - `{{query.<name>}}` and `{{header.<name>}}` for the first value;
+//! - `{{body}}` and, with the `json` feature, `{{jsonPath <expr>}}` for the first node;
+//!   strings are inserted unquoted, other values as JSON.
+//!
+//! Placeholders without a value render empty; unknown ones are kept as written.
+
+use crate::matchers::Matchers;
+use crate::raw::ParseLimits;
+use crate::request::Request;
+use crate::response::Response;
+use crate::router::Router;
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "admin")]
+mod admin;
+mod chunked;
+mod connection;
+mod delay;
+mod diagnosis;
+mod journal;
+#[cfg(feature = "serde")]
+mod mapping;
+mod proxy;
+#[cfg(feature = "record")]
+mod record;
+mod scenario;
+mod sse;
+mod stub;
+mod template;
+#[cfg(feature = "tls")]
+mod tls;
+mod verify;
+#[cfg(feature = "websocket")]
+mod websocket;
+#[cfg(feature = "fixtures")]
+mod wiremock;
+
+pub use chunked::Chunking;
+pub use delay::Delay;
+pub use diagnosis::NearMiss;
+pub use journal::{Journal, Received};
+#[cfg(feature = "serde")]
+pub use mapping::Mapping;
+pub use proxy::{Proxy, ProxyError, ProxyMode};
+#[cfg(feature = "record")]
+pub use record::{MappingFormat, Recording};
+pub use scenario::Scenario;
+pub use sse::{EventStream, SseEvent};
+pub use stub::{Stub, StubId};
+#[cfg(feature = "tls")]
+pub use tls::{TlsConfig, TlsError};
+pub use verify::{Times, VerificationError};
+#[cfg(feature = "websocket")]
+pub use websocket::{WebSocket, WsExpectation, WsMessage};
+
+use delay::Rng;
+use scenario::States;
+
+/// State shared between the [`MockServer`] handle and its connection threads.
+struct Shared {
+    /// Replaced whole on every change, so requests route on a snapshot of their own while
+    /// stubs are registered or removed, even from within a matcher.
+    stubs: RwLock<Arc<Vec<(StubId, Stub)>>>,
+    next_id: AtomicU64,
+    default_response: Mutex<Option<Response>>,
+    states: Mutex<States>,
+    rng: Mutex<Rng>,
+    /// Every request received, in arrival order.
+    received: Mutex<Vec<Received>>,
+    proxy: Mutex<Option<Proxy>>,
+    /// Whether requests under `/__admin` go to the admin API.
+    #[cfg(feature = "admin")]
+    admin: AtomicBool,
+    /// The active recording and the mappings of the exchanges proxied since it started.
+    #[cfg(feature = "record")]
+    recording: Mutex<Option<(Recording, Vec<Mapping>)>>,
+    /// Set when connections are served over TLS.
+    #[cfg(feature = "tls")]
+    tls: Option<Arc<rustls::ServerConfig>>,
+    limits: ParseLimits,
+    connections: connection::Connections,
+}
+
+/// What a connection sends back for a request.
+struct Reply {
+    response: Response,
+    /// How long to wait before writing the response.
+    delay: Duration,
+    stream: Option<Stream>,
+}
+
+/// What a connection writes after the response head instead of the response body.
+enum Stream {
+    /// The script to play once the `101` handshake response is written.
+    #[cfg(feature = "websocket")]
+    WebSocket(websocket::WebSocket),
+    Events(sse::EventStream),
+    /// The response body in chunks, each with the time to wait before sending it.
+    Chunks(Vec<(Duration, Vec<u8>)>),
+}
+
+impl From<Response> for Reply {
+    fn from(response: Response) -> Self {
+        Reply {
+            response,
+            delay: Duration::ZERO,
+            stream: None,
+        }
+    }
+}
+
+impl Shared {
+    fn new() -> Self {
+        Shared {
+            stubs: RwLock::default(),
+            next_id: AtomicU64::new(1),
+            default_response: Mutex::default(),
+            states: Mutex::default(),
+            rng: Mutex::new(Rng::new(0)),
+            received: Mutex::default(),
+            proxy: Mutex::default(),
+            #[cfg(feature = "admin")]
+            admin: AtomicBool::new(false),
+            #[cfg(feature = "record")]
+            recording: Mutex::default(),
+            #[cfg(feature = "tls")]
+            tls: None,
+            limits: ParseLimits::default(),
+            connections: Default::default(),
+        }
+    }
+
+    fn respond(&self, request: &Request) -> Reply {
+        #[cfg(feature = "admin")]
+        if self.admin.load(Ordering::Acquire)
+            && let Some(response) = admin::handle(self, request)
+        {
+            return response.into();
+        }
+        let started = Instant::now();
+        let (stub, near_misses, reply) = self.dispatch(request);
+        self.record(Received {
+            request: request.clone(),
+            stub,
+            near_misses,
+            response: reply.response.clone(),
+            elapsed: started.elapsed() + reply.delay,
+        });
+        reply
+    }
+
+    /// The reply to `request` with the stub that answered it and how the others missed it.
+    fn dispatch(&self, request: &Request) -> (Option<StubId>, Vec<NearMiss>, Reply) {
+        let proxy = self
+            .proxy
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let near_misses = match &proxy {
+            Some(proxy) if proxy.mode == ProxyMode::All => Vec::new(),
+            _ => match self.answer(request) {
+                Ok((id, near_misses, reply)) => return (Some(id), near_misses, reply),
+                Err(near_misses) => near_misses,
+            },
+        };
+
+        let reply = match proxy {
+            Some(proxy) => {
+                let response = proxy.forward(request);
+                #[cfg(feature = "record")]
+                if let Some((recording, mappings)) = self
+                    .recording
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .as_mut()
+                {
+                    mappings.push(recording.mapping(request, &response));
+                }
+                response.into()
+            }
+            None => self.unmatched(request, &near_misses).into(),
+        };
+        (None, near_misses, reply)
+    }
+
+    /// The stub matching `request` and its reply, or how the stubs missed it.
+    fn answer(&self, request: &Request) -> Result<(StubId, Vec<NearMiss>, Reply), Vec<NearMiss>> {
+        let stubs = self.stubs();
+        // Matchers are user code too, so routing runs on a snapshot of the states; the
+        // transition applies only if no other request moved a scenario meanwhile, and the
+        // request is routed again otherwise.
+        let (id, near_misses, stub) = loop {
+            let snapshot = self.states_snapshot();
+            let (id, near_misses) = route(&stubs, &snapshot, request)?;
+            let Some((_, stub)) = stubs.iter().find(|(stub_id, _)| *stub_id == id) else {
+                return Ok((id, near_misses, Response::new(500).into()));
+            };
+            let mut states = self.states.lock().unwrap_or_else(PoisonError::into_inner);
+            if *states == snapshot {
+                states.advance(stub.scenario.as_ref());
+                break (id, near_misses, stub);
+            }
+        };
+        let delay = stub.delay.as_ref().map_or(Duration::ZERO, |delay| {
+            delay.sample(&mut self.rng.lock().unwrap_or_else(PoisonError::into_inner))
+        });
+        #[cfg(feature = "websocket")]
+        if let Some(script) = &stub.websocket
+            && websocket::is_upgrade(request)
+        {
+            let reply = Reply {
+                response: websocket::handshake(request),
+                delay,
+                stream: Some(Stream::WebSocket(script.clone())),
+            };
+            return Ok((id, near_misses, reply));
+        }
+        let response = stub.respond(request);
+        let reply = match (&stub.events, &stub.chunking) {
+            (Some(events), _) => Reply {
+                response: sse::EventStream::head(response),
+                delay,
+                stream: Some(Stream::Events(events.clone())),
+            },
+            (None, Some(chunking)) => {
+                let body = response.body.bytes().unwrap_or_default();
+                let mut rng = self.rng.lock().unwrap_or_else(PoisonError::into_inner);
+                let chunks = chunking.split(&body, &mut rng);
+                Reply {
+                    response,
+                    delay,
+                    stream: Some(Stream::Chunks(chunks)),
+                }
+            }
+            (None, None) => Reply {
+                response,
+                delay,
+                stream: None,
+            },
+        };
+        Ok((id, near_misses, reply))
+    }
+
+    /// The stubs registered right now; later changes don't affect the snapshot.
+    fn stubs(&self) -> Arc<Vec<(StubId, Stub)>> {
+        self.stubs
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Applies `change` to a copy of the stubs when requests are routing on the current ones,
+    /// then puts it in their place.
+    fn update_stubs<T>(&self, change: impl FnOnce(&mut Vec<(StubId, Stub)>) -> T) -> T {
+        let mut stubs = self.stubs.write().unwrap_or_else(PoisonError::into_inner);
+        change(Arc::make_mut(&mut stubs))
+    }
+
+    fn add(&self, stub: Stub) -> StubId {
+        let id = StubId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        self.update_stubs(|stubs| stubs.push((id, stub)));
+        id
+    }
+
+    /// Removes the stub `id`, returning whether it was registered.
+    fn remove(&self, id: StubId) -> bool {
+        self.update_stubs(|stubs| {
+            let count = stubs.len();
+            stubs.retain(|(stub_id, _)| *stub_id != id);
+            stubs.len() != count
+        })
+    }
+
+    /// Puts `stub` in place of the stub `id`, returning whether it was registered.
+    fn replace(&self, id: StubId, stub: Stub) -> bool {
+        self.update_stubs(
+            |stubs| match stubs.iter_mut().find(|(stub_id, _)| *stub_id == id) {
+                Some((_, registered)) => {
+                    *registered = stub;
+                    true
+                }
+                None => false,
+            },
+        )
+    }
+
+    fn near_misses(&self, request: &Request) -> Vec<NearMiss> {
+        match route(&self.stubs(), &self.states_snapshot(), request) {
+            Ok((_, near_misses)) | Err(near_misses) => near_misses,
+        }
+    }
+
+    fn states_snapshot(&self) -> States {
+        self.states
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    fn record(&self, received: Received) {
+        self.received
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(received);
+    }
+
+    /// The configured default, or a 404 explaining how the nearest stubs differed.
+    fn unmatched(&self, request: &Request, near_misses: &[NearMiss]) -> Response {
+        let default = self
+            .default_response
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(response) = default.as_ref() {
+            return response.clone();
+        }
+
+        let mut body = format!("No stub matched {request}");
+        for near_miss in near_misses {
+            body.push_str(&format!("\n- {near_miss}"));
+        }
+        Response::new(404)
+            .with_header("Content-Type", "text/plain; charset=utf-8")
+            .with_body(body)
+    }
+}
+
+/// Picks the stub answering `request` among those active in the current scenario states,
+/// trying fallback stubs only when no other matches, along with how the other stubs missed
+/// it. Without any match, the stubs are ranked as near misses.
+fn route(
+    stubs: &[(StubId, Stub)],
+    states: &States,
+    request: &Request,
+) -> Result<(StubId, Vec<NearMiss>), Vec<NearMiss>> {
+    let router = |fallback: bool| {
+        let mut router = Router::new();
+        for (id, stub) in stubs {
+            if stub.fallback == fallback && states.allows(stub.scenario.as_ref()) {
+                router.add(*id, stub.matchers.clone());
+            }
+        }
+        router
+    };
+    let near_misses = match router(false).route(request) {
+        Ok(id) => return Ok((*id, Vec::new())),
+        Err(candidates) => NearMiss::ranked(candidates),
+    };
+    match router(true).route(request) {
+        Ok(id) => Ok((*id, near_misses)),
+        Err(_) => Err(near_misses),
+    }
+}
+
+/// A server on an ephemeral local port that answers from its stubs. Requests no stub
+/// matches get a 404 explaining the nearest miss, unless a default response is set.
+/// Dropping the server stops accepting connections, closes idle ones and waits for the
+/// exchanges in flight to complete.
+pub struct MockServer {
+    address: SocketAddr,
+    shared: Arc<Shared>,
+    acceptor: Option<Acceptor>,
+}
+
+/// What accepts connections; either way each connection is served on a thread of its own,
+/// so slow stubs never hold up an async runtime.
+enum Acceptor {
+    /// A thread blocked in `accept`, told to stop by the flag.
+    Thread(JoinHandle<()>, Arc<AtomicBool>),
+    /// A task on the tokio runtime the server was started in.
+    #[cfg(feature = "tokio")]
+    Task(tokio::task::JoinHandle<()>),
+}
+
+impl MockServer {
+    /// Binds `127.0.0.1` on a port chosen by the OS and starts serving plain HTTP, accepting
+    /// connections on a thread of its own; for synchronous test suites.
+    pub fn start_blocking() -> io::Result<MockServer> {
+        Self::launch(Shared::new())
+    }
+
+    /// Like [`MockServer::start_blocking`], but serves HTTPS with the certificate in `config`.
+    #[cfg(feature = "tls")]
+    pub fn start_tls_blocking(config: &TlsConfig) -> io::Result<MockServer> {
+        Self::launch(Shared {
+            tls: Some(config.server_config()?),
+            ..Shared::new()
+        })
+    }
+
+    /// Like [`MockServer::start_blocking`], but accepts connections on a task of the current
+    /// tokio runtime, for async tests. Panics outside a runtime.
+    #[cfg(feature = "tokio")]
+    pub async fn start() -> io::Result<MockServer> {
+        Self::launch_task(Shared::new()).await
+    }
+
+    /// Like [`MockServer::start`], but serves HTTPS with the certificate in `config`.
+    #[cfg(all(feature = "tokio", feature = "tls"))]
+    pub async fn start_tls(config: &TlsConfig) -> io::Result<MockServer> {
+        Self::launch_task(Shared {
+            tls: Some(config.server_config()?),
+            ..Shared::new()
+        })
+        .await
+    }
+
+    fn launch(shared: Shared) -> io::Result<MockServer> {
+        let listener = TcpListener::bind(("127.0.0.1", 0))?;
+        let address = listener.local_addr()?;
+        let shared = Arc::new(shared);
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let acceptor = std::thread::spawn({
+            let shared = shared.clone();
+            let stopped = stopped.clone();
+            move || accept(listener, shared, stopped)
+        });
+
+        Ok(MockServer {
+            address,
+            shared,
+            acceptor: Some(Acceptor::Thread(acceptor, stopped)),
+        })
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn launch_task(shared: Shared) -> io::Result<MockServer> {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await?;
+        let address = listener.local_addr()?;
+        let shared = Arc::new(shared);
+        let acceptor = tokio::spawn(accept_task(listener, shared.clone()));
+
+        Ok(MockServer {
+            address,
+            shared,
+            acceptor: Some(Acceptor::Task(acceptor)),
+        })
+    }
+
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// The base URL to point clients at, e.g. `http://127.0.0.1:41234`, without a trailing
+    /// slash.
+    pub fn url(&self) -> String {
+        #[cfg(feature = "tls")]
+        if self.shared.tls.is_some() {
+            return format!("https://{}", self.address);
+        }
+        format!("http://{}", self.address)
+    }
+
+    /// Registers `stub`; it answers requests from now on.
+    pub fn stub(&self, stub: Stub) -> StubId {
+        self.shared.add(stub)
+    }
+
+    /// Unregisters the stub `id`, returning whether it was registered; requests already
+    /// answered by it are kept in the journal.
+    pub fn remove(&self, id: StubId) -> bool {
+        self.shared.remove(id)
+    }
+
+    /// Swaps the stub `id` for `stub`, which keeps the id and, among stubs of equal
+    /// precedence, its place; returns whether `id` was registered.
+    pub fn replace(&self, id: StubId, stub: Stub) -> bool {
+        self.shared.replace(id, stub)
+    }
+
+    /// Shorthand for registering [`Stub::new`].
+    pub fn mock(&self, matchers: Matchers, response: Response) -> StubId {
+        self.stub(Stub::new(matchers, response))
+    }
+
+    /// Registers a [`Stub::from_fn`], answering with responses computed by `responder`.
+    pub fn mock_fn<F>(&self, matchers: Matchers, responder: F) -> StubId
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        self.stub(Stub::from_fn(matchers, responder))
+    }
+
+    /// Checks that the number of received requests matching `matchers` is within `times`;
+    /// the error lists the unmatched requests that came closest.
+    pub fn verify(&self, matchers: &Matchers, times: Times) -> Result<(), VerificationError> {
+        verify::verify(&self.journal(), matchers, times)
+    }
+
+    /// Every request received so far, oldest first, see [`Journal`] for narrowing it down.
+    pub fn received(&self) -> Journal {
+        Journal::new(self.journal().clone())
+    }
+
+    /// Writes every exchange so far to `path` as a HAR file, for inspection in browser dev
+    /// tools; use [`Journal::to_har`] to export only some.
+    #[cfg(feature = "har")]
+    pub fn export_har<P: AsRef<std::path::Path>>(&self, path: P) -> io::Result<()> {
+        let har = self.received().to_har();
+        std::fs::write(path, serde_json::to_vec_pretty(&har)?)
+    }
+
+    /// Forgets every received request.
+    pub fn reset(&self) {
+        self.journal().clear();
+    }
+
+    fn journal(&self) -> std::sync::MutexGuard<'_, Vec<Received>> {
+        self.shared
+            .received
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// The state `scenario` is in, [`Scenario::STARTED`] until a stub moves it.
+    pub fn scenario_state(&self, scenario: &str) -> String {
+        self.states().get(scenario).to_string()
+    }
+
+    pub fn set_scenario_state(&self, scenario: &str, state: &str) {
+        self.states().set(scenario, state);
+    }
+
+    /// Moves every scenario back to [`Scenario::STARTED`].
+    pub fn reset_scenarios(&self) {
+        self.states().reset();
+    }
+
+    fn states(&self) -> std::sync::MutexGuard<'_, States> {
+        self.shared
+            .states
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Reseeds the generator behind random [`Delay`]s, which starts from a fixed seed so
+    /// delays repeat across runs.
+    pub fn set_delay_seed(&self, seed: u64) {
+        *self
+            .shared
+            .rng
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Rng::new(seed);
+    }
+
+    /// The stubs that came closest to matching `request` and what they differed in, without
+    /// sending it; empty when a stub other than a [fallback](Stub::fallback) matches.
+    pub fn near_misses(&self, request: &Request) -> Vec<NearMiss> {
+        self.shared.near_misses(request)
+    }
+
+    /// Forwards requests to an upstream as `proxy` configures, or stops forwarding for `None`.
+    pub fn set_proxy(&self, proxy: Option<Proxy>) {
+        *self
+            .shared
+            .proxy
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = proxy;
+    }
+
+    /// Records every exchange forwarded by the [`Proxy`] from now on, until
+    /// [`MockServer::stop_recording`] writes them out; replaces a recording in progress.
+    #[cfg(feature = "record")]
+    pub fn start_recording(&self, recording: Recording) {
+        *self.recording() = Some((recording, Vec::new()));
+    }
+
+    /// Ends the recording, writing one mapping file per distinct request recorded, and
+    /// returns their paths.
+    #[cfg(feature = "record")]
+    pub fn stop_recording(&self) -> Result<Vec<std::path::PathBuf>, crate::FixtureError> {
+        match self.recording().take() {
+            Some((recording, mappings)) => recording.write(&mappings),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    #[cfg(feature = "record")]
+    fn recording(&self) -> std::sync::MutexGuard<'_, Option<(Recording, Vec<Mapping>)>> {
+        self.shared
+            .recording
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Registers a stub for every JSON or YAML mapping file in `dir`, as recorded by
+    /// [`MockServer::stop_recording`], in file name order.
+    #[cfg(feature = "record")]
+    pub fn replay<P: AsRef<std::path::Path>>(
+        &self,
+        dir: P,
+    ) -> Result<Vec<StubId>, crate::FixtureError> {
+        self.load_dir(dir)
+    }
+
+    /// Registers the stubs of every JSON or YAML file directly in `dir`, in file name order:
+    /// [`Mapping`]s, or JSON WireMock mappings holding one stub or a `mappings` array.
+    /// Nothing is registered when a file fails to load.
+    #[cfg(feature = "fixtures")]
+    pub fn load_dir<P: AsRef<std::path::Path>>(
+        &self,
+        dir: P,
+    ) -> Result<Vec<StubId>, crate::FixtureError> {
+        let stubs = mapping::load_dir(dir.as_ref())?;
+        Ok(stubs.into_iter().map(|stub| self.stub(stub)).collect())
+    }
+
+    /// Serves the JSON admin API under `/__admin` while `enabled`, for managing stubs and the
+    /// journal over HTTP:
+    ///
+    /// - `GET`, `POST` and `DELETE /__admin/mappings` list, create and remove all stubs, as
+    ///   [`Mapping`]s with their `id`;
+    /// - `GET`, `PUT` and `DELETE /__admin/mappings/{id}` fetch, replace and remove one;
+    /// - `GET` and `DELETE /__admin/requests` list and forget the received requests;
+    /// - `POST /__admin/near-misses` explains how the stubs miss the request in the body;
+    /// - `POST /__admin/reset` removes everything and resets the scenarios.
+    ///
+    /// Admin requests aren't journaled.
+    #[cfg(feature = "admin")]
+    pub fn set_admin(&self, enabled: bool) {
+        self.shared.admin.store(enabled, Ordering::Release);
+    }
+
+    /// Answers unmatched requests with `response` instead of the explaining 404; `None`
+    /// restores the 404.
+    pub fn set_default_response(&self, response: Option<Response>) {
+        *self
+            .shared
+            .default_response
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = response;
+    }
+}
+
+fn accept(listener: TcpListener, shared: Arc<Shared>, stopped: Arc<AtomicBool>) {
+    for stream in listener.incoming() {
+        if stopped.load(Ordering::Acquire) {
+            break;
+        }
+        match stream {
+            Ok(stream) => spawn_connection(&shared, stream),
+            Err(error) if is_transient(&error) => {}
+            Err(_) => std::thread::sleep(ACCEPT_BACKOFF),
+        }
+    }
+}
+
+/// How long accepting pauses after an error such as running out of file descriptors, which
+/// retrying at once would only hit again.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(10);
+
+/// Whether an accept error concerns only the connection at hand, so the next can follow.
+fn is_transient(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+            | io::ErrorKind::Interrupted
+    )
+}
+
+#[cfg(feature = "tokio")]
+async fn accept_task(listener: tokio::net::TcpListener, shared: Arc<Shared>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(error) if is_transient(&error) => continue,
+            Err(_) => {
+                tokio::time::sleep(ACCEPT_BACKOFF).await;
+                continue;
+            }
+        };
+        let Ok(stream) = stream.into_std() else {
+            continue;
+        };
+        // Connections are served with blocking I/O, off the runtime.
+        if stream.set_nonblocking(false).is_err() {
+            continue;
+        }
+        spawn_connection(&shared, stream);
+    }
+}
+
+/// Serves `stream` on a thread of its own, tracked so dropping the server can drain it.
+fn spawn_connection(shared: &Arc<Shared>, stream: TcpStream) {
+    let Some(id) = shared.connections.open(&stream) else {
+        return;
+    };
+    let shared = shared.clone();
+    std::thread::spawn(move || {
+        let _ = connection::serve(&shared, stream);
+        shared.connections.close(id);
+    });
+}
+
+/// How long dropping a server waits for in-flight exchanges, e.g. endless event streams.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        match self.acceptor.take() {
+            Some(Acceptor::Thread(acceptor, stopped)) => {
+                stopped.store(true, Ordering::Release);
+                // Wakes the acceptor blocked in `accept` so it sees the flag.
+                let _ = TcpStream::connect(self.address);
+                let _ = acceptor.join();
+            }
+            #[cfg(feature = "tokio")]
+            Some(Acceptor::Task(acceptor)) => acceptor.abort(),
+            None => {}
+        }
+        self.shared.connections.drain(DRAIN_TIMEOUT);
+    }
+}
+
+impl std::fmt::Debug for MockServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockServer")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+    use std::io::{Read, Write};
+
+    /// Sends `request` over a fresh connection and reads the whole response.
+    pub(crate) fn send(address: SocketAddr, request: Request) -> Response {
+        let mut stream = TcpStream::connect(address).unwrap();
+        let request = request.with_header("Connection", "close");
+        stream.write_all(&request.to_http_bytes()).unwrap();
+        let mut bytes = Vec::new();
+        stream.read_to_end(&mut bytes).unwrap();
+        Response::parse_http(&bytes).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::testing::send;
+    use super::*;
+    use rstest::rstest;
+    use std::io::{Read, Write};
+
+    #[test]
+    fn serves_matching_stub() {
+        let server = MockServer::start_blocking().unwrap();
+        server.mock(
+            Matchers::new().method("POST").path("/users"),
+            Response::new(201).with_body(r#"{"id":1}"#),
+        );
+
+        let response = send(
+            server.address(),
+            Request::from("/users").with_method("POST").with_body("{}"),
+        );
+
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body, r#"{"id":1}"#.into());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn serves_from_tokio_runtime() {
+        let server = MockServer::start().await.unwrap();
+        server.mock(
+            Matchers::new().path("/ping"),
+            Response::default().with_body("pong"),
+        );
+        let address = server.address();
+
+        let response = tokio::task::spawn_blocking(move || send(address, Request::from("/ping")))
+            .await
+            .unwrap();
+
+        assert_eq!(response.body, "pong".into());
+        server
+            .verify(&Matchers::new().path("/ping"), Times::once())
+            .unwrap();
+    }
+
+    #[test]
+    fn explains_unmatched_request() {
+        let server = MockServer::start_blocking().unwrap();
+        let id = server.mock(Matchers::new().path("/users"), Response::default());
+
+        let response = send(server.address(), Request::from("/posts"));
+
+        assert_eq!(response.status, 404);
+        let body = response.body.text().unwrap_or_default().into_owned();
+        assert!(
+            body.starts_with("No stub matched [GET /posts | with headers")
+                && body.ends_with(&format!(
+                    "\n- stub {id} differed only in: path must equal `/users`, but got \"/posts\""
+                )),
+            "Unexpected body {body:?}"
+        );
+        let received = server.received();
+        let near_misses = &received.first().unwrap().near_misses;
+        assert_eq!(near_misses.len(), 1);
+        assert_eq!(near_misses, &server.near_misses(&Request::from("/posts")));
+    }
+
+    #[test]
+    fn fills_templated_response() {
+        let server = MockServer::start_blocking().unwrap();
+        server.stub(
+            Stub::new(
+                Matchers::new().path_template("/users/{id}"),
+                Response::default()
+                    .with_header("Location", "/users/{{path.id}}")
+                    .with_body("user {{path.id}} for {{header.X-Client}}"),
+            )
+            .templated(),
+        );
+
+        let response = send(
+            server.address(),
+            Request::from("/users/42").with_header("X-Client", "tests"),
+        );
+
+        assert_eq!(response.header("Location").unwrap(), "/users/42");
+        assert_eq!(response.body, "user 42 for tests".into());
+    }
+
+    #[test]
+    fn follows_scenario_states() {
+        let server = MockServer::start_blocking().unwrap();
+        let scenario = || Scenario::new("users");
+        server.stub(
+            Stub::new(Matchers::new().path("/users/1"), Response::new(404))
+                .in_scenario(scenario().when(Scenario::STARTED)),
+        );
+        server.stub(
+            Stub::new(
+                Matchers::new().method("POST").path("/users"),
+                Response::new(201),
+            )
+            .in_scenario(scenario().then("created")),
+        );
+        server.stub(
+            Stub::new(Matchers::new().path("/users/1"), Response::default())
+                .in_scenario(scenario().when("created")),
+        );
+        server.stub(
+            Stub::new(
+                Matchers::new().method("DELETE").path("/users/1"),
+                Response::new(204),
+            )
+            .in_scenario(scenario().when("created").then("deleted")),
+        );
+        let status = |request: Request| send(server.address(), request).status;
+
+        assert_eq!(status(Request::from("/users/1")), 404);
+        assert_eq!(status(Request::from("/users").with_method("POST")), 201);
+        assert_eq!(server.scenario_state("users"), "created");
+        assert_eq!(status(Request::from("/users/1")), 200);
+        assert_eq!(status(Request::from("/users/1").with_method("DELETE")), 204);
+        assert_eq!(server.scenario_state("users"), "deleted");
+        // No stub answers in the final state.
+        assert_eq!(status(Request::from("/users/1")), 404);
+
+        server.reset_scenarios();
+        assert_eq!(server.scenario_state("users"), Scenario::STARTED);
+    }
+
+    /// Blocks routing of `/slow` until released.
+    #[derive(Debug, Default)]
+    struct Hold {
+        entered: AtomicBool,
+        released: AtomicBool,
+    }
+
+    impl crate::matchers::Match for Arc<Hold> {
+        fn validate(&self, request: &Request) -> Option<crate::matchers::Mismatch> {
+            if request.path == "/slow" {
+                self.entered.store(true, Ordering::SeqCst);
+                let deadline = Instant::now() + Duration::from_secs(5);
+                while !self.released.load(Ordering::SeqCst) && Instant::now() < deadline {
+                    std::thread::sleep(Duration::from_millis(5));
+                }
+            }
+            None
+        }
+    }
+
+    #[test]
+    fn routes_while_another_request_is_matching() {
+        let server = MockServer::start_blocking().unwrap();
+        let hold = Arc::new(Hold::default());
+        server.stub(Stub::new(
+            Matchers::new().path("/slow").with(hold.clone()),
+            Response::default(),
+        ));
+        server.stub(
+            Stub::new(Matchers::new().path("/fast"), Response::new(201))
+                .in_scenario(Scenario::new("flow").then("moved")),
+        );
+        let address = server.address();
+        let slow = std::thread::spawn(move || send(address, Request::from("/slow")).status);
+        while !hold.entered.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let started = Instant::now();
+        assert_eq!(send(address, Request::from("/fast")).status, 201);
+        assert_eq!(server.scenario_state("flow"), "moved");
+        assert!(
+            started.elapsed() < Duration::from_secs(2),
+            "A request should not wait for another one's matchers"
+        );
+
+        hold.released.store(true, Ordering::SeqCst);
+        assert_eq!(slow.join().unwrap(), 200);
+    }
+
+    /// Registers a stub on the server the first time it sees a request.
+    #[derive(Default)]
+    struct Registers(std::sync::OnceLock<Arc<Shared>>);
+
+    impl std::fmt::Debug for Registers {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("Registers")
+        }
+    }
+
+    impl crate::matchers::Match for Arc<Registers> {
+        fn validate(&self, _: &Request) -> Option<crate::matchers::Mismatch> {
+            if let Some(shared) = self.0.get() {
+                shared.add(Stub::new(
+                    Matchers::new().path("/registered"),
+                    Response::new(201),
+                ));
+            }
+            None
+        }
+    }
+
+    #[test]
+    fn registers_stubs_from_within_matchers() {
+        let server = MockServer::start_blocking().unwrap();
+        let registers = Arc::new(Registers::default());
+        server.stub(Stub::new(
+            Matchers::new().path("/register").with(registers.clone()),
+            Response::default(),
+        ));
+        assert!(registers.0.set(server.shared.clone()).is_ok());
+
+        assert_eq!(
+            send(server.address(), Request::from("/register")).status,
+            200
+        );
+        assert_eq!(
+            send(server.address(), Request::from("/registered")).status,
+            201
+        );
+    }
+
+    #[test]
+    fn verifies_received_requests() {
+        let server = MockServer::start_blocking().unwrap();
+        send(
+            server.address(),
+            Request::from("/users").with_method("POST"),
+        );
+        send(server.address(), Request::from("/users"));
+
+        let matchers = Matchers::new().method("POST").path("/users");
+        assert_eq!(server.verify(&matchers, Times::once()), Ok(()));
+        let error = server.verify(&matchers, Times::exactly(2)).unwrap_err();
+        assert_eq!((error.actual, error.near_misses.len()), (1, 1));
+        assert!(
+            error.to_string().starts_with(
+                "expected matching requests exactly 2 times, received 1\nnear miss [GET /users"
+            ),
+            "Unexpected message {error}"
+        );
+    }
+
+    #[test]
+    fn journals_received_requests() {
+        let server = MockServer::start_blocking().unwrap();
+        let id = server.mock(Matchers::new().path("/users"), Response::default());
+        send(server.address(), Request::from("/users"));
+        send(server.address(), Request::from("/posts"));
+
+        let received = server.received();
+        assert_eq!(
+            received
+                .iter()
+                .map(|entry| (entry.request.path.as_str(), entry.stub))
+                .collect::<Vec<_>>(),
+            [("/users", Some(id)), ("/posts", None)]
+        );
+        let connection = &received.first().unwrap().request.connection;
+        assert!(connection.remote_addr.is_some() && connection.received_at.is_some());
+
+        server.reset();
+        assert!(server.received().is_empty());
+    }
+
+    #[rstest]
+    #[case::unmatched(ProxyMode::Unmatched, "stubbed")]
+    #[case::all(ProxyMode::All, "upstream")]
+    fn proxies_to_upstream(#[case] mode: ProxyMode, #[case] stubbed: &str) {
+        let upstream = MockServer::start_blocking().unwrap();
+        upstream.mock(
+            Matchers::new().path_prefix("/api/"),
+            Response::default().with_body("upstream"),
+        );
+        let server = MockServer::start_blocking().unwrap();
+        server.mock(
+            Matchers::new().path("/users"),
+            Response::default().with_body("stubbed"),
+        );
+        let url = format!("http://{}/api", upstream.address());
+        server.set_proxy(Some(Proxy::new(&url).unwrap().with_mode(mode)));
+
+        let body = |path: &str| send(server.address(), Request::from(path)).body;
+
+        assert_eq!(body("/users"), stubbed.into());
+        assert_eq!(body("/posts"), "upstream".into());
+        let forwarded = upstream.received();
+        let forwarded = forwarded.requests().last().unwrap();
+        assert_eq!(forwarded.path, "/api/posts");
+        assert_eq!(forwarded.header("Host").unwrap(), "localhost");
+    }
+
+    #[test]
+    fn rewrites_host_for_upstream() {
+        let upstream = MockServer::start_blocking().unwrap();
+        let server = MockServer::start_blocking().unwrap();
+        let url = format!("http://{}", upstream.address());
+        server.set_proxy(Some(Proxy::new(&url).unwrap().rewrite_host()));
+
+        let response = send(server.address(), Request::from("/posts"));
+
+        assert_eq!(response.status, 404);
+        let forwarded = upstream.received();
+        let host = forwarded.requests().next().unwrap().header("Host");
+        assert_eq!(host, Some(&upstream.address().to_string()));
+    }
+
+    #[test]
+    fn drops_headers_listed_in_connection() {
+        let upstream = MockServer::start_blocking().unwrap();
+        let server = MockServer::start_blocking().unwrap();
+        server.set_proxy(Some(
+            Proxy::new(&format!("http://{}", upstream.address())).unwrap(),
+        ));
+
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+        stream
+            .write_all(
+                b"GET / HTTP/1.1\r\nConnection: close, X-Hop\r\nX-Hop: 1\r\nX-Kept: 2\r\n\r\n",
+            )
+            .unwrap();
+        stream.read_to_end(&mut Vec::new()).unwrap();
+
+        let forwarded = upstream.received();
+        let forwarded = forwarded.requests().next().unwrap();
+        assert_eq!(forwarded.header("X-Hop"), None);
+        assert_eq!(forwarded.header("X-Kept").unwrap(), "2");
+        assert_eq!(forwarded.header("Connection").unwrap(), "close");
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn proxies_to_https_upstream() {
+        let config = TlsConfig::self_signed().unwrap();
+        let upstream = MockServer::start_tls_blocking(&config).unwrap();
+        upstream.mock(
+            Matchers::new().scheme("https"),
+            Response::default().with_body("secure"),
+        );
+        let server = MockServer::start_blocking().unwrap();
+        let url = format!("https://localhost:{}", upstream.address().port());
+        let proxy = Proxy::new(&url).unwrap();
+
+        server.set_proxy(Some(proxy.clone()));
+        assert_eq!(send(server.address(), Request::from("/")).status, 502);
+
+        let proxy = proxy
+            .trust_pem(config.certificate_pem().as_bytes())
+            .unwrap();
+        server.set_proxy(Some(proxy));
+        assert_eq!(
+            send(server.address(), Request::from("/")).body,
+            "secure".into()
+        );
+    }
+
+    #[test]
+    fn reports_unreachable_upstream() {
+        let server = MockServer::start_blocking().unwrap();
+        let unused = TcpListener::bind(("127.0.0.1", 0))
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let url = format!("http://{unused}");
+        server.set_proxy(Some(Proxy::new(&url).unwrap()));
+
+        assert_eq!(send(server.address(), Request::default()).status, 502);
+    }
+
+    #[cfg(feature = "record")]
+    #[test]
+    fn records_and_replays_proxied_exchanges() {
+        let dir = tempfile::tempdir().unwrap();
+        let upstream = MockServer::start_blocking().unwrap();
+        upstream.mock(
+            Matchers::new().path("/users/42"),
+            Response::default()
+                .with_header("Date", "Tue, 14 Nov 2023 22:13:20 GMT")
+                .with_body("Bob"),
+        );
+        let server = MockServer::start_blocking().unwrap();
+        let url = format!("http://{}", upstream.address());
+        server.set_proxy(Some(Proxy::new(&url).unwrap()));
+        server.start_recording(Recording::new(dir.path()));
+
+        send(server.address(), Request::from("/users/42"));
+        send(server.address(), Request::from("/users/42"));
+        let written = server.stop_recording().unwrap();
+        drop(upstream);
+
+        assert_eq!(written, [dir.path().join("001-get-users-42.json")]);
+        let replaying = MockServer::start_blocking().unwrap();
+        assert_eq!(replaying.replay(dir.path()).unwrap().len(), 1);
+        let response = send(replaying.address(), Request::from("/users/42"));
+        assert_eq!(response.body, "Bob".into());
+        assert_eq!(response.header("Date"), None);
+    }
+
+    #[cfg(feature = "fixtures")]
+    #[test]
+    fn loads_mapping_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = |name: &str, content: &str| std::fs::write(dir.path().join(name), content);
+        file(
+            "a.yaml",
+            "matchers: [!path /native]\nresponse: {status: 202}\n",
+        )
+        .unwrap();
+        file(
+            "b.json",
+            r#"{"mappings": [{"request": {"method": "GET", "urlPath": "/wiremock"}, "response": {"status": 203}}]}"#,
+        )
+        .unwrap();
+        file("notes.txt", "ignored").unwrap();
+        let server = MockServer::start_blocking().unwrap();
+
+        let ids = server.load_dir(dir.path()).unwrap();
+
+        assert_eq!(ids.len(), 2);
+        assert_eq!(send(server.address(), Request::from("/native")).status, 202);
+        assert_eq!(
+            send(server.address(), Request::from("/wiremock")).status,
+            203
+        );
+        file(
+            "c.json",
+            r#"{"request": {"urlPath": "/"}, "response": {"bodyFileName": "x"}}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            server.load_dir(dir.path()),
+            Err(crate::FixtureError::Invalid(path, _)) if path.ends_with("c.json")
+        ));
+    }
+
+    #[rstest]
+    #[case::aborted(io::ErrorKind::ConnectionAborted, true)]
+    #[case::interrupted(io::ErrorKind::Interrupted, true)]
+    #[case::out_of_descriptors(io::ErrorKind::Other, false)]
+    fn tells_transient_accept_errors(#[case] kind: io::ErrorKind, #[case] transient: bool) {
+        assert_eq!(is_transient(&io::Error::from(kind)), transient);
+    }
+
+    #[test]
+    fn drains_connections_on_drop() {
+        let server = MockServer::start_blocking().unwrap();
+        server.stub(
+            Stub::new(
+                Matchers::new().path("/slow"),
+                Response::new(200).with_body("done"),
+            )
+            .with_delay(Delay::fixed(Duration::from_millis(200))),
+        );
+        let address = server.address();
+        assert_eq!(server.url(), format!("http://{address}"));
+        let mut idle = TcpStream::connect(address).unwrap();
+        idle.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        let in_flight = std::thread::spawn(move || send(address, Request::from("/slow")));
+        while server.received().is_empty() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        drop(server);
+
+        assert_eq!(in_flight.join().unwrap().body, "done".into());
+        assert_eq!(idle.read(&mut [0; 1]).unwrap(), 0);
+        assert!(TcpStream::connect(address).is_err());
+    }
+
+    #[cfg(feature = "har")]
+    #[test]
+    fn exports_exchanges_as_har() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.har");
+        let server = MockServer::start_blocking().unwrap();
+        server.mock(
+            Matchers::new().path("/hello"),
+            Response::new(201).with_body("hi"),
+        );
+        send(server.address(), Request::from("/hello"));
+        send(server.address(), Request::from("/missing"));
+
+        server.export_har(&path).unwrap();
+
+        let har: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let entries = har["log"]["entries"].as_array().unwrap();
+        let summary = entries
+            .iter()
+            .map(|entry| {
+                (
+                    Request::from_har_request(&entry["request"]).unwrap().path,
+                    entry["response"]["status"].as_u64().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            [("/hello".to_string(), 201), ("/missing".to_string(), 404)]
+        );
+        assert_eq!(entries[0]["response"]["content"]["text"], "hi");
+        assert!(
+            entries[0]["startedDateTime"]
+                .as_str()
+                .unwrap()
+                .ends_with('Z')
+        );
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn serves_https() {
+        use rustls::pki_types::ServerName;
+
+        let config = TlsConfig::self_signed().unwrap();
+        let server = MockServer::start_tls_blocking(&config).unwrap();
+        server.mock(
+            Matchers::new().scheme("https"),
+            Response::default().with_body("secure"),
+        );
+        let mut roots = rustls::RootCertStore::empty();
+        roots.add(config.certificate_der().to_vec().into()).unwrap();
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let client = rustls::ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        let connection = rustls::ClientConnection::new(
+            Arc::new(client),
+            ServerName::try_from("localhost").unwrap(),
+        )
+        .unwrap();
+        assert_eq!(server.url(), format!("https://{}", server.address()));
+        let socket = TcpStream::connect(server.address()).unwrap();
+        let mut stream = rustls::StreamOwned::new(connection, socket);
+
+        let request = Request::from("/").with_header("Connection", "close");
+        stream.write_all(&request.to_http_bytes()).unwrap();
+        let mut bytes = Vec::new();
+        stream.read_to_end(&mut bytes).unwrap();
+
+        assert_eq!(Response::parse_http(&bytes).unwrap().body, "secure".into());
+        let received = server.received();
+        let tls = received.first().unwrap().request.connection.tls.clone();
+        assert_eq!(tls.unwrap().server_name.as_deref(), Some("localhost"));
+        assert!(
+            config
+                .certificate_pem()
+                .starts_with("-----BEGIN CERTIFICATE-----\n")
+        );
+    }
+
+    #[cfg(feature = "websocket")]
+    #[rstest]
+    #[case::scripted("subscribe", &[(websocket::TEXT, &b"ack"[..]), (websocket::CLOSE, b"\x0f\xa0bye")])]
+    #[case::violated("hello", &[(websocket::CLOSE, &b"\x03\xf0expected text \"subscribe\""[..])])]
+    fn plays_websocket_script(#[case] sent: &str, #[case] expected: &[(u8, &[u8])]) {
+        let server = MockServer::start_blocking().unwrap();
+        server.stub(Stub::websocket(
+            Matchers::new().path("/live"),
+            WebSocket::new()
+                .send_text("welcome")
+                .expect_text("subscribe")
+                .send_text("ack")
+                .close(4000, "bye"),
+        ));
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+        let handshake = Request::from("/live")
+            .with_header("Upgrade", "websocket")
+            .with_header("Connection", "Upgrade")
+            .with_header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+            .with_header("Sec-WebSocket-Version", "13");
+        stream.write_all(&handshake.to_http_bytes()).unwrap();
+
+        let mut buffer = Vec::new();
+        let head = loop {
+            if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+                break buffer.drain(..end + 4).collect::<Vec<_>>();
+            }
+            let mut chunk = [0; 1024];
+            let read = stream.read(&mut chunk).unwrap();
+            buffer.extend_from_slice(&chunk[..read]);
+        };
+        let response = Response::parse_http(&head).unwrap();
+        assert_eq!(response.status, 101);
+        assert_eq!(
+            response.header("Sec-WebSocket-Accept").map(String::as_str),
+            Some("s3pPLMBiTxaQ9kYGzzhZRbK+xOo=")
+        );
+
+        let mut frames = websocket::Frames { buffer };
+        let welcome = frames.next_frame(&mut stream).unwrap().unwrap();
+        assert_eq!(welcome.payload, b"welcome");
+        let mask = Some([0x12, 0x34, 0x56, 0x78]);
+        websocket::write_frame(&mut stream, websocket::PING, b"?", mask).unwrap();
+        websocket::write_frame(&mut stream, websocket::TEXT, sent.as_bytes(), mask).unwrap();
+
+        let mut received = Vec::new();
+        while let Some(frame) = frames.next_frame(&mut stream).unwrap() {
+            received.push((frame.opcode, frame.payload));
+        }
+        let mut expected = expected
+            .iter()
+            .map(|(opcode, payload)| (*opcode, payload.to_vec()))
+            .collect::<Vec<_>>();
+        expected.insert(0, (websocket::PONG, b"?".to_vec()));
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    fn streams_server_sent_events() {
+        let server = MockServer::start_blocking().unwrap();
+        server.stub(
+            Stub::new(Matchers::new().path("/events"), Response::default()).with_events(
+                EventStream::new()
+                    .event(SseEvent::new("first").with_id("1"))
+                    .wait(Duration::from_millis(50))
+                    .event(SseEvent::new("second").with_event("update")),
+            ),
+        );
+        let started = std::time::Instant::now();
+
+        let response = send(server.address(), Request::from("/events"));
+
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert_eq!(
+            response.header("Content-Type").map(String::as_str),
+            Some("text/event-stream")
+        );
+        assert_eq!(
+            response.body,
+            "id: 1\ndata: first\n\nevent: update\ndata: second\n\n".into()
+        );
+    }
+
+    #[test]
+    fn repeats_event_stream() {
+        let server = MockServer::start_blocking().unwrap();
+        server.stub(
+            Stub::new(Matchers::new(), Response::default())
+                .with_events(EventStream::new().event(SseEvent::new("tick")).repeat()),
+        );
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+        stream
+            .write_all(&Request::default().to_http_bytes())
+            .unwrap();
+
+        let mut bytes = Vec::new();
+        while bytes.windows(4).filter(|window| window == b"tick").count() < 3 {
+            let mut chunk = [0; 1024];
+            let read = stream.read(&mut chunk).unwrap();
+            bytes.extend_from_slice(&chunk[..read]);
+        }
+
+        let head = String::from_utf8_lossy(&bytes);
+        assert!(head.contains("Transfer-Encoding: chunked\r\n"));
+        assert!(head.contains("\r\nc\r\ndata: tick\n\n\r\n"));
+    }
+
+    #[test]
+    fn sends_chunked_body() {
+        let server = MockServer::start_blocking().unwrap();
+        server.stub(
+            Stub::new(
+                Matchers::new(),
+                Response::default().with_body("hello world"),
+            )
+            .with_chunking(Chunking::new(4).with_delay(Delay::fixed(Duration::from_millis(20)))),
+        );
+        let started = std::time::Instant::now();
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+
+        stream
+            .write_all(b"GET / HTTP/1.1\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        let mut bytes = Vec::new();
+        stream.read_to_end(&mut bytes).unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(40));
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+             4\r\nhell\r\n4\r\no wo\r\n3\r\nrld\r\n0\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn delays_response() {
+        let server = MockServer::start_blocking().unwrap();
+        server.stub(
+            Stub::new(Matchers::new(), Response::default())
+                .with_delay(Delay::fixed(Duration::from_millis(100))),
+        );
+        let started = std::time::Instant::now();
+
+        send(server.address(), Request::default());
+
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[rstest]
+    #[case::specificity("/users/me", "me")]
+    #[case::priority("/users/42", "pinned")]
+    #[case::fallback("/posts", "fallback")]
+    fn picks_stub_by_priority_then_fallback(#[case] path: &str, #[case] expected: &str) {
+        let server = MockServer::start_blocking().unwrap();
+        server
+            .stub(Stub::new(Matchers::new(), Response::default().with_body("fallback")).fallback());
+        server.mock(
+            Matchers::new().path_template("/users/{id}"),
+            Response::default().with_body("template"),
+        );
+        server.mock(
+            Matchers::new().path("/users/me"),
+            Response::default().with_body("me"),
+        );
+        server.stub(
+            Stub::new(
+                Matchers::new().path_prefix("/users/4"),
+                Response::default().with_body("pinned"),
+            )
+            .with_priority(1),
+        );
+
+        let response = send(server.address(), Request::from(path));
+
+        assert_eq!(response.body, expected.into());
+    }
+
+    #[test]
+    fn fallback_keeps_near_misses() {
+        let server = MockServer::start_blocking().unwrap();
+        let fallback = server.stub(Stub::new(Matchers::new(), Response::new(503)).fallback());
+        server.mock(Matchers::new().path("/users"), Response::default());
+
+        assert_eq!(send(server.address(), Request::from("/user")).status, 503);
+
+        let received = server.received();
+        let entry = received.first().unwrap();
+        assert_eq!(entry.stub, Some(fallback));
+        assert_eq!(entry.near_misses.len(), 1);
+        assert_eq!(server.near_misses(&Request::from("/user")).len(), 1);
+    }
+
+    #[rstest]
+    #[case::echo("/echo", "ping", 200, "ping")]
+    #[case::computed("/items?page=3", "", 200, "items 21-30")]
+    #[case::panicking("/broken", "", 500, "Stub responder panicked")]
+    fn computes_response_with_closure(
+        #[case] url: &str,
+        #[case] body: &str,
+        #[case] status: u16,
+        #[case] expected: &str,
+    ) {
+        let server = MockServer::start_blocking().unwrap();
+        server.mock_fn(Matchers::new().path("/echo"), |request| {
+            Response::default().with_body(request.body.clone())
+        });
+        server.mock_fn(Matchers::new().path("/items"), |request| {
+            let page = request
+                .query
+                .get("page")
+                .and_then(|values| values[0].as_deref());
+            let page = page.and_then(|page| page.parse::<u32>().ok()).unwrap_or(1);
+            Response::default().with_body(format!("items {}-{}", page * 10 - 9, page * 10))
+        });
+        server.mock_fn(Matchers::new().path("/broken"), |_| panic!("responder bug"));
+
+        let response = send(server.address(), Request::from(url).with_body(body));
+
+        assert_eq!(response.status, status);
+        assert_eq!(response.body, expected.into());
+    }
+
+    #[cfg(feature = "admin")]
+    #[test]
+    fn manages_stubs_over_admin_api() {
+        let server = MockServer::start_blocking().unwrap();
+        server.set_admin(true);
+        let admin = |method: &str, path: &str, body: &str| {
+            let request = Request::from(path).with_method(method).with_body(body);
+            let response = send(server.address(), request);
+            let json = response.body.text().map(|text| text.to_string());
+            (response.status, json.unwrap_or_default())
+        };
+
+        let mapping = r#"{"matchers": [{"path": "/users"}], "response": {"status": 201}}"#;
+        assert_eq!(
+            admin("POST", "/__admin/mappings", mapping),
+            (201, r#"{"id":1}"#.into())
+        );
+        assert_eq!(send(server.address(), Request::from("/users")).status, 201);
+        assert_eq!(
+            admin("GET", "/__admin/mappings/1", ""),
+            (200, r#"{"id":1,"matchers":[{"path":"/users"}],"response":{"status":201,"version":"HTTP/1.1"}}"#.into())
+        );
+        let (status, near_misses) = admin("POST", "/__admin/near-misses", r#"{"path": "/user"}"#);
+        assert_eq!(status, 200);
+        assert!(near_misses.starts_with(r#"[{"description":"stub #1 differed only in: path"#));
+        send(
+            server.address(),
+            Request::from("/users").with_header("Authorization", "Bearer s3cret"),
+        );
+        let (_, requests) = admin("GET", "/__admin/requests", "");
+        assert!(requests.contains(r#""stub":1"#), "{requests}");
+        assert!(
+            requests.contains(r#""Authorization":"[REDACTED]""#) && !requests.contains("s3cret"),
+            "{requests}"
+        );
+        let replacement = mapping.replace("201", "202");
+        assert_eq!(admin("PUT", "/__admin/mappings/1", &replacement).0, 204);
+        assert_eq!(send(server.address(), Request::from("/users")).status, 202);
+        assert_eq!(admin("PUT", "/__admin/mappings/9", &replacement).0, 404);
+
+        assert_eq!(admin("DELETE", "/__admin/mappings/1", "").0, 204);
+        assert_eq!(admin("DELETE", "/__admin/mappings/1", "").0, 404);
+        assert_eq!(admin("GET", "/__admin/unknown", "").0, 404);
+        assert_eq!(admin("POST", "/__admin/mappings", "{").0, 400);
+        assert_eq!(admin("DELETE", "/__admin/requests", "").0, 204);
+        assert!(server.received().is_empty());
+    }
+
+    #[cfg(feature = "admin")]
+    #[test]
+    fn lists_stubs_over_admin_api() {
+        let server = MockServer::start_blocking().unwrap();
+        server.set_admin(true);
+        server.stub(
+            Stub::new(Matchers::new().path("/a"), Response::new(201))
+                .in_scenario(Scenario::new("flow").then("done"))
+                .with_delay(Delay::fixed(Duration::from_millis(5))),
+        );
+        server.stub(Stub::from_fn(Matchers::new().path("/b"), |_| {
+            Response::new(202)
+        }));
+
+        let response = send(server.address(), Request::from("/__admin/mappings"));
+
+        assert_eq!(
+            response.body.text().as_deref(),
+            Some(concat!(
+                r#"[{"delay":{"fixed":5},"id":1,"matchers":[{"path":"/a"}],"#,
+                r#""response":{"status":201,"version":"HTTP/1.1"},"#,
+                r#""scenario":{"name":"flow","new_state":"done"}},"#,
+                r#"{"error":"a stub answering from a closure has no mapping form","id":2}]"#
+            ))
+        );
+    }
+
+    #[test]
+    fn reconfigures_stubs_while_serving() {
+        let server = MockServer::start_blocking().unwrap();
+        let address = server.address();
+        let id = server.mock(Matchers::new().path("/a"), Response::new(200));
+
+        std::thread::scope(|scope| {
+            let clients = (0..4)
+                .map(|_| {
+                    scope.spawn(|| {
+                        (0..20)
+                            .map(|_| send(address, "/a".into()).status)
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            for status in 201..221 {
+                assert!(server.replace(
+                    id,
+                    Stub::new(Matchers::new().path("/a"), Response::new(status))
+                ));
+            }
+            for client in clients {
+                assert!(
+                    client
+                        .join()
+                        .unwrap()
+                        .iter()
+                        .all(|status| (200..221).contains(status))
+                );
+            }
+        });
+
+        assert_eq!(send(address, "/a".into()).status, 220);
+        assert!(server.remove(id));
+        assert!(!server.remove(id));
+        assert!(!server.replace(id, Stub::new(Matchers::new(), Response::new(200))));
+        assert_eq!(send(address, "/a".into()).status, 404);
+    }
+
+    #[test]
+    fn default_response_replaces_explanation() {
+        let server = MockServer::start_blocking().unwrap();
+        server.set_default_response(Some(Response::new(503)));
+
+        assert_eq!(send(server.address(), Request::default()).status, 503);
+    }
+
+    #[test]
+    fn keeps_connection_alive() {
+        let server = MockServer::start_blocking().unwrap();
+        server.mock(Matchers::new(), Response::default().with_body("ok"));
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+
+        stream
+            .write_all(b"GET /a HTTP/1.1\r\n\r\nHEAD /b HTTP/1.1\r\nConnection: close\r\n\r\n")
+            .unwrap();
+        let mut bytes = Vec::new();
+        stream.read_to_end(&mut bytes).unwrap();
+
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nokHTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_request() {
+        let server = MockServer::start_blocking().unwrap();
+        let mut stream = TcpStream::connect(server.address()).unwrap();
+
+        stream.write_all(b"BROKEN\r\n\r\n").unwrap();
+        let mut bytes = Vec::new();
+        stream.read_to_end(&mut bytes).unwrap();
+
+        assert_eq!(Response::parse_http(&bytes).unwrap().status, 400);
+    }
+}
@@ -0,0 +1,198 @@
+//! The JSON management API under `/__admin`, for harnesses that can't call into Rust.
+
+use super::{Mapping, Shared, Stub, StubId};
+use crate::matchers::Matchers;
+use crate::redaction::Redaction;
+use crate::request::Request;
+use crate::response::Response;
+use crate::router::Router;
+use serde_json::{Value, json};
+use std::sync::PoisonError;
+
+/// Where the admin endpoints live; requests under it never reach the stubs.
+const PREFIX: &str = "/__admin";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Endpoint {
+    ListMappings,
+    CreateMapping,
+    DeleteMappings,
+    GetMapping,
+    UpdateMapping,
+    DeleteMapping,
+    ListRequests,
+    DeleteRequests,
+    NearMisses,
+    Reset,
+}
+
+fn router() -> Router<Endpoint> {
+    let endpoint = |method: &str, path: &str| {
+        let path = format!("{PREFIX}{path}");
+        match path.contains('{') {
+            true => Matchers::new().method(method).path_template(&path),
+            false => Matchers::new().method(method).path(&path),
+        }
+    };
+    Router::new()
+        .with(Endpoint::ListMappings, endpoint("GET", "/mappings"))
+        .with(Endpoint::CreateMapping, endpoint("POST", "/mappings"))
+        .with(Endpoint::DeleteMappings, endpoint("DELETE", "/mappings"))
+        .with(Endpoint::GetMapping, endpoint("GET", "/mappings/{id}"))
+        .with(Endpoint::UpdateMapping, endpoint("PUT", "/mappings/{id}"))
+        .with(
+            Endpoint::DeleteMapping,
+            endpoint("DELETE", "/mappings/{id}"),
+        )
+        .with(Endpoint::ListRequests, endpoint("GET", "/requests"))
+        .with(Endpoint::DeleteRequests, endpoint("DELETE", "/requests"))
+        .with(Endpoint::NearMisses, endpoint("POST", "/near-misses"))
+        .with(Endpoint::Reset, endpoint("POST", "/reset"))
+}
+
+/// Answers `request` when it targets the admin API.
+pub(super) fn handle(shared: &Shared, request: &Request) -> Option<Response> {
+    let under_prefix = request
+        .path
+        .strip_prefix(PREFIX)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+    if !under_prefix {
+        return None;
+    }
+    let router = router();
+    let Ok(&endpoint) = router.route(request) else {
+        return Some(error(404, format!("no admin endpoint {request}")));
+    };
+    let id = router
+        .get(&endpoint)
+        .and_then(|matchers| matchers.captures(request).remove("id"))
+        .map(|id| id.parse().map(StubId));
+
+    Some(match (endpoint, id) {
+        (_, Some(Err(_))) => error(400, "mapping id must be a number".to_string()),
+        (Endpoint::ListMappings, _) => {
+            let stubs = shared.stubs();
+            let mappings = stubs
+                .iter()
+                .map(|(id, stub)| mapping_json(*id, stub))
+                .collect::<Vec<_>>();
+            Response::default().with_json(&mappings)
+        }
+        (Endpoint::CreateMapping, _) => match serde_json::from_slice::<Mapping>(&body(request)) {
+            Ok(mapping) => {
+                let id = shared.add(mapping.into());
+                Response::new(201).with_json(&json!({ "id": id.0 }))
+            }
+            Err(invalid) => error(400, format!("invalid mapping: {invalid}")),
+        },
+        (Endpoint::DeleteMappings, _) => {
+            shared.update_stubs(Vec::clear);
+            Response::new(204)
+        }
+        (Endpoint::GetMapping, Some(Ok(id))) => {
+            let stubs = shared.stubs();
+            match stubs.iter().find(|(stub_id, _)| *stub_id == id) {
+                Some((_, stub)) => Response::default().with_json(&mapping_json(id, stub)),
+                None => missing(id),
+            }
+        }
+        (Endpoint::UpdateMapping, Some(Ok(id))) => {
+            match serde_json::from_slice::<Mapping>(&body(request)) {
+                Ok(mapping) => match shared.replace(id, mapping.into()) {
+                    true => Response::new(204),
+                    false => missing(id),
+                },
+                Err(invalid) => error(400, format!("invalid mapping: {invalid}")),
+            }
+        }
+        (Endpoint::DeleteMapping, Some(Ok(id))) => match shared.remove(id) {
+            true => Response::new(204),
+            false => missing(id),
+        },
+        (Endpoint::ListRequests, _) => {
+            let received = shared
+                .received
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            let requests = received
+                .iter()
+                .map(|received| {
+                    json!({
+                        "request": Redaction::current().apply(&received.request),
+                        "stub": received.stub.map(|id| id.0),
+                        "near_misses": descriptions(&received.near_misses),
+                    })
+                })
+                .collect::<Vec<_>>();
+            Response::default().with_json(&requests)
+        }
+        (Endpoint::DeleteRequests, _) => {
+            shared
+                .received
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clear();
+            Response::new(204)
+        }
+        (Endpoint::NearMisses, _) => match serde_json::from_slice::<Request>(&body(request)) {
+            Ok(probe) => {
+                let near_misses = shared.near_misses(&probe);
+                Response::default().with_json(&descriptions(&near_misses))
+            }
+            Err(invalid) => error(400, format!("invalid request: {invalid}")),
+        },
+        (Endpoint::Reset, _) => {
+            shared.update_stubs(Vec::clear);
+            shared
+                .received
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clear();
+            shared
+                .states
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .reset();
+            Response::new(204)
+        }
+        (Endpoint::GetMapping | Endpoint::UpdateMapping | Endpoint::DeleteMapping, None) => {
+            error(400, "missing id".into())
+        }
+    })
+}
+
+fn body(request: &Request) -> Vec<u8> {
+    request.body.bytes().unwrap_or_default().into_owned()
+}
+
+/// The mapping of `stub` with its `id`, or why it can't be represented, e.g. for custom
+/// matchers or closure responses.
+fn mapping_json(id: StubId, stub: &Stub) -> Value {
+    let mapping = match Mapping::of(stub) {
+        Ok(mapping) => mapping,
+        Err(unrepresentable) => return json!({ "id": id.0, "error": unrepresentable }),
+    };
+    match serde_json::to_value(mapping) {
+        Ok(Value::Object(mut fields)) => {
+            fields.insert("id".into(), id.0.into());
+            Value::Object(fields)
+        }
+        Ok(_) => json!({ "id": id.0 }),
+        Err(unrepresentable) => json!({ "id": id.0, "error": unrepresentable.to_string() }),
+    }
+}
+
+fn descriptions(near_misses: &[super::NearMiss]) -> Vec<Value> {
+    near_misses
+        .iter()
+        .map(|near_miss| json!({ "stub": near_miss.stub.0, "description": near_miss.to_string() }))
+        .collect()
+}
+
+fn missing(id: StubId) -> Response {
+    error(404, format!("no mapping {id}"))
+}
+
+fn error(status: u16, message: String) -> Response {
+    Response::new(status).with_json(&json!({ "error": message }))
+}
@@ -0,0 +1,87 @@
+//! Response bodies delivered piecewise with chunked transfer encoding.
+
+use super::delay::{Delay, Rng};
+use std::time::Duration;
+
+/// How a stub's response body is split into chunks and paced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chunking {
+    /// Sizes of the leading chunks, the last one repeating for the rest of the body.
+    sizes: Vec<usize>,
+    /// Waited before each chunk but the first.
+    delay: Option<Delay>,
+}
+
+impl Chunking {
+    /// Chunks of `size` bytes, the last one possibly shorter.
+    pub fn new(size: usize) -> Self {
+        Self::sizes([size])
+    }
+
+    /// Chunks of the given sizes in turn, the last size repeating until the body is sent.
+    pub fn sizes<I: IntoIterator<Item = usize>>(sizes: I) -> Self {
+        Self {
+            sizes: sizes.into_iter().map(|size| size.max(1)).collect(),
+            delay: None,
+        }
+    }
+
+    /// Waits for `delay` between chunks, sampled anew for each.
+    pub fn with_delay(mut self, delay: Delay) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// The chunks of `body`, each with the time to wait before sending it.
+    pub(super) fn split(&self, body: &[u8], rng: &mut Rng) -> Vec<(Duration, Vec<u8>)> {
+        let mut chunks = Vec::new();
+        let mut rest = body;
+        let mut sizes = self.sizes.iter().copied();
+        let mut size = sizes.next().unwrap_or(usize::MAX);
+        while !rest.is_empty() {
+            let (chunk, tail) = rest.split_at(size.min(rest.len()));
+            let delay = match (&self.delay, chunks.is_empty()) {
+                (Some(delay), false) => delay.sample(rng),
+                _ => Duration::ZERO,
+            };
+            chunks.push((delay, chunk.to_vec()));
+            rest = tail;
+            size = sizes.next().unwrap_or(size);
+        }
+        chunks
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::even(Chunking::new(2), "abcd", &["ab", "cd"])]
+    #[case::remainder(Chunking::new(3), "abcd", &["abc", "d"])]
+    #[case::sizes(Chunking::sizes([1, 2]), "abcdef", &["a", "bc", "de", "f"])]
+    #[case::zero(Chunking::new(0), "ab", &["a", "b"])]
+    #[case::empty(Chunking::new(4), "", &[])]
+    fn splits_body(#[case] chunking: Chunking, #[case] body: &str, #[case] expected: &[&str]) {
+        let chunks = chunking.split(body.as_bytes(), &mut Rng::new(0));
+        let chunks = chunks
+            .iter()
+            .map(|(_, chunk)| std::str::from_utf8(chunk).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(chunks, expected);
+    }
+
+    #[test]
+    fn delays_all_but_first_chunk() {
+        let chunking = Chunking::new(1).with_delay(Delay::fixed(Duration::from_millis(5)));
+
+        let delays = chunking
+            .split(b"abc", &mut Rng::new(0))
+            .into_iter()
+            .map(|(delay, _)| delay.as_millis())
+            .collect::<Vec<_>>();
+
+        assert_eq!(delays, [0, 5, 5]);
+    }
+}
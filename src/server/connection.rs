@@ -0,0 +1,452 @@
+use super::{Shared, Stream};
+use crate::raw::{self, ParseLimits, RawRequestError};
+use crate::request::{Connection, HttpVersion, Request};
+use crate::response::Response;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpStream};
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+/// The connections being served, so dropping the server can wait for them to finish.
+#[derive(Debug, Default)]
+pub(super) struct Connections {
+    open: Mutex<Open>,
+    closed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct Open {
+    next_id: u64,
+    streams: HashMap<u64, TcpStream>,
+    draining: bool,
+}
+
+impl Connections {
+    /// Tracks `stream` until [closed](Self::close); `None` once draining, when it should be
+    /// dropped unserved.
+    pub(super) fn open(&self, stream: &TcpStream) -> Option<u64> {
+        let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        let stream = stream.try_clone().ok()?;
+        if open.draining {
+            return None;
+        }
+        open.next_id += 1;
+        let id = open.next_id;
+        open.streams.insert(id, stream);
+        Some(id)
+    }
+
+    pub(super) fn close(&self, id: u64) {
+        let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        open.streams.remove(&id);
+        self.closed.notify_all();
+    }
+
+    /// Stops reading further requests, so idle connections close right away and busy ones
+    /// once their response is written, and waits up to `timeout` for them before cutting
+    /// off those still streaming.
+    pub(super) fn drain(&self, timeout: Duration) {
+        let mut open = self.open.lock().unwrap_or_else(PoisonError::into_inner);
+        open.draining = true;
+        for stream in open.streams.values() {
+            let _ = stream.shutdown(Shutdown::Read);
+        }
+        let deadline = Instant::now() + timeout;
+        while !open.streams.is_empty() {
+            let left = deadline.saturating_duration_since(Instant::now());
+            if left.is_zero() {
+                break;
+            }
+            open = self
+                .closed
+                .wait_timeout(open, left)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        for stream in open.streams.values() {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+}
+
+/// Serves requests on `stream`, after a TLS handshake when the server terminates TLS, until
+/// the client closes it or asks to.
+pub(super) fn serve(shared: &Shared, stream: TcpStream) -> io::Result<()> {
+    let connection = Connection {
+        remote_addr: stream.peer_addr().ok(),
+        local_addr: stream.local_addr().ok(),
+        ..Default::default()
+    };
+    #[cfg(feature = "tls")]
+    if let Some(config) = &shared.tls {
+        let (mut stream, tls) = super::tls::accept(config.clone(), stream)?;
+        let connection = Connection {
+            tls: Some(tls),
+            ..connection
+        };
+        exchange(shared, &mut stream, &connection)?;
+        stream.conn.send_close_notify();
+        return stream.flush();
+    }
+    exchange(shared, stream, &connection)
+}
+
+fn exchange(
+    shared: &Shared,
+    mut stream: impl Read + Write,
+    connection: &Connection,
+) -> io::Result<()> {
+    let mut buffer = Vec::new();
+
+    while let Some(message) = read_message(&mut stream, &mut buffer, &shared.limits)? {
+        let parsed = message.and_then(|message| raw::parse(&message, &shared.limits));
+        let mut request = match parsed {
+            Ok(request) => request,
+            Err(error) => return stream.write_all(&refusal(&error).to_http_bytes()),
+        };
+        request.connection = Connection {
+            received_at: Some(SystemTime::now()),
+            ..connection.clone()
+        };
+        if connection.tls.is_some() {
+            request.scheme.get_or_insert_with(|| "https".to_string());
+        }
+
+        let reply = shared.respond(&request);
+        std::thread::sleep(reply.delay);
+        match reply.stream {
+            None => write_response(&mut stream, &request, &reply.response)?,
+            #[cfg(feature = "websocket")]
+            Some(Stream::WebSocket(script)) => {
+                write_response(&mut stream, &request, &reply.response)?;
+                return script.play(&mut stream, std::mem::take(&mut buffer));
+            }
+            Some(Stream::Events(events)) => {
+                let body = BodyWriter::start(&mut stream, &request, reply.response)?;
+                if let Some(mut body) = body {
+                    events.play(&mut body)?;
+                    if !body.finish()? {
+                        break;
+                    }
+                }
+            }
+            Some(Stream::Chunks(chunks)) => {
+                let body = BodyWriter::start(&mut stream, &request, reply.response)?;
+                if let Some(mut body) = body {
+                    for (delay, chunk) in chunks {
+                        std::thread::sleep(delay);
+                        body.write(&chunk)?;
+                    }
+                    if !body.finish()? {
+                        break;
+                    }
+                }
+            }
+        }
+        if !keeps_alive(&request) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+fn write_response(
+    stream: &mut impl Write,
+    request: &Request,
+    response: &Response,
+) -> io::Result<()> {
+    let mut bytes = response.to_http_bytes();
+    if request.method.eq_ignore_ascii_case("HEAD") && response.allows_body() {
+        let body = response.body.bytes().map_or(0, |body| body.len());
+        bytes.truncate(bytes.len() - body);
+    }
+    stream.write_all(&bytes)?;
+    stream.flush()
+}
+
+/// Writes a body incrementally after the response head: chunked, or delimited by closing the
+/// connection for HTTP/1.0 clients.
+pub(super) struct BodyWriter<'a, W: Write> {
+    stream: &'a mut W,
+    chunked: bool,
+}
+
+impl<'a, W: Write> BodyWriter<'a, W> {
+    /// Writes the head of `response`, whose own body is left out; `None` for `HEAD` requests,
+    /// which get the head only.
+    pub(super) fn start(
+        stream: &'a mut W,
+        request: &Request,
+        mut response: Response,
+    ) -> io::Result<Option<Self>> {
+        let chunked = request.http_version != HttpVersion::Http10;
+        response.body = Default::default();
+        response.headers.remove("Content-Length");
+        match chunked {
+            true => response.set_header("Transfer-Encoding", "chunked"),
+            false => response.set_header("Connection", "close"),
+        }
+        stream.write_all(&response.to_http_bytes())?;
+        stream.flush()?;
+        if request.method.eq_ignore_ascii_case("HEAD") {
+            return Ok(None);
+        }
+        Ok(Some(Self { stream, chunked }))
+    }
+
+    /// Sends `bytes` right away.
+    pub(super) fn write(&mut self, bytes: &[u8]) -> io::Result<()> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        if self.chunked {
+            write!(self.stream, "{:x}\r\n", bytes.len())?;
+            self.stream.write_all(bytes)?;
+            self.stream.write_all(b"\r\n")?;
+        } else {
+            self.stream.write_all(bytes)?;
+        }
+        self.stream.flush()
+    }
+
+    /// Ends the body, returning whether the connection can carry another response.
+    pub(super) fn finish(self) -> io::Result<bool> {
+        if self.chunked {
+            self.stream.write_all(b"0\r\n\r\n")?;
+            self.stream.flush()?;
+        }
+        Ok(self.chunked)
+    }
+}
+
+fn keeps_alive(request: &Request) -> bool {
+    let connection = request
+        .header("Connection")
+        .map(|value| value.to_ascii_lowercase());
+    match request.http_version {
+        HttpVersion::Http10 => connection.is_some_and(|value| value.contains("keep-alive")),
+        _ => !connection.is_some_and(|value| value.contains("close")),
+    }
+}
+
+/// Reads one complete request into a buffer of its own, keeping whatever follows it in
+/// `buffer` for the next call. `None` once the client closed the connection between requests;
+/// an error inside when the request breaks `limits` or can't be framed.
+fn read_message(
+    stream: &mut impl Read,
+    buffer: &mut Vec<u8>,
+    limits: &ParseLimits,
+) -> io::Result<Option<Result<Vec<u8>, RawRequestError>>> {
+    loop {
+        match message_length(buffer, limits) {
+            Ok(Some(length)) => return Ok(Some(Ok(buffer.drain(..length).collect()))),
+            Ok(None) => {}
+            Err(error) => return Ok(Some(Err(error))),
+        }
+
+        let mut chunk = [0; 8192];
+        match stream.read(&mut chunk)? {
+            0 if buffer.iter().all(u8::is_ascii_whitespace) => return Ok(None),
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            read => buffer.extend_from_slice(&chunk[..read]),
+        }
+    }
+}
+
+/// The answer to a request that couldn't be read, sent before closing the connection.
+fn refusal(error: &RawRequestError) -> Response {
+    let status = match error {
+        RawRequestError::TooManyHeaders(_) | RawRequestError::HeaderTooLarge(_) => 431,
+        RawRequestError::BodyTooLarge(_) => 413,
+        _ => 400,
+    };
+    Response::new(status)
+        .with_header("Connection", "close")
+        .with_header("Content-Type", "text/plain; charset=utf-8")
+        .with_body(error.to_string())
+}
+
+/// The length of the first complete request in `bytes`, if it has fully arrived. A request
+/// without `Content-Length` or chunked framing has no body.
+fn message_length(bytes: &[u8], limits: &ParseLimits) -> Result<Option<usize>, RawRequestError> {
+    let max_head = limits
+        .max_header_size
+        .saturating_mul(limits.max_headers.saturating_add(1));
+    let Some(head_end) = head_end(bytes) else {
+        return match bytes.len() > max_head {
+            true => Err(RawRequestError::HeaderTooLarge(limits.max_header_size)),
+            false => Ok(None),
+        };
+    };
+    let head = String::from_utf8_lossy(&bytes[..head_end]);
+    let mut chunked = false;
+    let mut content_length: Option<&str> = None;
+    for (name, value) in head.lines().filter_map(|line| line.split_once(':')) {
+        let (name, value) = (name.trim(), value.trim());
+        if name.eq_ignore_ascii_case("Transfer-Encoding") {
+            chunked |= value
+                .rsplit(',')
+                .next()
+                .is_some_and(|last| last.trim().eq_ignore_ascii_case("chunked"));
+        } else if name.eq_ignore_ascii_case("Content-Length") {
+            if content_length.is_some_and(|length| length != value) {
+                return Err(RawRequestError::InvalidContentLength(value.into()));
+            }
+            content_length = Some(value);
+        }
+    }
+    // An invalid length is left for the parser to reject.
+    let length = content_length.map_or(0, |length| length.parse().unwrap_or(0));
+
+    let body = &bytes[head_end..];
+    if chunked {
+        return match chunked_length(body) {
+            Some(length) => Ok(Some(head_end + length)),
+            // Chunk sizes and trailers count against the head allowance.
+            None if body.len() > max_head.saturating_add(limits.max_body_size) => {
+                Err(RawRequestError::BodyTooLarge(limits.max_body_size))
+            }
+            None => Ok(None),
+        };
+    }
+    if length > limits.max_body_size {
+        return Err(RawRequestError::BodyTooLarge(limits.max_body_size));
+    }
+    match body.len() >= length {
+        true => Ok(Some(head_end + length)),
+        false => Ok(None),
+    }
+}
+
+/// Where the blank line ending the head stops.
+fn head_end(bytes: &[u8]) -> Option<usize> {
+    let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace())?;
+    (start..bytes.len()).find_map(|idx| {
+        let rest = &bytes[idx..];
+        if rest.starts_with(b"\r\n\r\n") {
+            Some(idx + 4)
+        } else if rest.starts_with(b"\n\n") {
+            Some(idx + 2)
+        } else {
+            None
+        }
+    })
+}
+
+/// The length of a complete chunked body including its trailers, if it has fully arrived.
+fn chunked_length(body: &[u8]) -> Option<usize> {
+    let mut offset = 0;
+    loop {
+        let line_end = offset + body[offset..].iter().position(|byte| *byte == b'\n')?;
+        let line = std::str::from_utf8(&body[offset..line_end]).ok()?;
+        let size = usize::from_str_radix(line.split(';').next()?.trim(), 16).ok()?;
+        offset = line_end + 1;
+        if size == 0 {
+            // Trailers, up to an empty line.
+            loop {
+                let line_end = offset + body[offset..].iter().position(|byte| *byte == b'\n')?;
+                let empty = body[offset..line_end].iter().all(|byte| *byte == b'\r');
+                offset = line_end + 1;
+                if empty {
+                    return Some(offset);
+                }
+            }
+        }
+        offset = offset.checked_add(size)?;
+        let after = body.get(offset..)?;
+        offset += match after {
+            [b'\r', b'\n', ..] => 2,
+            [b'\n', ..] => 1,
+            _ => return None,
+        };
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::bodiless(b"GET / HTTP/1.1\r\n\r\nGET", Ok(Some(18)))]
+    #[case::partial_head(b"GET / HTTP/1.1\r\nHost: a", Ok(None))]
+    #[case::length(b"POST / HTTP/1.1\r\nContent-Length: 2\r\n\r\nhiGET", Ok(Some(40)))]
+    #[case::partial_body(b"POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhi", Ok(None))]
+    #[case::repeated_length(
+        b"POST / HTTP/1.1\r\nContent-Length: 2\r\nContent-Length: 2\r\n\r\nhi",
+        Ok(Some(59))
+    )]
+    #[case::conflicting_lengths(
+        b"POST / HTTP/1.1\r\nContent-Length: 2\r\nContent-Length: 5\r\n\r\nhi",
+        Err(RawRequestError::InvalidContentLength("5".into()))
+    )]
+    #[case::chunked(
+        b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nhi\r\n0\r\n\r\n",
+        Ok(Some(59))
+    )]
+    #[case::chunked_last(
+        b"POST / HTTP/1.1\r\nTransfer-Encoding: gzip, chunked\r\n\r\n0\r\n\r\n",
+        Ok(Some(58))
+    )]
+    #[case::chunked_suffix(
+        b"POST / HTTP/1.1\r\nTransfer-Encoding: notchunked\r\n\r\n0\r\n\r\n",
+        Ok(Some(50))
+    )]
+    #[case::partial_chunk(
+        b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nhi\r\n",
+        Ok(None)
+    )]
+    fn frames_messages(
+        #[case] bytes: &[u8],
+        #[case] expected: Result<Option<usize>, RawRequestError>,
+    ) {
+        assert_eq!(message_length(bytes, &ParseLimits::default()), expected);
+    }
+
+    #[rstest]
+    #[case::head(b"GET / HTTP/1.1\r\nHost: example.com", 431)]
+    #[case::length(b"POST / HTTP/1.1\r\nContent-Length: 9\r\n\r\n", 413)]
+    #[case::chunked(
+        &[&b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\nff\r\n"[..], &[b'x'; 64]].concat(),
+        413
+    )]
+    #[case::conflicting_lengths(
+        b"POST / HTTP/1.1\r\nContent-Length: 1\r\nContent-Length: 2\r\n\r\n",
+        400
+    )]
+    fn refuses_oversized_messages(#[case] bytes: &[u8], #[case] status: u16) {
+        let limits = ParseLimits {
+            max_headers: 1,
+            max_header_size: 16,
+            max_body_size: 8,
+            ..ParseLimits::default()
+        };
+        let mut input = bytes;
+        let mut buffer = Vec::new();
+
+        let error = read_message(&mut input, &mut buffer, &limits)
+            .unwrap()
+            .unwrap()
+            .unwrap_err();
+
+        assert_eq!(refusal(&error).status, status, "{error}");
+    }
+
+    #[test]
+    fn reads_pipelined_requests() {
+        let mut input: &[u8] = b"GET /a HTTP/1.1\r\n\r\nGET /b HTTP/1.1\r\n\r\n";
+        let mut buffer = Vec::new();
+        let limits = ParseLimits::default();
+
+        let first = read_message(&mut input, &mut buffer, &limits).unwrap();
+        let second = read_message(&mut input, &mut buffer, &limits).unwrap();
+
+        assert_eq!(first, Some(Ok(b"GET /a HTTP/1.1\r\n\r\n".to_vec())));
+        assert_eq!(second, Some(Ok(b"GET /b HTTP/1.1\r\n\r\n".to_vec())));
+        assert_eq!(
+            read_message(&mut input, &mut buffer, &limits).unwrap(),
+            None
+        );
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+/// How long a stub waits before writing its response.
+///
+/// With the `serde` feature, variants are externally tagged in `snake_case` with durations in
+/// whole milliseconds, e.g. `{"fixed": 250}` or `{"uniform": {"min": 10, "max": 20}}`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Delay {
+    Fixed(#[cfg_attr(feature = "serde", serde(with = "millis"))] Duration),
+    /// Uniformly distributed between `min` and `max`.
+    Uniform {
+        #[cfg_attr(feature = "serde", serde(with = "millis"))]
+        min: Duration,
+        #[cfg_attr(feature = "serde", serde(with = "millis"))]
+        max: Duration,
+    },
+    /// Log-normally distributed around `median`, with `sigma` the standard deviation of the
+    /// underlying normal distribution, for realistic long-tailed latencies.
+    LogNormal {
+        #[cfg_attr(feature = "serde", serde(with = "millis"))]
+        median: Duration,
+        sigma: f64,
+    },
+}
+
+/// Reads and writes a [`Duration`] as whole milliseconds.
+#[cfg(feature = "serde")]
+mod millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(u64::try_from(duration.as_millis()).unwrap_or(u64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+impl Delay {
+    pub fn fixed(duration: Duration) -> Self {
+        Delay::Fixed(duration)
+    }
+
+    pub fn uniform(min: Duration, max: Duration) -> Self {
+        Delay::Uniform { min, max }
+    }
+
+    pub fn log_normal(median: Duration, sigma: f64) -> Self {
+        Delay::LogNormal { median, sigma }
+    }
+
+    pub(super) fn sample(&self, rng: &mut Rng) -> Duration {
+        match *self {
+            Delay::Fixed(duration) => duration,
+            Delay::Uniform { min, max } if max <= min => min,
+            Delay::Uniform { min, max } => min + (max - min).mul_f64(rng.next_f64()),
+            Delay::LogNormal { median, sigma } => {
+                // Box-Muller; `1 - u` keeps the logarithm finite.
+                let (u, v) = (1.0 - rng.next_f64(), rng.next_f64());
+                let normal = (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos();
+                median.mul_f64((sigma * normal).exp().min(1e6))
+            }
+        }
+    }
+}
+
+/// A small seeded generator (SplitMix64), so random delays repeat across runs.
+#[derive(Debug, Clone)]
+pub(super) struct Rng(u64);
+
+impl Rng {
+    pub(super) fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    #[rstest]
+    #[case::fixed(Delay::fixed(MS * 5), MS * 5, MS * 5)]
+    #[case::uniform(Delay::uniform(MS * 10, MS * 20), MS * 10, MS * 20)]
+    #[case::empty_range(Delay::uniform(MS * 20, MS * 10), MS * 20, MS * 20)]
+    #[case::log_normal(Delay::log_normal(MS * 100, 0.5), MS, MS * 10_000)]
+    fn samples_within_bounds(#[case] delay: Delay, #[case] min: Duration, #[case] max: Duration) {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let sample = delay.sample(&mut rng);
+            assert!(
+                min <= sample && sample <= max,
+                "Sample {sample:?} out of bounds"
+            );
+        }
+    }
+
+    #[test]
+    fn seed_repeats_samples() {
+        let delay = Delay::log_normal(MS * 100, 1.0);
+        let samples = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..5).map(|_| delay.sample(&mut rng)).collect::<Vec<_>>()
+        };
+
+        assert_eq!(samples(1), samples(1));
+        assert_ne!(samples(1), samples(2));
+    }
+
+    #[test]
+    fn log_normal_centers_on_median() {
+        let delay = Delay::log_normal(MS * 100, 0.5);
+        let mut rng = Rng::new(3);
+        let mut samples = (0..1001)
+            .map(|_| delay.sample(&mut rng))
+            .collect::<Vec<_>>();
+        samples.sort();
+
+        let median = samples[500];
+        assert!(MS * 90 < median && median < MS * 110, "Median {median:?}");
+    }
+}
@@ -0,0 +1,85 @@
+use super::StubId;
+use crate::matchers::{MatchScore, MismatchReport};
+use crate::router::Candidate;
+
+/// How many near misses diagnostics list at most.
+pub(super) const NEAR_MISSES: usize = 3;
+
+/// A stub that didn't match a request, with how close it came and what differed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NearMiss {
+    pub stub: StubId,
+    pub score: MatchScore,
+    pub report: MismatchReport,
+}
+
+impl NearMiss {
+    /// The closest candidates first, at most [`NEAR_MISSES`].
+    pub(super) fn ranked(candidates: Vec<Candidate<'_, StubId>>) -> Vec<NearMiss> {
+        candidates
+            .into_iter()
+            .take(NEAR_MISSES)
+            .map(|candidate| NearMiss {
+                stub: *candidate.id,
+                score: candidate.score,
+                report: candidate.report,
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for NearMiss {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut mismatches = self.report.iter();
+        match (mismatches.next(), mismatches.next()) {
+            (Some(only), None) => write!(f, "stub {} differed only in: {only}", self.stub),
+            _ => {
+                write!(
+                    f,
+                    "stub {} missed {} of {} expectations",
+                    self.stub,
+                    self.score.failed(),
+                    self.score.total()
+                )?;
+                for mismatch in self.report.iter() {
+                    write!(f, "\n  - {mismatch}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::matchers::Matchers;
+    use crate::request::Request;
+    use crate::router::Router;
+
+    #[test]
+    fn ranks_and_explains_candidates() {
+        let mut router = Router::new();
+        router.add(StubId(1), Matchers::new().method("POST").path("/users"));
+        router.add(
+            StubId(2),
+            Matchers::new().path("/users").header_exists("X-Api-Key"),
+        );
+        router.add(StubId(3), Matchers::new().method("PUT").path("/posts"));
+        let request = Request::from("/users");
+
+        let near_misses = NearMiss::ranked(router.route(&request).unwrap_err());
+
+        assert_eq!(
+            near_misses
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            [
+                "stub #1 differed only in: method must be `POST`, but got \"GET\"",
+                "stub #2 differed only in: header `X-Api-Key` must be present, but it is missing",
+                "stub #3 missed 2 of 2 expectations\n  - method must be `PUT`, but got \"GET\"\n  - path must equal `/posts`, but got \"/users\"",
+            ]
+        );
+    }
+}
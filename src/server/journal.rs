@@ -0,0 +1,202 @@
+use super::{NearMiss, StubId};
+use crate::matchers::Matchers;
+use crate::request::Request;
+use crate::response::Response;
+use std::time::Duration;
+
+/// A request the server received, with the stub that answered it and the response sent.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Received {
+    pub request: Request,
+    /// `None` when no stub matched.
+    pub stub: Option<StubId>,
+    /// For requests unmatched or answered by a [fallback](super::Stub::fallback), the stubs
+    /// that came closest, best first.
+    pub near_misses: Vec<NearMiss>,
+    /// For streamed bodies, only the head and any chunked body.
+    pub response: Response,
+    /// How long the server took to answer, stub delay included.
+    pub elapsed: Duration,
+}
+
+/// A snapshot of received requests, oldest first, narrowed down by chaining queries such as
+/// `received().matching(&matchers).newest_first().limit(10)`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Journal {
+    entries: Vec<Received>,
+}
+
+impl Journal {
+    pub(super) fn new(entries: Vec<Received>) -> Self {
+        Self { entries }
+    }
+
+    /// Keeps the requests `matchers` accepts.
+    pub fn matching(mut self, matchers: &Matchers) -> Self {
+        self.entries
+            .retain(|entry| matchers.is_matched(&entry.request));
+        self
+    }
+
+    /// Keeps the requests answered by `stub`.
+    pub fn answered_by(mut self, stub: StubId) -> Self {
+        self.entries.retain(|entry| entry.stub == Some(stub));
+        self
+    }
+
+    /// Keeps the requests no stub matched.
+    pub fn unmatched(mut self) -> Self {
+        self.entries.retain(|entry| entry.stub.is_none());
+        self
+    }
+
+    pub fn newest_first(mut self) -> Self {
+        self.entries.reverse();
+        self
+    }
+
+    /// Drops the first `count` requests.
+    pub fn skip(mut self, count: usize) -> Self {
+        self.entries.drain(..count.min(self.entries.len()));
+        self
+    }
+
+    /// Keeps at most the first `count` requests.
+    pub fn limit(mut self, count: usize) -> Self {
+        self.entries.truncate(count);
+        self
+    }
+
+    /// The zero-based `page` of `size` requests.
+    pub fn page(self, page: usize, size: usize) -> Self {
+        self.skip(page.saturating_mul(size)).limit(size)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn first(&self) -> Option<&Received> {
+        self.entries.first()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Received> {
+        self.entries.iter()
+    }
+
+    pub fn requests(&self) -> impl Iterator<Item = &Request> {
+        self.entries.iter().map(|entry| &entry.request)
+    }
+
+    /// The exchanges as a HAR log, for browser dev tools and other HAR viewers. HAR files
+    /// tend to be shared, so values the current [`Redaction`](crate::Redaction) hides are
+    /// masked.
+    #[cfg(feature = "har")]
+    pub fn to_har(&self) -> serde_json::Value {
+        let policy = crate::Redaction::current();
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let started = entry
+                    .request
+                    .connection
+                    .received_at
+                    .unwrap_or_else(std::time::SystemTime::now);
+                let time = entry.elapsed.as_secs_f64() * 1_000.0;
+                serde_json::json!({
+                    "startedDateTime": crate::har::timestamp(started),
+                    "time": time,
+                    "request": policy.apply(&entry.request).to_har_request(),
+                    "response": policy.apply_response(&entry.response).to_har_response(),
+                    "cache": {},
+                    "timings": {"send": 0, "wait": time, "receive": 0},
+                })
+            })
+            .collect();
+        crate::har::log(entries)
+    }
+}
+
+impl IntoIterator for Journal {
+    type Item = Received;
+    type IntoIter = std::vec::IntoIter<Received>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Journal {
+    type Item = &'a Received;
+    type IntoIter = std::slice::Iter<'a, Received>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    fn journal() -> Journal {
+        Journal::new(
+            ["/a", "/b", "/c", "/d", "/e"]
+                .into_iter()
+                .enumerate()
+                .map(|(idx, path)| Received {
+                    request: path.into(),
+                    stub: (idx % 2 == 0).then_some(StubId(1)),
+                    near_misses: Vec::new(),
+                    response: Response::default(),
+                    elapsed: Duration::ZERO,
+                })
+                .collect(),
+        )
+    }
+
+    #[cfg(feature = "har")]
+    #[test]
+    fn har_masks_secrets() {
+        let entry = Received {
+            request: Request::from("/login?token=abc")
+                .with_header("Authorization", "Bearer s3cret")
+                .with_cookie("sid", "1"),
+            stub: None,
+            near_misses: Vec::new(),
+            response: Response::default().with_header("Set-Cookie", "sid=2"),
+            elapsed: Duration::ZERO,
+        };
+
+        let har = Journal::new(vec![entry]).to_har().to_string();
+
+        for secret in ["abc", "s3cret", "sid=1", "sid=2"] {
+            assert!(!har.contains(secret), "{secret} leaked in {har}");
+        }
+    }
+
+    #[rstest]
+    #[case::all(journal(), &["/a", "/b", "/c", "/d", "/e"])]
+    #[case::matching(journal().matching(&Matchers::new().path_regex("^/[a-c]$")), &["/a", "/b", "/c"])]
+    #[case::answered_by(journal().answered_by(StubId(1)), &["/a", "/c", "/e"])]
+    #[case::unmatched(journal().unmatched(), &["/b", "/d"])]
+    #[case::newest_first(journal().newest_first().limit(2), &["/e", "/d"])]
+    #[case::page(journal().page(1, 2), &["/c", "/d"])]
+    #[case::last_page(journal().page(2, 2), &["/e"])]
+    #[case::past_end(journal().skip(9), &[])]
+    fn queries(#[case] journal: Journal, #[case] paths: &[&str]) {
+        assert_eq!(
+            journal
+                .requests()
+                .map(|r| r.path.as_str())
+                .collect::<Vec<_>>(),
+            paths
+        );
+    }
+}
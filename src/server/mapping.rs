@@ -0,0 +1,162 @@
+use super::{Delay, Scenario, Stub};
+#[cfg(feature = "fixtures")]
+use crate::fixtures::{self, FixtureError, Format};
+use crate::matchers::Matchers;
+use crate::response::Response;
+#[cfg(feature = "fixtures")]
+use std::path::Path;
+
+/// The data-file form of a [`Stub`]: its matchers in their `serde` form and the response.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct Mapping {
+    pub matchers: Matchers,
+    #[serde(default)]
+    pub response: Response,
+    /// See [`Stub::templated`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub templated: bool,
+    /// See [`Stub::with_priority`].
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub priority: i32,
+    /// See [`Stub::fallback`].
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub fallback: bool,
+    /// See [`Stub::in_scenario`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scenario: Option<Scenario>,
+    /// See [`Stub::with_delay`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<Delay>,
+}
+
+impl Mapping {
+    /// The data-file form of `stub`, or why it has none: closure responses, streamed bodies
+    /// and WebSocket scripts can't be written as data.
+    pub(super) fn of(stub: &Stub) -> Result<Self, &'static str> {
+        if stub.responder.is_some() {
+            return Err("a stub answering from a closure has no mapping form");
+        }
+        if stub.events.is_some() {
+            return Err("a stub streaming server-sent events has no mapping form");
+        }
+        if stub.chunking.is_some() {
+            return Err("a stub streaming a chunked body has no mapping form");
+        }
+        #[cfg(feature = "websocket")]
+        if stub.websocket.is_some() {
+            return Err("a stub playing a WebSocket script has no mapping form");
+        }
+        Ok(Mapping {
+            matchers: stub.matchers.clone(),
+            response: stub.response.clone(),
+            templated: stub.templated,
+            priority: stub.matchers.priority(),
+            fallback: stub.fallback,
+            scenario: stub.scenario.clone(),
+            delay: stub.delay,
+        })
+    }
+}
+
+impl From<Mapping> for Stub {
+    fn from(mapping: Mapping) -> Self {
+        let mut stub =
+            Stub::new(mapping.matchers, mapping.response).with_priority(mapping.priority);
+        stub.templated = mapping.templated;
+        stub.fallback = mapping.fallback;
+        stub.scenario = mapping.scenario;
+        stub.delay = mapping.delay;
+        stub
+    }
+}
+
+fn is_zero(priority: &i32) -> bool {
+    *priority == 0
+}
+
+/// The stubs in a mapping file: a [`Mapping`] as JSON, or YAML for `yaml` and `yml` files,
+/// or a JSON WireMock mapping.
+#[cfg(feature = "fixtures")]
+pub(super) fn load(path: &Path) -> Result<Vec<Stub>, FixtureError> {
+    let invalid = |error: String| FixtureError::Invalid(path.into(), error);
+    let format = fixtures::format(path).filter(|format| *format != Format::Http);
+    let format = format.ok_or_else(|| FixtureError::UnsupportedFormat(path.into()))?;
+    let bytes = std::fs::read(path).map_err(|error| FixtureError::Io(path.into(), error))?;
+    if format == Format::Yaml {
+        let mapping: Mapping =
+            serde_yaml::from_slice(&bytes).map_err(|e| invalid(e.to_string()))?;
+        return Ok(vec![mapping.into()]);
+    }
+    let value: serde_json::Value =
+        serde_json::from_slice(&bytes).map_err(|e| invalid(e.to_string()))?;
+    if super::wiremock::is_wiremock(&value) {
+        return super::wiremock::stubs(&value).map_err(invalid);
+    }
+    let mapping: Mapping = serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?;
+    Ok(vec![mapping.into()])
+}
+
+/// The stubs of every mapping file directly in `dir`, in file name order.
+#[cfg(feature = "fixtures")]
+pub(super) fn load_dir(dir: &Path) -> Result<Vec<Stub>, FixtureError> {
+    let io = |error| FixtureError::Io(dir.into(), error);
+    let mut paths = std::fs::read_dir(dir)
+        .map_err(io)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(io)?;
+    paths.sort();
+    let mut stubs = Vec::new();
+    for path in paths.iter().filter(|path| {
+        path.is_file() && fixtures::format(path).is_some_and(|format| format != Format::Http)
+    }) {
+        stubs.extend(load(path)?);
+    }
+    Ok(stubs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+
+    #[test]
+    fn carries_scenario_and_delay() {
+        let value = json!({
+            "matchers": [{"path": "/users"}],
+            "response": {"status": 201, "version": "HTTP/1.1"},
+            "scenario": {"name": "users", "required_state": "Started", "new_state": "created"},
+            "delay": {"uniform": {"min": 10, "max": 20}},
+        });
+
+        let stub = Stub::from(serde_json::from_value::<Mapping>(value.clone()).unwrap());
+
+        assert_eq!(
+            stub.scenario,
+            Some(
+                Scenario::new("users")
+                    .when(Scenario::STARTED)
+                    .then("created")
+            )
+        );
+        assert_eq!(
+            stub.delay,
+            Some(Delay::uniform(
+                Duration::from_millis(10),
+                Duration::from_millis(20)
+            ))
+        );
+        assert_eq!(
+            serde_json::to_value(Mapping::of(&stub).unwrap()).unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn refuses_closure_responses() {
+        let stub = Stub::from_fn(Matchers::new(), |_| Response::new(202));
+
+        assert!(Mapping::of(&stub).is_err());
+    }
+}
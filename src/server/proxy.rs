@@ -0,0 +1,214 @@
+use crate::headers::Headers;
+use crate::request::{HttpVersion, Request};
+use crate::response::Response;
+use std::io::{self, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+/// Headers that only concern a single connection and aren't forwarded, along with those the
+/// `Connection` header names.
+const HOP_BY_HOP: [&str; 7] = [
+    "Connection",
+    "Keep-Alive",
+    "Proxy-Connection",
+    "TE",
+    "Trailer",
+    "Transfer-Encoding",
+    "Upgrade",
+];
+
+/// How long the upstream may take to answer before the proxy gives up.
+const UPSTREAM_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Which requests a [`Proxy`] forwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProxyMode {
+    /// Only requests no stub matched.
+    #[default]
+    Unmatched,
+    /// Every request, bypassing the stubs.
+    All,
+}
+
+/// The upstream URL isn't one a [`Proxy`] can forward to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProxyError {
+    /// Only `http` upstreams are supported, and `https` ones with the `tls` feature.
+    UnsupportedScheme(String),
+    MissingHost,
+}
+
+impl std::fmt::Display for ProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProxyError::UnsupportedScheme(scheme) => {
+                write!(f, "unsupported upstream scheme {scheme:?}")
+            }
+            ProxyError::MissingHost => f.write_str("upstream URL has no host"),
+        }
+    }
+}
+
+impl std::error::Error for ProxyError {}
+
+/// Forwards requests to a real upstream and returns its responses, so only some endpoints
+/// need stubs. With the `tls` feature the upstream may be `https`, verified against the
+/// system certificate bundle (the file named by `SSL_CERT_FILE`, or the usual locations)
+/// and any roots added with [`Proxy::trust_pem`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proxy {
+    host: String,
+    port: u16,
+    /// Trusted roots for an `https` upstream, `None` for plain `http`.
+    #[cfg(feature = "tls")]
+    roots: Option<Vec<rustls::pki_types::CertificateDer<'static>>>,
+    /// Prepended to forwarded paths, without a trailing slash.
+    base_path: String,
+    pub(crate) mode: ProxyMode,
+    rewrite_host: bool,
+}
+
+impl Proxy {
+    /// Forwards unmatched requests to `url`, e.g. `http://localhost:8080/api`; its path is
+    /// prepended to the forwarded ones.
+    pub fn new(url: &str) -> Result<Self, ProxyError> {
+        let upstream = Request::from(url);
+        let default_port = match upstream.scheme.as_deref() {
+            Some("http") => 80,
+            #[cfg(feature = "tls")]
+            Some("https") => 443,
+            Some(scheme) => return Err(ProxyError::UnsupportedScheme(scheme.to_string())),
+            None => return Err(ProxyError::MissingHost),
+        };
+        let host = upstream.host.ok_or(ProxyError::MissingHost)?;
+        Ok(Self {
+            #[cfg(feature = "tls")]
+            roots: (default_port == 443).then(super::tls::system_roots),
+            host,
+            port: upstream.port.unwrap_or(default_port),
+            base_path: upstream.path.trim_end_matches('/').to_string(),
+            mode: ProxyMode::default(),
+            rewrite_host: false,
+        })
+    }
+
+    pub fn with_mode(mut self, mode: ProxyMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Also trusts the certificates in `pem` for an `https` upstream, e.g. a self-signed one.
+    #[cfg(feature = "tls")]
+    pub fn trust_pem(mut self, pem: &[u8]) -> Result<Self, super::TlsError> {
+        let certificates = super::tls::parse_certificates(pem)?;
+        if let Some(roots) = &mut self.roots {
+            roots.extend(certificates);
+        }
+        Ok(self)
+    }
+
+    /// Sends the upstream's own authority as `Host` instead of the client's.
+    pub fn rewrite_host(mut self) -> Self {
+        self.rewrite_host = true;
+        self
+    }
+
+    /// The upstream response, or a `502 Bad Gateway` explaining why there is none.
+    pub(crate) fn forward(&self, request: &Request) -> Response {
+        self.exchange(request).unwrap_or_else(|error| {
+            Response::new(502)
+                .with_header("Content-Type", "text/plain; charset=utf-8")
+                .with_body(format!(
+                    "Proxying to {}:{} failed: {error}",
+                    self.host, self.port
+                ))
+        })
+    }
+
+    fn exchange(&self, request: &Request) -> io::Result<Response> {
+        let mut forwarded = request.clone();
+        strip_hop_by_hop(&mut forwarded.headers);
+        forwarded.headers.remove("Content-Length");
+        forwarded.set_header("Connection", "close");
+        if self.rewrite_host {
+            forwarded.set_header("Host", format!("{}:{}", self.host, self.port));
+        }
+        forwarded.path = format!("{}{}", self.base_path, request.path);
+        forwarded.http_version = HttpVersion::Http11;
+
+        let stream = TcpStream::connect((self.host.as_str(), self.port))?;
+        stream.set_read_timeout(Some(UPSTREAM_TIMEOUT))?;
+        let request = forwarded.to_http_bytes();
+        #[cfg(feature = "tls")]
+        let bytes = match &self.roots {
+            Some(roots) => send(super::tls::connect(&self.host, roots, stream)?, &request)?,
+            None => send(stream, &request)?,
+        };
+        #[cfg(not(feature = "tls"))]
+        let bytes = send(stream, &request)?;
+
+        let mut response = Response::parse_http(&bytes)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error.to_string()))?;
+        strip_hop_by_hop(&mut response.headers);
+        Ok(response)
+    }
+}
+
+/// Writes `request` and reads the response up to the upstream closing the connection.
+fn send(mut stream: impl Read + Write, request: &[u8]) -> io::Result<Vec<u8>> {
+    stream.write_all(request)?;
+    stream.flush()?;
+    let mut bytes = Vec::new();
+    if let Err(error) = stream.read_to_end(&mut bytes)
+        // Servers often close TLS connections without a `close_notify`.
+        && (error.kind() != io::ErrorKind::UnexpectedEof || bytes.is_empty())
+    {
+        return Err(error);
+    }
+    Ok(bytes)
+}
+
+/// Removes the [`HOP_BY_HOP`] headers and those the `Connection` header lists.
+fn strip_hop_by_hop(headers: &mut Headers) {
+    let listed: Vec<String> = headers
+        .get_all("Connection")
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect();
+    for name in HOP_BY_HOP
+        .iter()
+        .copied()
+        .chain(listed.iter().map(String::as_str))
+    {
+        headers.remove(name);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::plain("http://localhost:8080", Ok(("localhost", 8080, "")))]
+    #[case::base_path("http://api.test/v1/", Ok(("api.test", 80, "/v1")))]
+    #[cfg_attr(
+        not(feature = "tls"),
+        case::https("https://api.test", Err(ProxyError::UnsupportedScheme("https".into())))
+    )]
+    #[cfg_attr(feature = "tls", case::https("https://api.test", Ok(("api.test", 443, ""))))]
+    #[case::other_scheme("ftp://api.test", Err(ProxyError::UnsupportedScheme("ftp".into())))]
+    #[case::relative("/v1", Err(ProxyError::MissingHost))]
+    fn parses_upstream(#[case] url: &str, #[case] expected: Result<(&str, u16, &str), ProxyError>) {
+        let proxy = Proxy::new(url);
+        assert_eq!(
+            proxy
+                .as_ref()
+                .map(|proxy| (proxy.host.as_str(), proxy.port, proxy.base_path.as_str()))
+                .map_err(Clone::clone),
+            expected
+        );
+    }
+}
@@ -0,0 +1,245 @@
+use super::Mapping;
+use crate::fixtures::FixtureError;
+use crate::matchers::{CaptureOptions, Matchers};
+use crate::request::Request;
+use crate::response::Response;
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+/// Response headers that differ between otherwise identical exchanges, left out of
+/// recordings by default.
+const VOLATILE_HEADERS: [&str; 8] = [
+    "Date",
+    "Age",
+    "Expires",
+    "Last-Modified",
+    "ETag",
+    "X-Request-Id",
+    "X-Correlation-Id",
+    "X-Amzn-Trace-Id",
+];
+
+/// The file format recorded mappings are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MappingFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+/// Where and how proxied exchanges are recorded as [`Mapping`] files.
+#[derive(Debug, Clone)]
+pub struct Recording {
+    dir: PathBuf,
+    format: MappingFormat,
+    capture: CaptureOptions,
+    volatile_headers: Vec<String>,
+    volatile_params: Vec<String>,
+    replacements: Vec<(Regex, String)>,
+}
+
+impl Recording {
+    /// Records JSON mappings into `dir`, matching on method, path and query and leaving out
+    /// common volatile response headers such as `Date` and `X-Request-Id`.
+    pub fn new<P: Into<PathBuf>>(dir: P) -> Self {
+        Self {
+            dir: dir.into(),
+            format: MappingFormat::default(),
+            capture: CaptureOptions::default(),
+            volatile_headers: VOLATILE_HEADERS.map(String::from).to_vec(),
+            volatile_params: Vec::new(),
+            replacements: Vec::new(),
+        }
+    }
+
+    pub fn with_format(mut self, format: MappingFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Chooses which request parts the recorded matchers pin.
+    pub fn with_capture(mut self, capture: CaptureOptions) -> Self {
+        self.capture = capture;
+        self
+    }
+
+    /// Also leaves the response header `name` out.
+    pub fn volatile_header<S: Into<String>>(mut self, name: S) -> Self {
+        self.volatile_headers.push(name.into());
+        self
+    }
+
+    /// Doesn't match on the query parameter `name`, e.g. a timestamp or nonce.
+    pub fn volatile_param<S: Into<String>>(mut self, name: S) -> Self {
+        self.volatile_params.push(name.into());
+        self
+    }
+
+    /// Replaces every match of `pattern` in recorded response bodies and header values
+    /// with `replacement`, e.g. generated IDs with a fixed one or a template placeholder.
+    pub fn replace(mut self, pattern: Regex, replacement: &str) -> Self {
+        self.replacements.push((pattern, replacement.to_string()));
+        self
+    }
+
+    /// The mapping replaying `response` for requests like `request`.
+    pub(super) fn mapping(&self, request: &Request, response: &Response) -> Mapping {
+        let mut request = request.clone();
+        for name in &self.volatile_params {
+            request.query.shift_remove(name);
+        }
+
+        let mut response = response.clone();
+        for name in self
+            .volatile_headers
+            .iter()
+            .map(String::as_str)
+            .chain(["Content-Length"])
+        {
+            response.headers.remove(name);
+        }
+        let replace = |text: &str| {
+            self.replacements
+                .iter()
+                .fold(text.to_string(), |text, (pattern, replacement)| {
+                    pattern
+                        .replace_all(&text, replacement.as_str())
+                        .into_owned()
+                })
+        };
+        response.headers = response
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), replace(value)))
+            .collect();
+        if let Some(text) = response.body.text() {
+            response.body = replace(&text).into();
+        }
+
+        Mapping {
+            matchers: Matchers::from_request(&request, &self.capture),
+            response,
+            templated: false,
+            priority: 0,
+            fallback: false,
+            scenario: None,
+            delay: None,
+        }
+    }
+
+    /// Writes `mappings` into the recording directory, one file each, skipping repeats
+    /// of a request already recorded.
+    pub(super) fn write(&self, mappings: &[Mapping]) -> Result<Vec<PathBuf>, FixtureError> {
+        let io = |path: &Path| {
+            let path = path.to_path_buf();
+            move |error| FixtureError::Io(path, error)
+        };
+        std::fs::create_dir_all(&self.dir).map_err(io(&self.dir))?;
+
+        let mut written = Vec::new();
+        let mut seen = Vec::new();
+        for mapping in mappings {
+            let matchers = serde_json::to_string(&mapping.matchers);
+            let invalid = |error: String| FixtureError::Invalid(self.dir.clone(), error);
+            let matchers = matchers.map_err(|error| invalid(error.to_string()))?;
+            if seen.contains(&matchers) {
+                continue;
+            }
+            seen.push(matchers);
+
+            let (content, extension) = match self.format {
+                MappingFormat::Json => (
+                    serde_json::to_string_pretty(mapping).map_err(|e| invalid(e.to_string()))?,
+                    "json",
+                ),
+                MappingFormat::Yaml => (
+                    serde_yaml::to_string(mapping).map_err(|e| invalid(e.to_string()))?,
+                    "yaml",
+                ),
+            };
+            let path = self.dir.join(format!(
+                "{:03}-{}.{extension}",
+                seen.len(),
+                file_stem(mapping)
+            ));
+            std::fs::write(&path, content).map_err(io(&path))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
+
+/// A readable file name from the method and path the mapping matches, e.g. `get-users-42`.
+fn file_stem(mapping: &Mapping) -> String {
+    let mut stem = String::new();
+    for matcher in mapping.matchers.iter().filter_map(|m| m.as_matcher()) {
+        let part = match matcher {
+            crate::matchers::Matcher::Method(method) => method,
+            crate::matchers::Matcher::Path(path) => path,
+            _ => continue,
+        };
+        for word in part
+            .split(|ch: char| !ch.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+        {
+            if !stem.is_empty() {
+                stem.push('-');
+            }
+            stem.push_str(&word.to_ascii_lowercase());
+        }
+    }
+    match stem.is_empty() {
+        true => "mapping".to_string(),
+        false => stem,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::server::mapping::load_dir;
+
+    #[test]
+    fn normalizes_volatile_parts() {
+        let recording = Recording::new("unused")
+            .volatile_param("ts")
+            .replace(Regex::new("req-[0-9]+").unwrap(), "req-0");
+        let request = Request::from("/users/42?ts=1700000000&fields=name");
+        let response = Response::default()
+            .with_header("Date", "Tue, 14 Nov 2023 22:13:20 GMT")
+            .with_header("Content-Length", "27")
+            .with_header("X-Trace", "req-123")
+            .with_body(r#"{"id":42,"request":"req-9"}"#);
+
+        let mapping = recording.mapping(&request, &response);
+
+        assert!(
+            mapping
+                .matchers
+                .is_matched(&"/users/42?ts=1&fields=name".into())
+        );
+        assert!(!mapping.matchers.is_matched(&"/users/42?ts=1".into()));
+        assert_eq!(
+            mapping.response,
+            Response::default()
+                .with_header("X-Trace", "req-0")
+                .with_body(r#"{"id":42,"request":"req-0"}"#)
+        );
+        assert_eq!(file_stem(&mapping), "get-users-42");
+    }
+
+    #[test]
+    fn writes_and_loads_mappings() {
+        let dir = tempfile::tempdir().unwrap();
+        let recording = Recording::new(dir.path()).with_format(MappingFormat::Yaml);
+        let mapping = recording.mapping(&"/health".into(), &Response::new(204));
+
+        let written = recording.write(&[mapping.clone(), mapping]).unwrap();
+
+        assert_eq!(written, [dir.path().join("001-get-health.yaml")]);
+        let loaded = load_dir(dir.path()).unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert!(loaded[0].matchers.is_matched(&"/health".into()));
+        assert_eq!(loaded[0].response, Response::new(204));
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+/// Ties a stub to a state of a named scenario, a state machine shared by every stub in it.
+/// A stub with a required state only answers while its scenario is in that state, and a
+/// matched stub can move the scenario to another one. Scenarios start in
+/// [`Scenario::STARTED`].
+///
+/// With the `serde` feature it reads and writes as
+/// `{"name": "users", "required_state": "Started", "new_state": "created"}`, both states
+/// optional.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scenario {
+    pub(crate) name: String,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) required_state: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) new_state: Option<String>,
+}
+
+impl Scenario {
+    /// The state every scenario is in until a stub moves it.
+    pub const STARTED: &'static str = "Started";
+
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Self {
+            name: name.into(),
+            required_state: None,
+            new_state: None,
+        }
+    }
+
+    /// Only answers while the scenario is in `state`.
+    pub fn when<S: Into<String>>(mut self, state: S) -> Self {
+        self.required_state = Some(state.into());
+        self
+    }
+
+    /// Moves the scenario to `state` once the stub answered.
+    pub fn then<S: Into<String>>(mut self, state: S) -> Self {
+        self.new_state = Some(state.into());
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// The current state of each scenario; ones never moved are in [`Scenario::STARTED`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct States(HashMap<String, String>);
+
+impl States {
+    pub(super) fn get(&self, scenario: &str) -> &str {
+        self.0
+            .get(scenario)
+            .map_or(Scenario::STARTED, String::as_str)
+    }
+
+    pub(super) fn set(&mut self, scenario: &str, state: &str) {
+        self.0.insert(scenario.to_string(), state.to_string());
+    }
+
+    /// Whether a stub in `scenario` may answer now.
+    pub(super) fn allows(&self, scenario: Option<&Scenario>) -> bool {
+        scenario.is_none_or(|scenario| {
+            scenario
+                .required_state
+                .as_ref()
+                .is_none_or(|state| state == self.get(&scenario.name))
+        })
+    }
+
+    /// Applies the transition of a stub that answered.
+    pub(super) fn advance(&mut self, scenario: Option<&Scenario>) {
+        if let Some(Scenario {
+            name,
+            new_state: Some(state),
+            ..
+        }) = scenario
+        {
+            self.set(name, state);
+        }
+    }
+
+    pub(super) fn reset(&mut self) {
+        self.0.clear();
+    }
+}
@@ -0,0 +1,145 @@
+//! Server-Sent Events bodies: scripted events written to the client as they are due.
+
+use super::connection::BodyWriter;
+use crate::response::Response;
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// A single event of an [`EventStream`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SseEvent {
+    id: Option<String>,
+    event: Option<String>,
+    data: String,
+    retry: Option<Duration>,
+}
+
+impl SseEvent {
+    /// A `message` event; multi-line `data` is sent as one `data:` field per line.
+    pub fn new<S: Into<String>>(data: S) -> Self {
+        Self {
+            id: None,
+            event: None,
+            data: data.into(),
+            retry: None,
+        }
+    }
+
+    pub fn with_id<S: Into<String>>(mut self, id: S) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Sets the event type clients dispatch on instead of `message`.
+    pub fn with_event<S: Into<String>>(mut self, event: S) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Tells the client how long to wait before reconnecting.
+    pub fn with_retry(mut self, retry: Duration) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// The event in `text/event-stream` form, terminated by an empty line.
+    pub fn encode(&self) -> String {
+        let mut encoded = String::new();
+        if let Some(id) = &self.id {
+            encoded.push_str(&format!("id: {id}\n"));
+        }
+        if let Some(event) = &self.event {
+            encoded.push_str(&format!("event: {event}\n"));
+        }
+        if let Some(retry) = self.retry {
+            encoded.push_str(&format!("retry: {}\n", retry.as_millis()));
+        }
+        for line in self.data.split('\n') {
+            encoded.push_str(&format!("data: {line}\n"));
+        }
+        encoded.push('\n');
+        encoded
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Event(SseEvent),
+    Wait(Duration),
+}
+
+/// The events an SSE stub streams, step by step, after the response head. A repeating
+/// stream starts over until the client disconnects.
+#[derive(Debug, Clone, Default)]
+pub struct EventStream {
+    steps: Vec<Step>,
+    repeat: bool,
+}
+
+impl EventStream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn event(mut self, event: SseEvent) -> Self {
+        self.steps.push(Step::Event(event));
+        self
+    }
+
+    pub fn wait(mut self, duration: Duration) -> Self {
+        self.steps.push(Step::Wait(duration));
+        self
+    }
+
+    /// Plays the steps again and again instead of ending the body after the last one.
+    pub fn repeat(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
+    /// `response` with the event stream's content type and caching defaults filled in.
+    pub(super) fn head(response: Response) -> Response {
+        let mut response = response;
+        for (name, value) in [
+            ("Content-Type", "text/event-stream"),
+            ("Cache-Control", "no-cache"),
+        ] {
+            if response.header(name).is_none() {
+                response.set_header(name, value);
+            }
+        }
+        response
+    }
+
+    pub(super) fn play<W: Write>(&self, body: &mut BodyWriter<'_, W>) -> io::Result<()> {
+        loop {
+            for step in &self.steps {
+                match step {
+                    Step::Event(event) => body.write(event.encode().as_bytes())?,
+                    Step::Wait(duration) => std::thread::sleep(*duration),
+                }
+            }
+            // An empty stream would otherwise spin without ever noticing the client left.
+            if !self.repeat || self.steps.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::data(SseEvent::new("hello"), "data: hello\n\n")]
+    #[case::multiline(SseEvent::new("a\nb"), "data: a\ndata: b\n\n")]
+    #[case::fields(
+        SseEvent::new("{}").with_id("7").with_event("update").with_retry(Duration::from_secs(3)),
+        "id: 7\nevent: update\nretry: 3000\ndata: {}\n\n"
+    )]
+    fn encodes_events(#[case] event: SseEvent, #[case] expected: &str) {
+        assert_eq!(event.encode(), expected);
+    }
+}
@@ -0,0 +1,168 @@
+use super::chunked::Chunking;
+use super::delay::Delay;
+use super::scenario::Scenario;
+use super::sse::EventStream;
+use super::template::{self, Context};
+#[cfg(feature = "websocket")]
+use super::websocket::WebSocket;
+use crate::matchers::Matchers;
+use crate::request::Request;
+use crate::response::Response;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::Arc;
+
+/// Identifies a stub registered on a [`MockServer`](super::MockServer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StubId(pub(crate) u64);
+
+impl std::fmt::Display for StubId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Computes a stub's response from the matched request.
+#[derive(Clone)]
+pub(crate) struct Responder(Arc<dyn Fn(&Request) -> Response + Send + Sync>);
+
+impl std::fmt::Debug for Responder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Responder")
+    }
+}
+
+/// A canned answer: requests passing `matchers` get `response`.
+#[derive(Debug, Clone)]
+pub struct Stub {
+    pub(crate) matchers: Matchers,
+    pub(crate) response: Response,
+    pub(crate) templated: bool,
+    /// Answers in place of `response` when set.
+    pub(crate) responder: Option<Responder>,
+    pub(crate) scenario: Option<Scenario>,
+    pub(crate) delay: Option<Delay>,
+    pub(crate) fallback: bool,
+    /// Streamed as the body instead of the response's own.
+    pub(crate) events: Option<EventStream>,
+    pub(crate) chunking: Option<Chunking>,
+    /// Played on connections upgraded by a matching WebSocket handshake.
+    #[cfg(feature = "websocket")]
+    pub(crate) websocket: Option<WebSocket>,
+}
+
+impl Stub {
+    pub fn new(matchers: Matchers, response: Response) -> Self {
+        Self {
+            matchers,
+            response,
+            templated: false,
+            responder: None,
+            scenario: None,
+            delay: None,
+            fallback: false,
+            events: None,
+            chunking: None,
+            #[cfg(feature = "websocket")]
+            websocket: None,
+        }
+    }
+
+    /// Upgrades matching WebSocket handshakes and plays `script` on the connection; other
+    /// matching requests get `426 Upgrade Required`.
+    #[cfg(feature = "websocket")]
+    pub fn websocket(matchers: Matchers, script: WebSocket) -> Self {
+        let response = Response::new(426)
+            .with_header("Upgrade", "websocket")
+            .with_header("Connection", "Upgrade");
+        Self {
+            websocket: Some(script),
+            ..Self::new(matchers, response)
+        }
+    }
+
+    /// Answers requests passing `matchers` with whatever `responder` computes from them, e.g.
+    /// echoing the body or paginating; a panicking responder yields `500`.
+    pub fn from_fn<F>(matchers: Matchers, responder: F) -> Self
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        Self {
+            responder: Some(Responder(Arc::new(responder))),
+            ..Self::new(matchers, Response::default())
+        }
+    }
+
+    /// Ranks the stub among others matching the same request: higher wins, then the more
+    /// specific matchers, then the stub added first. Sets [`Matchers::priority`].
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.matchers.set_priority(priority);
+        self
+    }
+
+    /// Answers only requests no other stub matches, e.g. a catch-all with empty matchers.
+    pub fn fallback(mut self) -> Self {
+        self.fallback = true;
+        self
+    }
+
+    /// Waits for `delay` before writing the response.
+    pub fn with_delay(mut self, delay: Delay) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Streams `events` as Server-Sent Events in place of the response body; the response
+    /// status and headers are kept, with `Content-Type: text/event-stream` by default.
+    pub fn with_events(mut self, events: EventStream) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Sends the response body with chunked transfer encoding, split and paced by
+    /// `chunking`; ignored when the stub streams [events](Stub::with_events).
+    pub fn with_chunking(mut self, chunking: Chunking) -> Self {
+        self.chunking = Some(chunking);
+        self
+    }
+
+    /// Makes the stub part of a [`Scenario`], answering only in its required state.
+    pub fn in_scenario(mut self, scenario: Scenario) -> Self {
+        self.scenario = Some(scenario);
+        self
+    }
+
+    /// Fills `{{…}}` placeholders in the response headers and body from the matched request,
+    /// see the [`server`](super) module docs for the syntax.
+    pub fn templated(mut self) -> Self {
+        self.templated = true;
+        self
+    }
+
+    pub fn matchers(&self) -> &Matchers {
+        &self.matchers
+    }
+
+    /// The canned response, left empty by [`Stub::from_fn`].
+    pub fn response(&self) -> &Response {
+        &self.response
+    }
+
+    /// The response for a request the matchers accepted.
+    pub(crate) fn respond(&self, request: &Request) -> Response {
+        if let Some(Responder(responder)) = &self.responder {
+            return catch_unwind(AssertUnwindSafe(|| responder(request))).unwrap_or_else(|_| {
+                Response::new(500)
+                    .with_header("Content-Type", "text/plain; charset=utf-8")
+                    .with_body("Stub responder panicked")
+            });
+        }
+        if !self.templated {
+            return self.response.clone();
+        }
+        let context = Context {
+            request,
+            captures: self.matchers.captures(request),
+        };
+        template::render_response(&self.response, &context)
+    }
+}
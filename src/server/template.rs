@@ -0,0 +1,130 @@
+use crate::request::Request;
+use crate::response::Response;
+use std::collections::HashMap;
+
+/// What placeholders are resolved against.
+pub(super) struct Context<'a> {
+    pub(super) request: &'a Request,
+    pub(super) captures: HashMap<String, String>,
+}
+
+impl Context<'_> {
+    /// The value of a placeholder expression; `None` for unknown expressions.
+    fn resolve(&self, expression: &str) -> Option<String> {
+        let request = self.request;
+        if let Some(path) = expression.strip_prefix("jsonPath ") {
+            return Some(self.json_path(path.trim()));
+        }
+        let value = match expression.split_once('.') {
+            None => match expression {
+                "method" => Some(request.method.clone()),
+                "path" => Some(request.path.clone()),
+                "url" => Some(request.target()),
+                "body" => request.body.text().map(|text| text.into_owned()),
+                _ => return None,
+            },
+            Some(("path", name)) => self.captures.get(name).cloned(),
+            Some(("query", name)) => request
+                .query
+                .get(name)
+                .and_then(|values| values.first()?.clone()),
+            Some(("header", name)) => request.header(name).cloned(),
+            Some(_) => return None,
+        };
+        Some(value.unwrap_or_default())
+    }
+
+    #[cfg(feature = "json")]
+    fn json_path(&self, path: &str) -> String {
+        let Some(body) = self.request.body.bytes() else {
+            return String::new();
+        };
+        let Ok(body) = serde_json::from_slice::<serde_json::Value>(&body) else {
+            return String::new();
+        };
+        let Ok(path) = serde_json_path::JsonPath::parse(path) else {
+            return String::new();
+        };
+        match path.query(&body).first() {
+            Some(serde_json::Value::String(text)) => text.clone(),
+            Some(value) => value.to_string(),
+            None => String::new(),
+        }
+    }
+
+    #[cfg(not(feature = "json"))]
+    fn json_path(&self, _path: &str) -> String {
+        String::new()
+    }
+}
+
+/// Fills every `{{…}}` placeholder in `text`.
+pub(super) fn render(text: &str, context: &Context<'_>) -> String {
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        rendered.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find("}}") else {
+            rest = &rest[start..];
+            break;
+        };
+        let placeholder = &rest[start..start + end + 2];
+        match context.resolve(placeholder[2..placeholder.len() - 2].trim()) {
+            Some(value) => rendered.push_str(&value),
+            None => rendered.push_str(placeholder),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Renders the header values and text body of `response`; binary bodies are sent as is.
+pub(super) fn render_response(response: &Response, context: &Context<'_>) -> Response {
+    let mut rendered = response.clone();
+    rendered.headers = response
+        .headers
+        .iter()
+        .map(|(name, value)| (name.clone(), render(value, context)))
+        .collect();
+    if let Some(text) = response.body.text() {
+        rendered.body = render(&text, context).into();
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::request_line(
+        "{{method}} {{ path }} {{url}}",
+        "POST /users/42 /users/42?page=2&flag"
+    )]
+    #[case::capture("id={{path.id}}", "id=42")]
+    #[case::query("{{query.page}}|{{query.flag}}|{{query.missing}}", "2||")]
+    #[case::header("{{header.x-trace}}", "abc")]
+    #[case::body("{{body}}", r#"{"name":"Bob","age":7}"#)]
+    #[case::unknown("{{nope}} {{path.id", "{{nope}} {{path.id")]
+    #[cfg_attr(
+        feature = "json",
+        case::json_path(
+            r#"{"echo": "{{jsonPath $.name}}", "age": {{jsonPath $.age}}}"#,
+            r#"{"echo": "Bob", "age": 7}"#
+        )
+    )]
+    fn renders(#[case] template: &str, #[case] expected: &str) {
+        let request = Request::from("/users/42?page=2&flag")
+            .with_method("POST")
+            .with_header("X-Trace", "abc")
+            .with_body(r#"{"name":"Bob","age":7}"#);
+        let context = Context {
+            request: &request,
+            captures: HashMap::from([("id".to_string(), "42".to_string())]),
+        };
+
+        assert_eq!(render(template, &context), expected);
+    }
+}
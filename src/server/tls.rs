@@ -0,0 +1,211 @@
+use crate::request::TlsInfo;
+use rustls::pki_types::ServerName;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::{
+    ClientConfig, ClientConnection, RootCertStore, ServerConfig, ServerConnection, StreamOwned,
+};
+use std::io;
+use std::net::TcpStream;
+use std::sync::Arc;
+
+/// Why a [`TlsConfig`] couldn't be built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TlsError {
+    /// Generating the self-signed certificate failed.
+    Generate(String),
+    /// The certificate chain or key isn't valid PEM.
+    InvalidPem(String),
+    /// rustls rejected the certificate and key.
+    Config(String),
+}
+
+impl std::fmt::Display for TlsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlsError::Generate(error) => write!(f, "failed to generate certificate: {error}"),
+            TlsError::InvalidPem(error) => write!(f, "invalid PEM: {error}"),
+            TlsError::Config(error) => write!(f, "invalid TLS configuration: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {}
+
+impl From<TlsError> for io::Error {
+    fn from(error: TlsError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidInput, error)
+    }
+}
+
+/// The certificate and key a [`MockServer`](super::MockServer) terminates TLS with.
+#[derive(Clone)]
+pub struct TlsConfig {
+    certificates: Vec<CertificateDer<'static>>,
+    key: Arc<PrivateKeyDer<'static>>,
+}
+
+impl TlsConfig {
+    /// A fresh self-signed certificate for `localhost` and `127.0.0.1`; clients have to
+    /// trust [`TlsConfig::certificate_pem`].
+    pub fn self_signed() -> Result<Self, TlsError> {
+        let generate = |error: rcgen::Error| TlsError::Generate(error.to_string());
+        let names = ["localhost".to_string(), "127.0.0.1".to_string()];
+        let certified = rcgen::generate_simple_self_signed(names).map_err(generate)?;
+        let key = PrivatePkcs8KeyDer::from(certified.signing_key.serialize_der());
+        Ok(Self {
+            certificates: vec![certified.cert.der().clone()],
+            key: Arc::new(key.into()),
+        })
+    }
+
+    /// A PEM certificate chain, leaf first, and its PEM private key.
+    pub fn from_pem(certificates: &[u8], key: &[u8]) -> Result<Self, TlsError> {
+        let invalid =
+            |error: rustls::pki_types::pem::Error| TlsError::InvalidPem(error.to_string());
+        let certificates = parse_certificates(certificates)?;
+        if certificates.is_empty() {
+            return Err(TlsError::InvalidPem("no certificate found".into()));
+        }
+        let key = PrivateKeyDer::from_pem_slice(key).map_err(invalid)?;
+        Ok(Self {
+            certificates,
+            key: Arc::new(key),
+        })
+    }
+
+    /// The leaf certificate as PEM, for clients' trust stores.
+    pub fn certificate_pem(&self) -> String {
+        use base64::Engine;
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&self.certificates[0]);
+        let mut pem = String::from("-----BEGIN CERTIFICATE-----\n");
+        for line in encoded.as_bytes().chunks(64) {
+            pem.push_str(&String::from_utf8_lossy(line));
+            pem.push('\n');
+        }
+        pem.push_str("-----END CERTIFICATE-----\n");
+        pem
+    }
+
+    /// The leaf certificate in DER form.
+    pub fn certificate_der(&self) -> &[u8] {
+        &self.certificates[0]
+    }
+
+    pub(super) fn server_config(&self) -> Result<Arc<ServerConfig>, TlsError> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let mut config = ServerConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .and_then(|builder| {
+                builder
+                    .with_no_client_auth()
+                    .with_single_cert(self.certificates.clone(), self.key.clone_key())
+            })
+            .map_err(|error| TlsError::Config(error.to_string()))?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        Ok(Arc::new(config))
+    }
+}
+
+/// The private key is left out.
+impl std::fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TlsConfig")
+            .field("certificates", &self.certificates.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Completes the handshake on `stream`.
+pub(super) fn accept(
+    config: Arc<ServerConfig>,
+    mut stream: TcpStream,
+) -> io::Result<(StreamOwned<ServerConnection, TcpStream>, TlsInfo)> {
+    let invalid = |error: rustls::Error| io::Error::new(io::ErrorKind::InvalidData, error);
+    let mut connection = ServerConnection::new(config).map_err(invalid)?;
+    while connection.is_handshaking() {
+        connection.complete_io(&mut stream)?;
+    }
+    let tls = TlsInfo {
+        server_name: connection.server_name().map(String::from),
+        alpn_protocol: connection
+            .alpn_protocol()
+            .map(|protocol| String::from_utf8_lossy(protocol).into_owned()),
+    };
+    Ok((StreamOwned::new(connection, stream), tls))
+}
+
+/// Where certificate bundles usually live, after the file named by `SSL_CERT_FILE`.
+const SYSTEM_BUNDLES: [&str; 4] = [
+    "/etc/ssl/certs/ca-certificates.crt",
+    "/etc/pki/tls/certs/ca-bundle.crt",
+    "/etc/ssl/ca-bundle.pem",
+    "/etc/ssl/cert.pem",
+];
+
+/// The certificates of the first system bundle found; none when there is no bundle.
+pub(super) fn system_roots() -> Vec<CertificateDer<'static>> {
+    let configured = std::env::var_os("SSL_CERT_FILE").map(std::path::PathBuf::from);
+    configured
+        .into_iter()
+        .chain(SYSTEM_BUNDLES.map(Into::into))
+        .find_map(|path| std::fs::read(path).ok())
+        .map(|pem| parse_certificates(&pem).unwrap_or_default())
+        .unwrap_or_default()
+}
+
+/// Every certificate in a PEM bundle.
+pub(super) fn parse_certificates(pem: &[u8]) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    CertificateDer::pem_slice_iter(pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| TlsError::InvalidPem(error.to_string()))
+}
+
+/// Completes a client handshake with `host` on `stream`, verifying it against `roots`.
+pub(super) fn connect(
+    host: &str,
+    roots: &[CertificateDer<'static>],
+    mut stream: TcpStream,
+) -> io::Result<StreamOwned<ClientConnection, TcpStream>> {
+    let invalid = |error: rustls::Error| io::Error::new(io::ErrorKind::InvalidData, error);
+    let mut store = RootCertStore::empty();
+    store.add_parsable_certificates(roots.iter().cloned());
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(invalid)?
+        .with_root_certificates(store)
+        .with_no_client_auth();
+    let name = ServerName::try_from(host.trim_matches(['[', ']']).to_string())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+    let mut connection = ClientConnection::new(Arc::new(config), name).map_err(invalid)?;
+    while connection.is_handshaking() {
+        connection.complete_io(&mut stream)?;
+    }
+    Ok(StreamOwned::new(connection, stream))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn loads_pem_certificate_and_key() {
+        let certified = rcgen::generate_simple_self_signed(["localhost".to_string()]).unwrap();
+        let certificate = certified.cert.pem();
+
+        let config = TlsConfig::from_pem(
+            certificate.as_bytes(),
+            certified.signing_key.serialize_pem().as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(config.certificate_pem(), certificate.replace("\r\n", "\n"));
+        assert!(config.server_config().is_ok());
+        assert!(matches!(
+            TlsConfig::from_pem(b"", b""),
+            Err(TlsError::InvalidPem(_))
+        ));
+    }
+}
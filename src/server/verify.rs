@@ -0,0 +1,185 @@
+use super::Received;
+use super::diagnosis::NEAR_MISSES;
+use crate::matchers::{Matchers, MismatchReport};
+use crate::request::Request;
+
+/// How many received requests a [`MockServer::verify`](super::MockServer::verify) call
+/// expects to match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Times {
+    min: usize,
+    max: Option<usize>,
+}
+
+impl Times {
+    pub fn exactly(count: usize) -> Self {
+        Self {
+            min: count,
+            max: Some(count),
+        }
+    }
+
+    pub fn once() -> Self {
+        Self::exactly(1)
+    }
+
+    pub fn never() -> Self {
+        Self::exactly(0)
+    }
+
+    pub fn at_least(count: usize) -> Self {
+        Self {
+            min: count,
+            max: None,
+        }
+    }
+
+    pub fn at_most(count: usize) -> Self {
+        Self {
+            min: 0,
+            max: Some(count),
+        }
+    }
+
+    /// Between `min` and `max`, both inclusive.
+    pub fn between(min: usize, max: usize) -> Self {
+        Self {
+            min,
+            max: Some(max),
+        }
+    }
+
+    pub fn contains(&self, count: usize) -> bool {
+        self.min <= count && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+impl std::fmt::Display for Times {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let times = |count: usize| match count {
+            1 => "1 time".to_string(),
+            count => format!("{count} times"),
+        };
+        match (self.min, self.max) {
+            (min, Some(max)) if min == max => write!(f, "exactly {}", times(min)),
+            (0, Some(max)) => write!(f, "at most {}", times(max)),
+            (min, None) => write!(f, "at least {}", times(min)),
+            (min, Some(max)) => write!(f, "between {min} and {}", times(max)),
+        }
+    }
+}
+
+/// A verification found a different number of matching requests than expected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerificationError {
+    pub expected: Times,
+    pub actual: usize,
+    /// The unmatched requests that came closest, best first, with what they missed.
+    pub near_misses: Vec<(Request, MismatchReport)>,
+}
+
+impl std::fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "expected matching requests {}, received {}",
+            self.expected, self.actual
+        )?;
+        for (request, report) in &self.near_misses {
+            write!(f, "\nnear miss {request}: {report}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+/// Checks how many of `received` match `matchers`.
+pub(super) fn verify(
+    received: &[Received],
+    matchers: &Matchers,
+    expected: Times,
+) -> Result<(), VerificationError> {
+    let received = received.iter().map(|entry| &entry.request);
+    let actual = received
+        .clone()
+        .filter(|request| matchers.is_matched(request))
+        .count();
+    if expected.contains(actual) {
+        return Ok(());
+    }
+
+    let mut misses = received
+        .filter_map(|request| {
+            let report = matchers.validate(request).err()?;
+            Some((matchers.score(request), request, report))
+        })
+        .collect::<Vec<_>>();
+    // Stable, so equally close requests stay in arrival order.
+    misses.sort_by(|(a, ..), (b, ..)| b.cmp(a));
+    let near_misses = misses
+        .into_iter()
+        .take(NEAR_MISSES)
+        .map(|(_, request, report)| (request.clone(), report))
+        .collect();
+
+    Err(VerificationError {
+        expected,
+        actual,
+        near_misses,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::response::Response;
+    use rstest::rstest;
+    use std::time::Duration;
+
+    #[rstest]
+    #[case::exactly(Times::exactly(2), "exactly 2 times", &[2], &[1, 3])]
+    #[case::once(Times::once(), "exactly 1 time", &[1], &[0, 2])]
+    #[case::never(Times::never(), "exactly 0 times", &[0], &[1])]
+    #[case::at_least(Times::at_least(1), "at least 1 time", &[1, 9], &[0])]
+    #[case::at_most(Times::at_most(2), "at most 2 times", &[0, 2], &[3])]
+    #[case::between(Times::between(1, 3), "between 1 and 3 times", &[1, 3], &[0, 4])]
+    fn counts(
+        #[case] times: Times,
+        #[case] display: &str,
+        #[case] inside: &[usize],
+        #[case] outside: &[usize],
+    ) {
+        assert_eq!(times.to_string(), display);
+        assert!(inside.iter().all(|count| times.contains(*count)));
+        assert!(!outside.iter().any(|count| times.contains(*count)));
+    }
+
+    #[test]
+    fn lists_near_misses_closest_first() {
+        let received = [
+            Request::from("/posts"),
+            Request::from("/users").with_method("DELETE"),
+            Request::from("/users").with_method("POST"),
+        ]
+        .map(|request| Received {
+            request,
+            stub: None,
+            near_misses: Vec::new(),
+            response: Response::default(),
+            elapsed: Duration::ZERO,
+        });
+        let matchers = Matchers::new().method("POST").path("/users");
+
+        let error = verify(&received, &matchers, Times::exactly(2)).unwrap_err();
+
+        assert_eq!(error.actual, 1);
+        let near_misses = error
+            .near_misses
+            .iter()
+            .map(|(request, report)| (request.method.as_str(), request.path.as_str(), report.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(near_misses, [("DELETE", "/users", 1), ("GET", "/posts", 2)]);
+        assert!(verify(&received, &matchers, Times::once()).is_ok());
+    }
+}
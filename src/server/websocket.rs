@@ -0,0 +1,441 @@
+//! Scripted WebSocket exchanges (RFC 6455) for stubs that upgrade the connection.
+
+use crate::request::Request;
+use crate::response::Response;
+use regex::Regex;
+use sha1::{Digest, Sha1};
+use std::io::{self, Read, Write};
+use std::time::Duration;
+
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Frames above this size are refused rather than buffered.
+const MAX_FRAME: u64 = 16 * 1024 * 1024;
+/// Fragmented messages reassembling to more than this are refused with
+/// [`WebSocket::MESSAGE_TOO_BIG`].
+const MAX_MESSAGE: usize = 16 * 1024 * 1024;
+
+pub(super) const CONTINUATION: u8 = 0x0;
+pub(super) const TEXT: u8 = 0x1;
+pub(super) const BINARY: u8 = 0x2;
+pub(super) const CLOSE: u8 = 0x8;
+pub(super) const PING: u8 = 0x9;
+pub(super) const PONG: u8 = 0xA;
+
+/// A data message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WsMessage {
+    Text(String),
+    Binary(Vec<u8>),
+}
+
+/// What the next inbound message must look like.
+#[derive(Debug, Clone)]
+pub enum WsExpectation {
+    Any,
+    Text(String),
+    TextMatching(Regex),
+    Binary(Vec<u8>),
+}
+
+impl WsExpectation {
+    /// Describes why `message` falls short, or `None` when it meets the expectation.
+    fn check(&self, message: &WsMessage) -> Option<String> {
+        let met = match (self, message) {
+            (WsExpectation::Any, _) => true,
+            (WsExpectation::Text(expected), WsMessage::Text(text)) => expected == text,
+            (WsExpectation::TextMatching(pattern), WsMessage::Text(text)) => pattern.is_match(text),
+            (WsExpectation::Binary(expected), WsMessage::Binary(bytes)) => expected == bytes,
+            _ => false,
+        };
+        let expected = match self {
+            WsExpectation::Any => "any message".to_string(),
+            WsExpectation::Text(text) => format!("text {text:?}"),
+            WsExpectation::TextMatching(pattern) => format!("text matching `{pattern}`"),
+            WsExpectation::Binary(bytes) => format!("{} binary bytes", bytes.len()),
+        };
+        (!met).then(|| format!("expected {expected}"))
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Step {
+    Send(WsMessage),
+    Expect(WsExpectation),
+    Wait(Duration),
+    Close(u16, String),
+}
+
+/// The exchange a WebSocket stub plays once the connection is upgraded, step by step. Pings
+/// are answered throughout; a client closing ends the script, and so does an inbound
+/// message failing an expectation, with [`WebSocket::POLICY_VIOLATION`], or too large to
+/// buffer, with [`WebSocket::MESSAGE_TOO_BIG`].
+#[derive(Debug, Clone, Default)]
+pub struct WebSocket {
+    steps: Vec<Step>,
+}
+
+impl WebSocket {
+    /// Normal closure, sent when a script ends without an explicit close.
+    pub const NORMAL_CLOSURE: u16 = 1000;
+    /// Sent when an inbound message doesn't meet the script's expectation.
+    pub const POLICY_VIOLATION: u16 = 1008;
+    /// Sent when an inbound message is too large to buffer.
+    pub const MESSAGE_TOO_BIG: u16 = 1009;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn send_text<S: Into<String>>(self, text: S) -> Self {
+        self.step(Step::Send(WsMessage::Text(text.into())))
+    }
+
+    pub fn send_binary<B: Into<Vec<u8>>>(self, bytes: B) -> Self {
+        self.step(Step::Send(WsMessage::Binary(bytes.into())))
+    }
+
+    /// Waits for the next data message and checks it against `expectation`.
+    pub fn expect(self, expectation: WsExpectation) -> Self {
+        self.step(Step::Expect(expectation))
+    }
+
+    pub fn expect_text<S: Into<String>>(self, text: S) -> Self {
+        self.expect(WsExpectation::Text(text.into()))
+    }
+
+    pub fn wait(self, duration: Duration) -> Self {
+        self.step(Step::Wait(duration))
+    }
+
+    /// Closes the connection with `code` and `reason`, ending the script.
+    pub fn close<S: Into<String>>(self, code: u16, reason: S) -> Self {
+        self.step(Step::Close(code, reason.into()))
+    }
+
+    fn step(mut self, step: Step) -> Self {
+        self.steps.push(step);
+        self
+    }
+
+    /// Plays the script on an upgraded connection; `buffer` holds bytes already read past
+    /// the handshake.
+    pub(super) fn play(&self, stream: &mut (impl Read + Write), buffer: Vec<u8>) -> io::Result<()> {
+        let mut frames = Frames { buffer };
+        for step in &self.steps {
+            match step {
+                Step::Send(WsMessage::Text(text)) => {
+                    write_frame(stream, TEXT, text.as_bytes(), None)?
+                }
+                Step::Send(WsMessage::Binary(bytes)) => write_frame(stream, BINARY, bytes, None)?,
+                Step::Wait(duration) => std::thread::sleep(*duration),
+                Step::Close(code, reason) => return close(stream, *code, reason),
+                Step::Expect(expectation) => {
+                    let Some(message) = frames.next_message(stream)? else {
+                        return Ok(());
+                    };
+                    if let Some(violation) = expectation.check(&message) {
+                        return close(stream, Self::POLICY_VIOLATION, &violation);
+                    }
+                }
+            }
+        }
+        close(stream, Self::NORMAL_CLOSURE, "")
+    }
+}
+
+/// Whether `request` asks to upgrade to WebSocket.
+pub(super) fn is_upgrade(request: &Request) -> bool {
+    let has_token = |name: &str, token: &str| {
+        request.header_values(name).iter().any(|value| {
+            value
+                .split(',')
+                .any(|item| item.trim().eq_ignore_ascii_case(token))
+        })
+    };
+    has_token("Upgrade", "websocket")
+        && has_token("Connection", "upgrade")
+        && request.header("Sec-WebSocket-Key").is_some()
+}
+
+/// The `101 Switching Protocols` answer to an upgrade request.
+pub(super) fn handshake(request: &Request) -> Response {
+    let key = request
+        .header("Sec-WebSocket-Key")
+        .map_or("", |key| key.trim());
+    Response::new(101)
+        .with_header("Upgrade", "websocket")
+        .with_header("Connection", "Upgrade")
+        .with_header("Sec-WebSocket-Accept", accept_key(key))
+}
+
+fn accept_key(key: &str) -> String {
+    use base64::Engine;
+
+    let digest = Sha1::digest(format!("{key}{ACCEPT_GUID}").as_bytes());
+    base64::engine::general_purpose::STANDARD.encode(digest)
+}
+
+fn close(stream: &mut impl Write, code: u16, reason: &str) -> io::Result<()> {
+    let mut payload = code.to_be_bytes().to_vec();
+    // Control frame payloads are limited to 125 bytes.
+    let reason = reason
+        .char_indices()
+        .take_while(|(idx, ch)| idx + ch.len_utf8() <= 123);
+    payload.extend(reason.map(|(_, ch)| ch).collect::<String>().bytes());
+    write_frame(stream, CLOSE, &payload, None)
+}
+
+/// Writes a single final frame, masked with `mask` as clients must.
+pub(super) fn write_frame(
+    stream: &mut impl Write,
+    opcode: u8,
+    payload: &[u8],
+    mask: Option<[u8; 4]>,
+) -> io::Result<()> {
+    let mut frame = vec![0x80 | opcode];
+    let mask_bit = if mask.is_some() { 0x80 } else { 0 };
+    match payload.len() {
+        len @ 0..=125 => frame.push(mask_bit | len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(mask_bit | 126);
+            frame.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(mask_bit | 127);
+            frame.extend((len as u64).to_be_bytes());
+        }
+    }
+    match mask {
+        Some(mask) => {
+            frame.extend(mask);
+            frame.extend(
+                payload
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, byte)| byte ^ mask[idx % 4]),
+            );
+        }
+        None => frame.extend_from_slice(payload),
+    }
+    stream.write_all(&frame)?;
+    stream.flush()
+}
+
+/// A decoded frame.
+#[derive(Debug, PartialEq)]
+pub(super) struct Frame {
+    pub(super) fin: bool,
+    pub(super) opcode: u8,
+    pub(super) payload: Vec<u8>,
+}
+
+/// Reads frames from a stream, starting with bytes already buffered.
+pub(super) struct Frames {
+    pub(super) buffer: Vec<u8>,
+}
+
+impl Frames {
+    /// The next frame, or `None` once the peer closed the stream.
+    pub(super) fn next_frame(&mut self, stream: &mut impl Read) -> io::Result<Option<Frame>> {
+        loop {
+            if let Some((frame, length)) = parse_frame(&self.buffer)? {
+                self.buffer.drain(..length);
+                return Ok(Some(frame));
+            }
+            let mut chunk = [0; 8192];
+            match stream.read(&mut chunk)? {
+                0 => return Ok(None),
+                read => self.buffer.extend_from_slice(&chunk[..read]),
+            }
+        }
+    }
+
+    /// The next data message, reassembled from fragments, answering pings on the way.
+    /// `None` once the peer closed, after echoing its close frame.
+    fn next_message(&mut self, stream: &mut (impl Read + Write)) -> io::Result<Option<WsMessage>> {
+        let mut message: Option<(u8, Vec<u8>)> = None;
+        while let Some(frame) = self.next_frame(stream)? {
+            match frame.opcode {
+                PING => write_frame(stream, PONG, &frame.payload, None)?,
+                PONG => {}
+                CLOSE => {
+                    write_frame(
+                        stream,
+                        CLOSE,
+                        frame.payload.get(..2).unwrap_or_default(),
+                        None,
+                    )?;
+                    return Ok(None);
+                }
+                opcode => {
+                    let (kind, payload) = message.get_or_insert((opcode, Vec::new()));
+                    if opcode != CONTINUATION && opcode != *kind {
+                        return Err(invalid("interleaved data frames"));
+                    }
+                    if payload.len().saturating_add(frame.payload.len()) > MAX_MESSAGE {
+                        close(stream, WebSocket::MESSAGE_TOO_BIG, "message too large")?;
+                        return Err(invalid("message too large"));
+                    }
+                    payload.extend(frame.payload);
+                    if !frame.fin {
+                        continue;
+                    }
+                    let (kind, payload) = message.take().unwrap_or_default();
+                    return match kind {
+                        TEXT => String::from_utf8(payload)
+                            .map(|text| Some(WsMessage::Text(text)))
+                            .map_err(|_| invalid("text message isn't UTF-8")),
+                        BINARY => Ok(Some(WsMessage::Binary(payload))),
+                        _ => Err(invalid("unexpected opcode")),
+                    };
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Decodes the frame at the start of `bytes` with its length, or `None` when incomplete.
+fn parse_frame(bytes: &[u8]) -> io::Result<Option<(Frame, usize)>> {
+    let [first, second, ..] = *bytes else {
+        return Ok(None);
+    };
+    let (length, mut offset) = match second & 0x7F {
+        126 if bytes.len() >= 4 => (u16::from_be_bytes([bytes[2], bytes[3]]) as u64, 4),
+        127 if bytes.len() >= 10 => {
+            let mut length = [0; 8];
+            length.copy_from_slice(&bytes[2..10]);
+            (u64::from_be_bytes(length), 10)
+        }
+        126 | 127 => return Ok(None),
+        length => (length as u64, 2),
+    };
+    if length > MAX_FRAME {
+        return Err(invalid("frame too large"));
+    }
+    let mask = match second & 0x80 {
+        0 => None,
+        _ => {
+            let Some(mask) = bytes.get(offset..offset + 4) else {
+                return Ok(None);
+            };
+            offset += 4;
+            Some([mask[0], mask[1], mask[2], mask[3]])
+        }
+    };
+    let end = offset + length as usize;
+    let Some(payload) = bytes.get(offset..end) else {
+        return Ok(None);
+    };
+    let payload = match mask {
+        Some(mask) => payload
+            .iter()
+            .enumerate()
+            .map(|(idx, byte)| byte ^ mask[idx % 4])
+            .collect(),
+        None => payload.to_vec(),
+    };
+    let frame = Frame {
+        fin: first & 0x80 != 0,
+        opcode: first & 0x0F,
+        payload,
+    };
+    Ok(Some((frame, end)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn computes_accept_key() {
+        // The example from RFC 6455, section 1.3.
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[rstest]
+    #[case::short(5, None)]
+    #[case::masked(5, Some([1, 2, 3, 4]))]
+    #[case::medium(300, Some([9, 8, 7, 6]))]
+    #[case::long(70_000, None)]
+    fn round_trips_frames(#[case] length: usize, #[case] mask: Option<[u8; 4]>) {
+        let payload = (0..length).map(|idx| idx as u8).collect::<Vec<_>>();
+        let mut bytes = Vec::new();
+        write_frame(&mut bytes, BINARY, &payload, mask).unwrap();
+
+        let expected = Frame {
+            fin: true,
+            opcode: BINARY,
+            payload,
+        };
+        assert_eq!(parse_frame(&bytes).unwrap(), Some((expected, bytes.len())));
+        assert_eq!(parse_frame(&bytes[..bytes.len() - 1]).unwrap(), None);
+    }
+
+    /// Reads from `input` and collects what is written back.
+    struct Duplex<'a> {
+        input: &'a [u8],
+        output: Vec<u8>,
+    }
+
+    impl Read for Duplex<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            self.input.read(buf)
+        }
+    }
+
+    impl Write for Duplex<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.output.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn refuses_oversized_messages() {
+        let half = vec![b'x'; MAX_MESSAGE / 2 + 1];
+        let mut input = Vec::new();
+        write_frame(&mut input, BINARY, &half, None).unwrap();
+        input[0] &= 0x7F;
+        write_frame(&mut input, CONTINUATION, &half, None).unwrap();
+        let mut stream = Duplex {
+            input: &input,
+            output: Vec::new(),
+        };
+
+        let result = Frames { buffer: Vec::new() }.next_message(&mut stream);
+
+        assert_eq!(
+            result.map_err(|error| error.kind()),
+            Err(io::ErrorKind::InvalidData)
+        );
+        let (frame, _) = parse_frame(&stream.output).unwrap().unwrap();
+        assert_eq!(frame.opcode, CLOSE);
+        assert_eq!(frame.payload[..2], WebSocket::MESSAGE_TOO_BIG.to_be_bytes());
+    }
+
+    #[rstest]
+    #[case::any(WsExpectation::Any, WsMessage::Binary(vec![1]), None)]
+    #[case::text(WsExpectation::Text("hi".into()), WsMessage::Text("hi".into()), None)]
+    #[case::other_text(WsExpectation::Text("hi".into()), WsMessage::Text("ho".into()), Some(r#"expected text "hi""#))]
+    #[case::pattern(WsExpectation::TextMatching(Regex::new("^sub:").unwrap()), WsMessage::Binary(vec![]), Some("expected text matching `^sub:`"))]
+    fn checks_expectations(
+        #[case] expectation: WsExpectation,
+        #[case] message: WsMessage,
+        #[case] violation: Option<&str>,
+    ) {
+        assert_eq!(expectation.check(&message).as_deref(), violation);
+    }
+}
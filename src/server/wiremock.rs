@@ -0,0 +1,362 @@
+//! Reads WireMock stub mappings, so existing mapping files work unchanged.
+
+use super::{Delay, Scenario, Stub};
+use crate::matchers::{Matcher, Matchers};
+use crate::request::Request;
+use crate::response::Response;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::time::Duration;
+
+/// Whether `value` looks like a WireMock mapping file rather than a [`Mapping`](super::Mapping).
+pub(super) fn is_wiremock(value: &Value) -> bool {
+    value.get("mappings").is_some_and(Value::is_array)
+        || value.get("request").is_some_and(Value::is_object)
+}
+
+/// The stubs of a single WireMock mapping or a `{"mappings": [...]}` file.
+pub(super) fn stubs(value: &Value) -> Result<Vec<Stub>, String> {
+    match value.get("mappings").and_then(Value::as_array) {
+        Some(mappings) => mappings
+            .iter()
+            .enumerate()
+            .map(|(idx, mapping)| stub(mapping).map_err(|error| format!("mapping {idx}: {error}")))
+            .collect(),
+        None => stub(value).map(|stub| vec![stub]),
+    }
+}
+
+/// The priority WireMock gives mappings that don't set one.
+const DEFAULT_PRIORITY: i64 = 5;
+
+fn stub(mapping: &Value) -> Result<Stub, String> {
+    let request = mapping.get("request").ok_or("missing `request`")?;
+    let response = mapping.get("response").unwrap_or(&Value::Null);
+    let templated = response
+        .get("transformers")
+        .and_then(Value::as_array)
+        .is_some_and(|transformers| transformers.iter().any(|t| t == "response-template"));
+
+    let mut stub = Stub::new(matchers(request)?, self::response(response, templated)?);
+    stub.templated = templated;
+    // WireMock ranks 1 highest and leaves unprioritized mappings at 5, which maps to 0 here.
+    if let Some(priority) = mapping.get("priority").and_then(Value::as_i64) {
+        let priority = i32::try_from(DEFAULT_PRIORITY.saturating_sub(priority))
+            .unwrap_or(if priority < 0 { i32::MAX } else { i32::MIN });
+        stub.matchers.set_priority(priority);
+    }
+    if let Some(name) = mapping.get("scenarioName").and_then(Value::as_str) {
+        let mut scenario = Scenario::new(name);
+        if let Some(state) = mapping.get("requiredScenarioState").and_then(Value::as_str) {
+            scenario = scenario.when(state);
+        }
+        if let Some(state) = mapping.get("newScenarioState").and_then(Value::as_str) {
+            scenario = scenario.then(state);
+        }
+        stub = stub.in_scenario(scenario);
+    }
+    if let Some(delay) = response
+        .get("fixedDelayMilliseconds")
+        .and_then(Value::as_u64)
+    {
+        stub = stub.with_delay(Delay::fixed(Duration::from_millis(delay)));
+    }
+    Ok(stub)
+}
+
+fn matchers(request: &Value) -> Result<Matchers, String> {
+    let mut matchers = Matchers::new();
+    match request.get("method").and_then(Value::as_str) {
+        None | Some("ANY") => {}
+        Some(method) => matchers = matchers.method(method),
+    }
+
+    let url = |key: &str| request.get(key).and_then(Value::as_str);
+    if let Some(url) = url("url") {
+        let url = Request::from(url);
+        matchers = matchers.path(url.path);
+        for (name, values) in url.query {
+            for value in values {
+                matchers = matchers.query_eq(name.clone(), value.unwrap_or_default());
+            }
+        }
+    } else if let Some(path) = url("urlPath") {
+        matchers = matchers.path(path);
+    } else if let Some(template) = url("urlPathTemplate") {
+        matchers = matchers.path_template(template);
+    } else if let Some(pattern) = url("urlPathPattern") {
+        matchers = matchers.path_regex(format!("^(?:{pattern})$"));
+    } else if let Some(pattern) = url("urlPattern") {
+        // Matchers see the path alone, so patterns spanning the query can't be honoured.
+        if pattern.contains(r"\?") {
+            return Err("`urlPattern` with a query is unsupported".into());
+        }
+        matchers = matchers.path_regex(format!("^(?:{pattern})$"));
+    }
+
+    for (key, kind) in [
+        ("headers", Part::Header),
+        ("queryParameters", Part::Query),
+        ("cookies", Part::Cookie),
+    ] {
+        for (name, pattern) in object(request, key) {
+            matchers = matchers.with(kind.matcher(name, pattern)?);
+        }
+    }
+    if let Some(credentials) = request.get("basicAuthCredentials") {
+        let field = |key: &str| {
+            credentials
+                .get(key)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        };
+        matchers = matchers.basic_auth(field("username"), field("password"));
+    }
+    for pattern in request
+        .get("bodyPatterns")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        matchers = matchers.with(body_matcher(pattern)?);
+    }
+    Ok(matchers)
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Part {
+    Header,
+    Query,
+    Cookie,
+}
+
+impl Part {
+    fn matcher(self, name: &str, pattern: &Value) -> Result<Matcher, String> {
+        let name = name.to_string();
+        if pattern.get("absent").and_then(Value::as_bool) == Some(true) {
+            return Ok(match self {
+                Part::Header => Matcher::HeaderMiss(name),
+                Part::Query => Matcher::QueryMiss(name),
+                Part::Cookie => Matcher::CookieMiss(name),
+            });
+        }
+        match pattern.get("equalTo").and_then(Value::as_str) {
+            Some(value) => Ok(match self {
+                Part::Header => Matcher::HeaderEq(name, value.into()),
+                Part::Query => Matcher::QueryEq(name, value.into()),
+                Part::Cookie => Matcher::CookieEq(name, value.into()),
+            }),
+            None => Err(format!("unsupported pattern {pattern} for `{name}`")),
+        }
+    }
+}
+
+fn body_matcher(pattern: &Value) -> Result<Matcher, String> {
+    let text = |key: &str| pattern.get(key).and_then(Value::as_str).map(String::from);
+    if let Some(body) = text("equalTo") {
+        return Ok(Matcher::BodyEq(body));
+    }
+    if let Some(needle) = text("contains") {
+        return Ok(Matcher::BodyContains(needle));
+    }
+    if let Some(regex) = text("matches") {
+        return Ok(Matcher::BodyRegex(regex));
+    }
+    #[cfg(feature = "json")]
+    if let Some(json) = pattern.get("equalToJson") {
+        // WireMock accepts the expected document inline or as a string.
+        let json = match json.as_str() {
+            Some(text) => serde_json::from_str(text).map_err(|error| error.to_string())?,
+            None => json.clone(),
+        };
+        let lenient = ["ignoreExtraElements", "ignoreArrayOrder"]
+            .iter()
+            .any(|flag| pattern.get(flag).and_then(Value::as_bool) == Some(true));
+        return Ok(match lenient {
+            true => Matcher::BodyJsonIncludes(json),
+            false => Matcher::BodyJsonEq(json),
+        });
+    }
+    Err(format!("unsupported body pattern {pattern}"))
+}
+
+fn response(response: &Value, templated: bool) -> Result<Response, String> {
+    let status = response
+        .get("status")
+        .and_then(Value::as_u64)
+        .unwrap_or(200);
+    let status = u16::try_from(status).map_err(|_| format!("invalid status {status}"))?;
+    let mut built = Response::new(status);
+    if let Some(reason) = response.get("statusMessage").and_then(Value::as_str) {
+        built = built.with_reason(reason);
+    }
+    for (name, values) in object(response, "headers") {
+        match values {
+            Value::Array(values) => {
+                for value in values {
+                    built.append_header(name.clone(), text(value));
+                }
+            }
+            value => built.append_header(name.clone(), text(value)),
+        }
+    }
+    if let Some(body) = response.get("body").and_then(Value::as_str) {
+        built.body = match templated {
+            true => handlebars_to_template(body),
+            false => body.to_string(),
+        }
+        .into();
+    } else if let Some(json) = response.get("jsonBody") {
+        built.body = json.to_string().into();
+        if built.header("Content-Type").is_none() {
+            built.set_header("Content-Type", "application/json");
+        }
+    } else if let Some(encoded) = response.get("base64Body").and_then(Value::as_str) {
+        built.body = BASE64
+            .decode(encoded)
+            .map_err(|error| format!("invalid `base64Body`: {error}"))?
+            .into();
+    } else if response.get("bodyFileName").is_some() {
+        return Err("`bodyFileName` is unsupported".into());
+    }
+    Ok(built)
+}
+
+/// Rewrites WireMock's `{{request.*}}` helpers into this crate's placeholder names.
+fn handlebars_to_template(body: &str) -> String {
+    let helpers =
+        Regex::new(r"\{\{\s*request\.(method|url|body|path|query|headers)(\.[^}\s]+)?\s*\}\}")
+            .expect("valid regex");
+    helpers
+        .replace_all(body, |captures: &regex::Captures| {
+            let name = match &captures[1] {
+                "headers" => "header",
+                other => other,
+            };
+            let rest = captures.get(2).map_or("", |rest| rest.as_str());
+            format!("{{{{{name}{rest}}}}}")
+        })
+        .into_owned()
+}
+
+fn object<'a>(value: &'a Value, key: &str) -> impl Iterator<Item = (&'a String, &'a Value)> {
+    value
+        .get(key)
+        .and_then(Value::as_object)
+        .map(Map::iter)
+        .into_iter()
+        .flatten()
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+    use serde_json::json;
+
+    #[test]
+    fn converts_mapping() {
+        let stubs = stubs(&json!({
+            "priority": 1,
+            "scenarioName": "checkout",
+            "requiredScenarioState": "Started",
+            "request": {
+                "method": "POST",
+                "url": "/orders?draft=true",
+                "headers": {"X-Tenant": {"equalTo": "acme"}, "X-Debug": {"absent": true}},
+                "bodyPatterns": [{"contains": "sku"}]
+            },
+            "response": {
+                "status": 201,
+                "headers": {"Location": "/orders/1"},
+                "body": "created",
+                "fixedDelayMilliseconds": 10
+            }
+        }))
+        .unwrap();
+
+        let stub = &stubs[0];
+        let request = Request::from("/orders?draft=true")
+            .with_method("POST")
+            .with_header("X-Tenant", "acme")
+            .with_body(r#"{"sku":"a"}"#);
+        assert!(stub.matchers.is_matched(&request));
+        assert!(
+            !stub
+                .matchers
+                .is_matched(&request.clone().with_header("X-Debug", "1"))
+        );
+        assert_eq!(stub.matchers.priority(), 4);
+        assert_eq!(stub.scenario.as_ref().map(Scenario::name), Some("checkout"));
+        assert_eq!(stub.delay, Some(Delay::fixed(Duration::from_millis(10))));
+        assert_eq!(
+            stub.response,
+            Response::new(201)
+                .with_header("Location", "/orders/1")
+                .with_body("created")
+        );
+    }
+
+    #[rstest]
+    #[case::path(json!({"urlPath": "/users"}), "/users?page=2", true)]
+    #[case::url(json!({"url": "/users?page=2"}), "/users?page=3", false)]
+    #[case::path_pattern(json!({"urlPathPattern": "/users/[0-9]+"}), "/users/42/posts", false)]
+    #[case::template(json!({"urlPathTemplate": "/users/{id}"}), "/users/42", true)]
+    #[case::any_method(json!({"method": "ANY", "urlPath": "/"}), "/", true)]
+    fn matches_urls(#[case] request: Value, #[case] url: &str, #[case] matched: bool) {
+        assert_eq!(
+            matchers(&request).unwrap().is_matched(&Request::from(url)),
+            matched
+        );
+    }
+
+    #[test]
+    fn prioritized_mapping_beats_unprioritized_catch_all() {
+        let stubs = stubs(&json!({"mappings": [
+            {"request": {"urlPattern": "/.*"}, "response": {"status": 500}},
+            {"priority": 1, "request": {"urlPattern": "/x.*"}, "response": {"status": 201}}
+        ]}))
+        .unwrap();
+        let mut router = crate::router::Router::new();
+        for stub in &stubs {
+            router.add(stub.response.status, stub.matchers.clone());
+        }
+
+        assert_eq!(router.route(&Request::from("/xyz")), Ok(&201));
+    }
+
+    #[rstest]
+    #[case::last(json!({"priority": 9}), -4)]
+    #[case::huge(json!({"priority": i64::MAX}), i32::MIN)]
+    #[case::negative(json!({"priority": i64::MIN}), i32::MAX)]
+    fn bounds_priorities(#[case] priority: Value, #[case] expected: i32) {
+        let mut mapping = priority;
+        mapping["request"] = json!({});
+        assert_eq!(stubs(&mapping).unwrap()[0].matchers.priority(), expected);
+    }
+
+    #[rstest]
+    #[case::header_regex(json!({"request": {"headers": {"Accept": {"matches": ".*"}}}}))]
+    #[case::body_file(json!({"request": {}, "response": {"bodyFileName": "a.json"}}))]
+    #[case::no_request(json!({"mappings": [{"response": {}}]}))]
+    fn rejects_unsupported(#[case] mapping: Value) {
+        assert!(stubs(&mapping).is_err());
+    }
+
+    #[test]
+    fn translates_response_templates() {
+        assert_eq!(
+            handlebars_to_template("{{request.path.id}} {{ request.headers.X-Id }} {{now}}"),
+            "{{path.id}} {{header.X-Id}} {{now}}"
+        );
+    }
+}